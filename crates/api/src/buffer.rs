@@ -1,4 +1,5 @@
-use core::ops::RangeBounds;
+use core::ops::{Bound, RangeBounds};
+use std::ffi::CStr;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::result::Result as StdResult;
@@ -17,8 +18,9 @@ use types::{
 
 use crate::choose;
 use crate::ffi::buffer::*;
+use crate::ffi::helpers::{find_buffer_by_handle, ml_get_buf};
 use crate::opts::*;
-use crate::types::{KeymapInfos, Mode};
+use crate::types::{KeymapInfos, Mode, Position, Range};
 use crate::utils;
 use crate::SuperIterator;
 use crate::LUA_INTERNAL_CALL;
@@ -309,6 +311,75 @@ impl Buffer {
         )
     }
 
+    /// Calls `fun` with the lines in the given range, without converting each
+    /// of them into a [`nvim::String`].
+    ///
+    /// The lines are read directly from Neovim's memory and copied into a
+    /// single buffer, so the number of allocations doesn't depend on the
+    /// number of lines. Indexing is zero-based, end-exclusive, and NUL bytes
+    /// are represented as newlines like in Neovim's internal storage.
+    ///
+    /// Returns an error if the range is out of bounds.
+    pub fn with_lines<R, F, T>(&self, line_range: R, fun: F) -> Result<T>
+    where
+        R: RangeBounds<usize>,
+        F: FnOnce(&[&[u8]]) -> T,
+    {
+        let line_count = self.line_count()?;
+
+        let start = match line_range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+
+        let end = match line_range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => line_count,
+        };
+
+        if start > end || end > line_count {
+            return Err(Error::custom("Index out of bounds"));
+        }
+
+        let mut err = nvim::Error::new();
+        let buf = unsafe { find_buffer_by_handle(self.0, &mut err) };
+        if err.is_err() {
+            return Err(err.into());
+        }
+
+        let mut bytes = Vec::new();
+
+        // The offsets in `bytes` where each line ends.
+        let ends = (start + 1..end + 1)
+            .map(|lnum| {
+                // The pointer returned by `ml_get_buf` is only valid until
+                // the next call, so the line has to be copied right away.
+                let line = unsafe {
+                    #[cfg(not(feature = "neovim-nightly"))]
+                    let ptr = ml_get_buf(buf, lnum as i32, false);
+                    #[cfg(feature = "neovim-nightly")]
+                    let ptr = ml_get_buf(buf, lnum as i32);
+                    CStr::from_ptr(ptr)
+                };
+                bytes.extend_from_slice(line.to_bytes());
+                bytes.len()
+            })
+            .collect::<Vec<_>>();
+
+        let lines = ends
+            .iter()
+            .scan(0, |line_start, &line_end| {
+                let line = &bytes[*line_start..line_end];
+                *line_start = line_end;
+                Some(line)
+            })
+            .collect::<Vec<_>>();
+
+        Ok(fun(&lines))
+    }
+
     /// Binding to [`nvim_buf_get_mark()`][1].
     ///
     /// Returns a (1-0) indexed `(row, col)` tuple representing the position
@@ -335,6 +406,14 @@ impl Buffer {
         })
     }
 
+    /// Same as [`get_mark`](Buffer::get_mark), but returns the position of
+    /// the mark as a 0-indexed [`Position`], or `None` if the mark is not
+    /// set.
+    pub fn get_mark_position(&self, name: char) -> Result<Option<Position>> {
+        let (row, col) = self.get_mark(name)?;
+        Ok(Position::from_one_indexed_row(row, col))
+    }
+
     /// Binding to [`nvim_buf_get_name()`][1].
     ///
     /// Returns the full filepath of the buffer.
@@ -415,6 +494,22 @@ impl Buffer {
         )
     }
 
+    /// Same as [`get_text`](Buffer::get_text), but takes the text to get as a
+    /// [`Range`].
+    pub fn get_text_range(
+        &self,
+        range: Range,
+        opts: &GetTextOpts,
+    ) -> Result<impl SuperIterator<nvim::String>> {
+        // `nvim_buf_get_text` takes an inclusive end row.
+        self.get_text(
+            range.start.row..range.end.row,
+            range.start.col,
+            range.end.col,
+            opts,
+        )
+    }
+
     /// Binding to [`nvim_buf_get_var()`][1].
     ///
     /// Gets a buffer-scoped (`b:`) variable.
@@ -534,6 +629,32 @@ impl Buffer {
         choose!(err, ())
     }
 
+    /// Same as [`set_lines`](Buffer::set_lines), but takes the replacement as
+    /// a single byte slice which is split on newlines. A single trailing
+    /// newline is ignored.
+    ///
+    /// The lines are passed to Neovim without being copied into separate
+    /// [`nvim::String`]s first.
+    pub fn set_lines_from_bytes<R>(
+        &mut self,
+        line_range: R,
+        strict_indexing: bool,
+        bytes: &[u8],
+    ) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+
+        let lines = bytes.split(|&b| b == b'\n').map(|line| {
+            // SAFETY: `nvim_buf_set_lines` copies the lines before returning,
+            // and doesn't require them to be null-terminated.
+            unsafe { nvim::String::from_raw_parts(line.as_ptr(), line.len()) }
+        });
+
+        self.set_lines(line_range, strict_indexing, lines)
+    }
+
     /// Binding to [`nvim_buf_set_mark()`][1].
     ///
     /// Sets a named mark in the buffer. Marks are (1,0)-indexed, and passing 0
@@ -573,6 +694,17 @@ impl Buffer {
         )
     }
 
+    /// Same as [`set_mark`](Buffer::set_mark), but takes the position of the
+    /// mark as a 0-indexed [`Position`].
+    pub fn set_mark_position(
+        &mut self,
+        name: char,
+        pos: Position,
+        opts: &SetMarkOpts,
+    ) -> Result<()> {
+        self.set_mark(name, pos.one_indexed_row(), pos.col, opts)
+    }
+
     /// Binding to [`nvim_buf_set_name()`][1].
     ///
     /// Sets the full file name for a buffer.
@@ -626,6 +758,26 @@ impl Buffer {
         choose!(err, ())
     }
 
+    /// Same as [`set_text`](Buffer::set_text), but takes the replaced text as
+    /// a [`Range`].
+    pub fn set_text_range<Line, Lines>(
+        &mut self,
+        range: Range,
+        replacement: Lines,
+    ) -> Result<()>
+    where
+        Lines: IntoIterator<Item = Line>,
+        Line: Into<nvim::String>,
+    {
+        // `nvim_buf_set_text` takes an inclusive end row.
+        self.set_text(
+            range.start.row..range.end.row,
+            range.start.col,
+            range.end.col,
+            replacement,
+        )
+    }
+
     /// Binding to [`nvim_buf_set_var()`][1].
     ///
     /// Sets a buffer-scoped (`b:`) variable.
@@ -0,0 +1,316 @@
+use types::{self as nvim};
+
+use crate::types::{Position, Range};
+use crate::{Buffer, Error, Result, Window};
+
+/// A replacement of a range of text in a buffer, like an LSP `TextEdit`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct TextEdit {
+    /// The range of text to replace.
+    pub range: Range,
+
+    /// The new text, with lines separated by `\n`.
+    pub new_text: String,
+}
+
+impl TextEdit {
+    /// Creates a new edit replacing `range` with `new_text`.
+    #[inline]
+    pub fn new(range: Range, new_text: impl Into<String>) -> Self {
+        Self { range, new_text: new_text.into() }
+    }
+
+    /// Returns the position of the end of the new text once the edit has
+    /// been applied.
+    fn new_end(&self) -> Position {
+        let start = self.range.start;
+        let mut lines = self.new_text.split('\n');
+        let first = lines.next().unwrap_or_default();
+        match lines.enumerate().last() {
+            Some((idx, last)) => {
+                Position::new(start.row + idx + 1, last.len())
+            },
+            None => Position::new(start.row, start.col + first.len()),
+        }
+    }
+}
+
+/// The edits collected by [`Buffer::edit`].
+///
+/// The positions of all the edits refer to the contents of the buffer before
+/// the transaction, like the edits of an LSP `WorkspaceEdit`, so they don't
+/// have to be adjusted for the changes made by the other edits.
+#[derive(Debug, Default)]
+pub struct EditTransaction {
+    edits: Vec<TextEdit>,
+}
+
+impl EditTransaction {
+    /// Replaces the text in `range` with `new_text`.
+    pub fn replace(
+        &mut self,
+        range: Range,
+        new_text: impl Into<String>,
+    ) -> &mut Self {
+        self.push(TextEdit::new(range, new_text))
+    }
+
+    /// Inserts `text` at `pos`.
+    pub fn insert(
+        &mut self,
+        pos: Position,
+        text: impl Into<String>,
+    ) -> &mut Self {
+        self.push(TextEdit::new(Range::empty(pos), text))
+    }
+
+    /// Deletes the text in `range`.
+    pub fn delete(&mut self, range: Range) -> &mut Self {
+        self.push(TextEdit::new(range, String::new()))
+    }
+
+    /// Adds an edit to the transaction.
+    pub fn push(&mut self, edit: TextEdit) -> &mut Self {
+        self.edits.push(edit);
+        self
+    }
+
+    /// Returns the number of edits in the transaction.
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Returns whether the transaction doesn't contain any edits.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Sorts the edits by position, checking that they don't overlap.
+    fn into_sorted(mut self) -> Result<Vec<TextEdit>> {
+        if let Some(edit) =
+            self.edits.iter().find(|edit| edit.range.start > edit.range.end)
+        {
+            return Err(Error::custom(format!(
+                "invalid edit range: {:?}",
+                edit.range
+            )));
+        }
+
+        // The sort is stable, so insertions at the same position keep the
+        // order in which they were added.
+        self.edits.sort_by_key(|edit| edit.range.start);
+
+        for pair in self.edits.windows(2) {
+            if pair[0].range.end > pair[1].range.start {
+                return Err(Error::custom(format!(
+                    "overlapping edits: {:?} and {:?}",
+                    pair[0].range, pair[1].range
+                )));
+            }
+        }
+
+        Ok(self.edits)
+    }
+}
+
+impl Buffer {
+    /// Applies a sequence of text edits to the buffer as a single undo
+    /// block.
+    ///
+    /// The edits are collected by `fun` and applied once it returns, from
+    /// the last one to the first one so that their positions, which all
+    /// refer to the buffer before the transaction, stay valid. Edits can't
+    /// overlap.
+    ///
+    /// If applying one of the edits fails, the ones already applied are
+    /// reverted and the error is returned. Nothing is applied if `fun`
+    /// returns an error.
+    ///
+    /// Extmarks are moved by Neovim as the text changes, and the cursors of
+    /// the windows displaying the buffer are moved to follow the text they
+    /// were on.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use nvim_oxi::api::types::{Position, Range};
+    ///
+    /// buffer.edit(|tx| {
+    ///     tx.insert(Position::new(0, 0), "// ");
+    ///     let foo = Range::new(Position::new(3, 4), Position::new(3, 7));
+    ///     tx.replace(foo, "bar");
+    ///     Ok(())
+    /// })?;
+    /// ```
+    pub fn edit<F, R>(&mut self, fun: F) -> Result<R>
+    where
+        F: FnOnce(&mut EditTransaction) -> Result<R>,
+    {
+        let mut transaction = EditTransaction::default();
+        let ret = fun(&mut transaction)?;
+        let edits = transaction.into_sorted()?;
+
+        if edits.is_empty() {
+            return Ok(ret);
+        }
+
+        let cursors = crate::list_wins()
+            .filter(|win| win.get_buf().is_ok_and(|buf| &buf == self))
+            .map(|win| win.get_cursor_position().map(|pos| (win, pos)))
+            .collect::<Result<Vec<(Window, Position)>>>()?;
+
+        let buffer = self.clone();
+        let applied = edits.clone();
+
+        // Undo blocks are per-buffer, so the edits are applied with the buffer
+        // as the current one for `:undojoin` to work.
+        self.call(move |()| apply_edits(buffer, &applied))?;
+
+        for (mut win, pos) in cursors {
+            win.set_cursor_position(adjust_position(pos, &edits))?;
+        }
+
+        Ok(ret)
+    }
+}
+
+/// Applies sorted edits from the last to the first one, reverting them if
+/// any of them fails.
+fn apply_edits(mut buffer: Buffer, edits: &[TextEdit]) -> Result<()> {
+    // The ranges of the edits that have been applied, together with the
+    // text they replaced.
+    let mut applied: Vec<(Range, Vec<nvim::String>)> = Vec::new();
+
+    for edit in edits.iter().rev() {
+        let result = buffer
+            .get_text_range(edit.range, &Default::default())
+            .map(Iterator::collect::<Vec<_>>)
+            .and_then(|old_text| {
+                if !applied.is_empty() {
+                    undojoin();
+                }
+                let lines = edit.new_text.split('\n');
+                buffer.set_text_range(edit.range, lines)?;
+                Ok(old_text)
+            });
+
+        match result {
+            Ok(old_text) => {
+                let new_range = Range::new(edit.range.start, edit.new_end());
+                applied.push((new_range, old_text));
+            },
+
+            Err(err) => {
+                // Revert the edits in the opposite order in which they were
+                // applied.
+                for (range, old_text) in applied.into_iter().rev() {
+                    undojoin();
+                    let _ = buffer.set_text_range(range, old_text);
+                }
+                return Err(err);
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Joins the next change with the previous undo block.
+fn undojoin() {
+    // This fails if the user just undid a change, in which case the change
+    // gets its own undo block.
+    let _ = crate::command("silent! undojoin");
+}
+
+/// Moves a position to follow the text it was on after a sequence of sorted
+/// edits is applied. Positions inside a replaced range are moved to its
+/// start.
+fn adjust_position(mut pos: Position, edits: &[TextEdit]) -> Position {
+    for edit in edits.iter().rev() {
+        let Range { start, end } = edit.range;
+
+        if pos < start {
+            continue;
+        }
+
+        if pos < end {
+            pos = start;
+            continue;
+        }
+
+        let new_end = edit.new_end();
+
+        pos = if pos.row == end.row {
+            Position::new(new_end.row, new_end.col + (pos.col - end.col))
+        } else {
+            Position::new(pos.row + new_end.row - end.row, pos.col)
+        };
+    }
+
+    pos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: (usize, usize), end: (usize, usize)) -> Range {
+        Range::new(start.into(), end.into())
+    }
+
+    #[test]
+    fn new_end() {
+        let edit = TextEdit::new(range((1, 2), (1, 2)), "foo");
+        assert_eq!(edit.new_end(), Position::new(1, 5));
+
+        let edit = TextEdit::new(range((1, 2), (3, 0)), "foo\nba");
+        assert_eq!(edit.new_end(), Position::new(2, 2));
+
+        let edit = TextEdit::new(range((1, 2), (3, 0)), "\n");
+        assert_eq!(edit.new_end(), Position::new(2, 0));
+    }
+
+    #[test]
+    fn overlapping_edits() {
+        let mut tx = EditTransaction::default();
+        tx.replace(range((0, 0), (1, 3)), "");
+        tx.insert(Position::new(1, 1), "x");
+        assert!(tx.into_sorted().is_err());
+
+        let mut tx = EditTransaction::default();
+        tx.insert(Position::new(1, 3), "b");
+        tx.replace(range((0, 0), (1, 3)), "");
+        tx.insert(Position::new(1, 3), "c");
+        let edits = tx.into_sorted().unwrap();
+        assert_eq!(edits[0].new_text, "");
+        assert_eq!(edits[1].new_text, "b");
+        assert_eq!(edits[2].new_text, "c");
+    }
+
+    #[test]
+    fn adjust_positions() {
+        let edits = [
+            // "foo" -> "f\nXoo" on row 0.
+            TextEdit::new(range((0, 1), (0, 1)), "\nX"),
+            // Delete rows 2 and 3.
+            TextEdit::new(range((2, 0), (4, 0)), ""),
+            // Replace "abc" with "d" on row 5.
+            TextEdit::new(range((5, 1), (5, 4)), "d"),
+        ];
+
+        // Before every edit.
+        assert_eq!(adjust_position((0, 0).into(), &edits), (0, 0).into());
+        // After the insertion, on the same row.
+        assert_eq!(adjust_position((0, 2).into(), &edits), (1, 2).into());
+        // Between the first and second edit.
+        assert_eq!(adjust_position((1, 4).into(), &edits), (2, 4).into());
+        // Inside the deleted rows.
+        assert_eq!(adjust_position((3, 1).into(), &edits), (3, 0).into());
+        // After the deletion.
+        assert_eq!(adjust_position((4, 2).into(), &edits), (3, 2).into());
+        // Inside the replacement.
+        assert_eq!(adjust_position((5, 2).into(), &edits), (4, 1).into());
+        // After the replacement, on the same row.
+        assert_eq!(adjust_position((5, 6).into(), &edits), (4, 4).into());
+    }
+}
@@ -0,0 +1,759 @@
+use core::cell::{Ref, RefCell};
+use core::cmp::Ordering;
+use core::ops::Range;
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+
+use crate::opts::{BufAttachOpts, OnBytesArgs};
+use crate::types::BytesChange;
+use crate::{Buffer, Result};
+
+/// Maximum number of changes kept around for
+/// [`changes_since`](BufferMirror::changes_since).
+const HISTORY_CAPACITY: usize = 1024;
+
+/// Maximum number of lines stored in a single chunk of a [`Rope`].
+const MAX_CHUNK_LINES: usize = 256;
+
+/// Chunks with fewer lines than this are merged with one of their
+/// neighbours when they're edited.
+const MIN_CHUNK_LINES: usize = MAX_CHUNK_LINES / 4;
+
+/// A copy of the contents of a [`Buffer`] kept on the Rust side and updated
+/// incrementally as the buffer changes.
+///
+/// The mirror attaches to the buffer with [`Buffer::attach`] and applies the
+/// edits received by the `on_bytes` callback, so reading from it never goes
+/// through the Neovim API. The whole buffer is re-read when it's reloaded,
+/// or if an edit can't be applied.
+///
+/// Dropping the mirror doesn't detach it right away: it detaches the next
+/// time the buffer changes, when `on_bytes` fires.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::api::{self, BufferMirror};
+///
+/// let mirror = BufferMirror::attach(&api::get_current_buf())?;
+///
+/// let tick = mirror.changedtick();
+///
+/// // ..later, after some edits.
+/// if let Some(changes) = mirror.changes_since(tick) {
+///     for change in changes {
+///         let rows = change.new_rows.clone();
+///         for row in rows {
+///             let line = mirror.line(row);
+///         }
+///     }
+/// }
+/// ```
+pub struct BufferMirror {
+    state: Rc<RefCell<MirrorState>>,
+    listeners: Rc<RefCell<Vec<OnChange>>>,
+}
+
+type OnChange = Box<dyn FnMut(&BytesChange, &[u8]) -> Result<()> + 'static>;
+
+/// A range of the buffer that was replaced by an edit, as recorded by a
+/// [`BufferMirror`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ChangedRange {
+    /// The value of `b:changedtick` after the edit.
+    pub changedtick: u32,
+
+    /// The byte range of the replaced text, before the edit.
+    pub old_bytes: Range<usize>,
+
+    /// The byte range of the new text, after the edit.
+    pub new_bytes: Range<usize>,
+
+    /// The rows spanned by the replaced text, before the edit.
+    pub old_rows: Range<usize>,
+
+    /// The rows spanned by the new text, after the edit.
+    pub new_rows: Range<usize>,
+}
+
+struct MirrorState {
+    buffer: Buffer,
+    rope: Rope,
+    changedtick: u32,
+    history: VecDeque<ChangedRange>,
+
+    /// Changes made after this tick are all in the history.
+    history_start: u32,
+
+    is_attached: bool,
+}
+
+impl BufferMirror {
+    /// Creates a new mirror of the given buffer, reading its current contents
+    /// and attaching to it.
+    pub fn attach(buffer: &Buffer) -> Result<Self> {
+        let state = Rc::new(RefCell::new(MirrorState::new(buffer.clone())?));
+
+        let listeners = Rc::new(RefCell::new(Vec::<OnChange>::new()));
+
+        let on_bytes = {
+            let state = Rc::downgrade(&state);
+            let listeners = Rc::downgrade(&listeners);
+            move |args: OnBytesArgs| {
+                let (Some(state), Some(listeners)) =
+                    (state.upgrade(), listeners.upgrade())
+                else {
+                    return Ok(true);
+                };
+
+                let change = BytesChange::from(args);
+
+                let read_old_text = !listeners.borrow().is_empty();
+
+                // The state is borrowed only while applying the change so
+                // that the listeners can read from the mirror.
+                let old_text =
+                    state.borrow_mut().on_bytes(&change, read_old_text)?;
+
+                let Some(old_text) = old_text else { return Ok(false) };
+
+                // The listeners are taken out while they run so that they
+                // can register new ones.
+                let mut running = std::mem::take(&mut *listeners.borrow_mut());
+                let res = running
+                    .iter_mut()
+                    .try_for_each(|listener| listener(&change, &old_text));
+
+                let mut listeners = listeners.borrow_mut();
+                running.append(&mut listeners);
+                *listeners = running;
+
+                res.map(|()| false)
+            }
+        };
+
+        let on_changedtick = {
+            let state = Rc::downgrade(&state);
+            move |(_, _, tick): (String, Buffer, u32)| {
+                with_state(&state, |state| {
+                    state.changedtick = tick;
+                    Ok(())
+                })
+            }
+        };
+
+        let on_reload = {
+            let state = Rc::downgrade(&state);
+            move |_| with_state(&state, MirrorState::resync)
+        };
+
+        let on_detach = {
+            let state = Rc::downgrade(&state);
+            move |_| {
+                with_state(&state, |state| {
+                    state.is_attached = false;
+                    Ok(())
+                })
+            }
+        };
+
+        let opts = BufAttachOpts::builder()
+            .on_bytes(on_bytes)
+            .on_changedtick(on_changedtick)
+            .on_reload(on_reload)
+            .on_detach(on_detach)
+            .build();
+
+        buffer.attach(false, &opts)?;
+
+        Ok(Self { state, listeners })
+    }
+
+    /// Registers a callback executed after every change is applied to the
+    /// mirror, receiving the change and the text it replaced.
+    ///
+    /// Together with [`BytesChange::to_content_change`] this can be used to
+    /// send incremental updates to a language server. The callback is not
+    /// executed when the whole buffer is re-read, e.g. after a reload.
+    pub fn on_change<F>(&self, callback: F)
+    where
+        F: FnMut(&BytesChange, &[u8]) -> Result<()> + 'static,
+    {
+        self.listeners.borrow_mut().push(Box::new(callback));
+    }
+
+    /// Returns the mirrored buffer.
+    pub fn buffer(&self) -> Buffer {
+        self.state().buffer.clone()
+    }
+
+    /// Returns whether the mirror is still attached to the buffer, i.e.
+    /// whether it's still being kept in sync.
+    pub fn is_attached(&self) -> bool {
+        self.state().is_attached
+    }
+
+    /// Returns the value of `b:changedtick` the contents of the mirror
+    /// correspond to.
+    pub fn changedtick(&self) -> u32 {
+        self.state().changedtick
+    }
+
+    /// Returns the number of lines in the buffer.
+    pub fn line_count(&self) -> usize {
+        self.state().rope.line_count()
+    }
+
+    /// Returns the size of the buffer in bytes, counting a newline at the end
+    /// of every line like [`Buffer::get_offset`] does.
+    pub fn byte_len(&self) -> usize {
+        self.state().rope.byte_len()
+    }
+
+    /// Returns the contents of a line, without the trailing newline, or
+    /// `None` if `row` is out of bounds.
+    pub fn line(&self, row: usize) -> Option<Ref<'_, [u8]>> {
+        Ref::filter_map(self.state(), |state| state.rope.line(row)).ok()
+    }
+
+    /// Returns the lines in the given range, or `None` if it's out of bounds.
+    pub fn lines(&self, rows: Range<usize>) -> Option<Vec<Vec<u8>>> {
+        let state = self.state();
+        rows.map(|row| state.rope.line(row).map(ToOwned::to_owned)).collect()
+    }
+
+    /// Returns the byte offset of the start of a row. `row` can be equal to
+    /// the number of lines, in which case the length of the buffer is
+    /// returned.
+    pub fn line_to_byte(&self, row: usize) -> Option<usize> {
+        self.state().rope.line_to_byte(row)
+    }
+
+    /// Converts a byte offset into a `(row, col)` position, both 0-indexed.
+    pub fn byte_to_position(&self, byte: usize) -> Option<(usize, usize)> {
+        self.state().rope.byte_to_position(byte)
+    }
+
+    /// Converts a 0-indexed `(row, col)` position into a byte offset.
+    pub fn position_to_byte(&self, row: usize, col: usize) -> Option<usize> {
+        let state = self.state();
+        let line = state.rope.line(row)?;
+        (col <= line.len())
+            .then(|| state.rope.line_to_byte(row).unwrap() + col)
+    }
+
+    /// Returns the text in the given byte range, with lines separated by
+    /// newlines.
+    pub fn text(&self, bytes: Range<usize>) -> Option<Vec<u8>> {
+        self.state().rope.text(bytes)
+    }
+
+    /// Returns the ranges that have changed since the given tick, from the
+    /// oldest to the most recent.
+    ///
+    /// Returns `None` if the history doesn't go that far back, for example
+    /// because the buffer was reloaded in the meantime, in which case the
+    /// whole buffer should be considered changed.
+    pub fn changes_since(
+        &self,
+        changedtick: u32,
+    ) -> Option<impl Iterator<Item = ChangedRange>> {
+        let state = self.state();
+
+        if changedtick < state.history_start {
+            return None;
+        }
+
+        let changes = state
+            .history
+            .iter()
+            .filter(|change| change.changedtick > changedtick)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Some(changes.into_iter())
+    }
+
+    /// Re-reads the whole buffer.
+    pub fn resync(&self) -> Result<()> {
+        self.state.borrow_mut().resync()
+    }
+
+    fn state(&self) -> Ref<'_, MirrorState> {
+        self.state.borrow()
+    }
+}
+
+/// Runs `fun` on the state of a mirror, detaching from the buffer if the
+/// mirror has been dropped.
+fn with_state(
+    state: &Weak<RefCell<MirrorState>>,
+    fun: impl FnOnce(&mut MirrorState) -> Result<()>,
+) -> Result<bool> {
+    let Some(state) = state.upgrade() else { return Ok(true) };
+    let mut state = state.borrow_mut();
+    fun(&mut state)?;
+    Ok(false)
+}
+
+impl MirrorState {
+    fn new(buffer: Buffer) -> Result<Self> {
+        let mut state = Self {
+            buffer,
+            rope: Rope::default(),
+            changedtick: 0,
+            history: VecDeque::new(),
+            history_start: 0,
+            is_attached: true,
+        };
+        state.resync()?;
+        Ok(state)
+    }
+
+    fn resync(&mut self) -> Result<()> {
+        let lines = self.buffer.get_lines(.., false)?;
+        self.rope = Rope::from_lines(lines.map(|l| l.as_bytes().to_owned()));
+        self.changedtick = self.buffer.get_changedtick()?;
+        self.history.clear();
+        self.history_start = self.changedtick;
+        Ok(())
+    }
+
+    /// Applies a change to the mirror, returning the replaced text if
+    /// `read_old_text` is set and the change was applied without resyncing.
+    fn on_bytes(
+        &mut self,
+        change: &BytesChange,
+        read_old_text: bool,
+    ) -> Result<Option<Vec<u8>>> {
+        let BytesChange { changedtick, start, old_end, new_end, .. } = *change;
+
+        let new_text = change.read_new_text()?;
+
+        let old_text = read_old_text
+            .then(|| self.rope.text(start.offset..old_end.offset))
+            .flatten();
+
+        let is_applied = new_text.len() == change.new_byte_len()
+            && self.rope.replace(
+                (start.row, start.col),
+                (old_end.row, old_end.col),
+                &new_text,
+            )
+            && self.rope.line_count() == self.buffer.line_count()?;
+
+        if !is_applied {
+            self.resync()?;
+            return Ok(None);
+        }
+
+        self.changedtick = changedtick;
+
+        if self.history.len() == HISTORY_CAPACITY {
+            let oldest = self.history.pop_front().unwrap();
+            self.history_start = oldest.changedtick;
+        }
+
+        self.history.push_back(ChangedRange {
+            changedtick,
+            old_bytes: start.offset..old_end.offset,
+            new_bytes: start.offset..new_end.offset,
+            old_rows: start.row..old_end.row + 1,
+            new_rows: start.row..new_end.row + 1,
+        });
+
+        Ok(old_text)
+    }
+}
+
+/// A sequence of lines split into chunks, so that edits only need to touch a
+/// small part of the buffer.
+#[derive(Default)]
+struct Rope {
+    chunks: Vec<Chunk>,
+
+    /// The number of lines before each chunk, plus the total number of
+    /// lines at the end.
+    line_offsets: Vec<usize>,
+
+    /// The number of bytes before each chunk, plus the total number of
+    /// bytes at the end.
+    byte_offsets: Vec<usize>,
+}
+
+#[derive(Default)]
+struct Chunk {
+    lines: Vec<Vec<u8>>,
+
+    /// The length of all the lines in the chunk, plus one newline per line.
+    byte_len: usize,
+}
+
+impl Chunk {
+    fn new(lines: Vec<Vec<u8>>) -> Self {
+        let byte_len = lines.iter().map(|l| l.len() + 1).sum();
+        Self { lines, byte_len }
+    }
+}
+
+impl Rope {
+    fn from_lines(lines: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        let mut rope = Self::default();
+        rope.splice_lines(0..0, lines.into_iter().collect());
+        rope
+    }
+
+    fn line_count(&self) -> usize {
+        self.line_offsets.last().copied().unwrap_or(0)
+    }
+
+    fn byte_len(&self) -> usize {
+        self.byte_offsets.last().copied().unwrap_or(0)
+    }
+
+    /// Returns the index of the chunk containing `row` and the index of the
+    /// row within that chunk.
+    fn locate_line(&self, row: usize) -> Option<(usize, usize)> {
+        if row >= self.line_count() {
+            return None;
+        }
+        // Chunks are never empty, so the offsets are strictly increasing.
+        let idx = self.line_offsets.partition_point(|&o| o <= row) - 1;
+        Some((idx, row - self.line_offsets[idx]))
+    }
+
+    /// Returns the index of the chunk containing `byte`, which must be
+    /// smaller than the length of the rope.
+    fn locate_byte(&self, byte: usize) -> usize {
+        self.byte_offsets.partition_point(|&o| o <= byte) - 1
+    }
+
+    fn line(&self, row: usize) -> Option<&[u8]> {
+        let (chunk, row) = self.locate_line(row)?;
+        Some(&self.chunks[chunk].lines[row])
+    }
+
+    fn line_to_byte(&self, row: usize) -> Option<usize> {
+        if row == self.line_count() {
+            return Some(self.byte_len());
+        }
+
+        let (idx, row) = self.locate_line(row)?;
+        let in_chunk: usize =
+            self.chunks[idx].lines[..row].iter().map(|l| l.len() + 1).sum();
+
+        Some(self.byte_offsets[idx] + in_chunk)
+    }
+
+    fn byte_to_position(&self, byte: usize) -> Option<(usize, usize)> {
+        match byte.cmp(&self.byte_len()) {
+            // The position right after the last newline.
+            Ordering::Equal => return Some((self.line_count(), 0)),
+            Ordering::Greater => return None,
+            Ordering::Less => {},
+        }
+
+        let idx = self.locate_byte(byte);
+        let mut byte = byte - self.byte_offsets[idx];
+        let rows = self.line_offsets[idx]..;
+
+        for (row, line) in rows.zip(&self.chunks[idx].lines) {
+            if byte <= line.len() {
+                return Some((row, byte));
+            }
+            byte -= line.len() + 1;
+        }
+
+        unreachable!("the byte is in the chunk")
+    }
+
+    fn text(&self, bytes: Range<usize>) -> Option<Vec<u8>> {
+        if bytes.start > bytes.end || bytes.end > self.byte_len() {
+            return None;
+        }
+
+        let mut text = Vec::with_capacity(bytes.len());
+
+        if bytes.is_empty() {
+            return Some(text);
+        }
+
+        let first = self.locate_byte(bytes.start);
+        let mut offset = self.byte_offsets[first];
+
+        let lines = self.chunks[first..].iter().flat_map(|c| &c.lines);
+
+        for line in lines {
+            let line_end = offset + line.len() + 1;
+
+            if line_end > bytes.start {
+                let from = bytes.start.saturating_sub(offset);
+                let to = (bytes.end - offset).min(line.len() + 1);
+                text.extend_from_slice(
+                    &line[from.min(line.len())..to.min(line.len())],
+                );
+                if to > line.len() {
+                    text.push(b'\n');
+                }
+            }
+
+            if line_end >= bytes.end {
+                break;
+            }
+
+            offset = line_end;
+        }
+
+        Some(text)
+    }
+
+    /// Replaces the text between two positions with `new_text`, returning
+    /// whether the positions were valid.
+    fn replace(
+        &mut self,
+        (start_row, start_col): (usize, usize),
+        (end_row, end_col): (usize, usize),
+        new_text: &[u8],
+    ) -> bool {
+        let line_count = self.line_count();
+
+        if (start_row, start_col) > (end_row, end_col) || end_row > line_count
+        {
+            return false;
+        }
+
+        // The lines touched by the edit, which can end one past the last
+        // line if the replaced text ends with the buffer's last newline.
+        let rows = start_row..(end_row + 1).min(line_count);
+
+        let mut old_text = Vec::new();
+        for row in rows.clone() {
+            old_text.extend_from_slice(self.line(row).unwrap());
+            old_text.push(b'\n');
+        }
+
+        let end = match self.line_to_byte(end_row) {
+            Some(end_byte) => {
+                end_byte - self.line_to_byte(start_row).unwrap() + end_col
+            },
+            None => return false,
+        };
+
+        if start_col > end || end > old_text.len() {
+            return false;
+        }
+
+        let mut text = old_text[..start_col].to_vec();
+        text.extend_from_slice(new_text);
+        text.extend_from_slice(&old_text[end..]);
+
+        let mut lines = text
+            .split(|&b| b == b'\n')
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+
+        // The text ends with a newline, which doesn't start a new line.
+        if text.last() == Some(&b'\n') || text.is_empty() {
+            lines.pop();
+        }
+
+        self.splice_lines(rows, lines);
+
+        true
+    }
+
+    /// Replaces the lines in the given range with `new_lines`.
+    fn splice_lines(&mut self, rows: Range<usize>, new_lines: Vec<Vec<u8>>) {
+        // The chunks touched by the edit.
+        let (first_chunk, start) =
+            self.locate_line(rows.start).unwrap_or((self.chunks.len(), 0));
+
+        let last_chunk = match rows.end.checked_sub(1) {
+            Some(last) if rows.end > rows.start => {
+                self.locate_line(last).map_or(first_chunk, |(c, _)| c)
+            },
+            _ => first_chunk,
+        };
+
+        let mut chunks = first_chunk..(last_chunk + 1).min(self.chunks.len());
+
+        let mut lines = self.chunks[chunks.clone()]
+            .iter_mut()
+            .flat_map(|chunk| core::mem::take(&mut chunk.lines))
+            .collect::<Vec<_>>();
+
+        lines.splice(start..start + rows.len(), new_lines);
+
+        // Merge small chunks with one of their neighbours, so that repeated
+        // deletions don't leave lots of tiny chunks behind.
+        if lines.len() < MIN_CHUNK_LINES {
+            if chunks.start > 0 {
+                chunks.start -= 1;
+                let prev = &mut self.chunks[chunks.start].lines;
+                lines.splice(0..0, core::mem::take(prev));
+            } else if chunks.end < self.chunks.len() {
+                lines.append(&mut self.chunks[chunks.end].lines);
+                chunks.end += 1;
+            }
+        }
+
+        // Split the lines evenly, so that a chunk is never smaller than half
+        // of the maximum unless it's the only one.
+        let num_chunks = lines.len().div_ceil(MAX_CHUNK_LINES);
+        let mut new_chunks = Vec::with_capacity(num_chunks);
+        for idx in (0..num_chunks).rev() {
+            let tail = lines.split_off(idx * lines.len() / (idx + 1));
+            new_chunks.push(Chunk::new(tail));
+        }
+        new_chunks.reverse();
+
+        let first_changed = chunks.start;
+        self.chunks.splice(chunks, new_chunks);
+        self.update_offsets(first_changed);
+    }
+
+    /// Recomputes the line and byte offsets of the chunks starting from the
+    /// one at index `from`.
+    fn update_offsets(&mut self, from: usize) {
+        self.line_offsets.truncate(from + 1);
+        self.byte_offsets.truncate(from + 1);
+
+        if self.line_offsets.is_empty() {
+            self.line_offsets.push(0);
+            self.byte_offsets.push(0);
+        }
+
+        for chunk in &self.chunks[from..] {
+            let lines = self.line_offsets.last().unwrap() + chunk.lines.len();
+            let bytes = self.byte_offsets.last().unwrap() + chunk.byte_len;
+            self.line_offsets.push(lines);
+            self.byte_offsets.push(bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rope(lines: &[&str]) -> Rope {
+        Rope::from_lines(lines.iter().map(|l| l.as_bytes().to_vec()))
+    }
+
+    fn lines(rope: &Rope) -> Vec<String> {
+        (0..rope.line_count())
+            .map(|row| String::from_utf8(rope.line(row).unwrap().to_vec()))
+            .collect::<std::result::Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn rope_offsets() {
+        let rope = rope(&["foo", "", "barbaz"]);
+        assert_eq!(rope.byte_len(), 12);
+        assert_eq!(rope.line_to_byte(1), Some(4));
+        assert_eq!(rope.line_to_byte(3), Some(12));
+        assert_eq!(rope.line_to_byte(4), None);
+        assert_eq!(rope.byte_to_position(5), Some((2, 0)));
+        assert_eq!(rope.byte_to_position(3), Some((0, 3)));
+        assert_eq!(rope.byte_to_position(12), Some((3, 0)));
+        assert_eq!(rope.byte_to_position(13), None);
+        assert_eq!(rope.text(2..7).unwrap(), b"o\n\nba");
+    }
+
+    #[test]
+    fn rope_replace_within_line() {
+        let mut rope = rope(&["hello world"]);
+        assert!(rope.replace((0, 6), (0, 11), b"there"));
+        assert_eq!(lines(&rope), ["hello there"]);
+    }
+
+    #[test]
+    fn rope_replace_across_lines() {
+        let mut rope = rope(&["foo", "bar", "baz"]);
+        assert!(rope.replace((0, 1), (2, 1), b"X\nY"));
+        assert_eq!(lines(&rope), ["fX", "Yaz"]);
+    }
+
+    #[test]
+    fn rope_delete_last_line() {
+        let mut rope = rope(&["a", "b"]);
+        assert!(rope.replace((1, 0), (2, 0), b""));
+        assert_eq!(lines(&rope), ["a"]);
+    }
+
+    #[test]
+    fn rope_append_lines() {
+        let mut rope = rope(&["a"]);
+        assert!(rope.replace((1, 0), (1, 0), b"b\nc\n"));
+        assert_eq!(lines(&rope), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn rope_invalid_replace() {
+        let mut rope = rope(&["a"]);
+        assert!(!rope.replace((0, 0), (3, 0), b""));
+        assert!(!rope.replace((0, 1), (0, 0), b""));
+    }
+
+    #[test]
+    fn rope_many_chunks() {
+        let n = MAX_CHUNK_LINES * 3 + 7;
+        let mut rope =
+            Rope::from_lines((0..n).map(|i| i.to_string().into_bytes()));
+        assert_eq!(rope.chunks.len(), 4);
+        assert_eq!(rope.line(MAX_CHUNK_LINES + 1).unwrap(), b"257");
+
+        // Delete lines spanning three chunks.
+        rope.splice_lines(10..MAX_CHUNK_LINES * 2 + 10, vec![b"x".to_vec()]);
+        assert_eq!(rope.line_count(), n - MAX_CHUNK_LINES * 2 + 1);
+        assert_eq!(rope.line(10).unwrap(), b"x");
+        assert_eq!(rope.line(11).unwrap(), b"522");
+
+        let byte_len: usize = (0..rope.line_count())
+            .map(|r| rope.line(r).unwrap().len() + 1)
+            .sum();
+        assert_eq!(rope.byte_len(), byte_len);
+    }
+
+    #[test]
+    fn rope_deletions_merge_chunks() {
+        let n = MAX_CHUNK_LINES * 4;
+        let mut rope =
+            Rope::from_lines((0..n).map(|i| i.to_string().into_bytes()));
+
+        for deleted in 1..=n - 10 {
+            rope.splice_lines(5..6, Vec::new());
+            assert_eq!(rope.line_count(), n - deleted);
+            assert!(rope
+                .chunks
+                .iter()
+                .all(|c| c.lines.len() >= MIN_CHUNK_LINES
+                    || rope.chunks.len() == 1));
+        }
+
+        assert_eq!(rope.chunks.len(), 1);
+        assert_eq!(lines(&rope)[4..6], ["4".to_owned(), (n - 5).to_string()]);
+    }
+
+    #[test]
+    fn rope_offsets_across_chunks() {
+        let n = MAX_CHUNK_LINES * 2 + 3;
+        let mut rope =
+            Rope::from_lines((0..n).map(|i| i.to_string().into_bytes()));
+        rope.splice_lines(MAX_CHUNK_LINES..MAX_CHUNK_LINES, vec![b"x".into()]);
+
+        let all = lines(&rope).join("\n") + "\n";
+        assert_eq!(rope.byte_len(), all.len());
+        assert_eq!(rope.text(0..all.len()).unwrap(), all.as_bytes());
+
+        for row in 0..=rope.line_count() {
+            let byte = rope.line_to_byte(row).unwrap();
+            assert_eq!(rope.byte_to_position(byte), Some((row, 0)));
+        }
+
+        let boundary = rope.line_to_byte(MAX_CHUNK_LINES).unwrap();
+        let range = boundary - 3..boundary + 4;
+        assert_eq!(rope.text(range.clone()).unwrap(), &all.as_bytes()[range]);
+    }
+}
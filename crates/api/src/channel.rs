@@ -0,0 +1,79 @@
+use types::{self as nvim, Integer};
+
+use crate::choose;
+use crate::ffi::vim::nvim_chan_send;
+use crate::types::ChannelInfos;
+use crate::{Error, Result};
+
+/// A wrapper around the id of a Neovim
+/// [channel](https://neovim.io/doc/user/channel.html), like the one
+/// connected to a [`Job`](crate::Job).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Channel(u32);
+
+impl From<u32> for Channel {
+    #[inline]
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl Channel {
+    /// Returns the id of the channel.
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.0
+    }
+
+    /// Sends raw data to the channel with [`chan_send()`](crate::chan_send).
+    #[inline]
+    pub fn send(&self, data: &str) -> Result<()> {
+        crate::chan_send(self.0, data)
+    }
+
+    /// Like [`send`](Channel::send), but the data doesn't have to be valid
+    /// UTF-8.
+    pub fn send_bytes(&self, data: &[u8]) -> Result<()> {
+        let data = nvim::String::from_bytes(data);
+        let mut err = nvim::Error::new();
+        unsafe {
+            nvim_chan_send(self.0.into(), data.non_owning(), &mut err)
+        };
+        choose!(err, ())
+    }
+
+    /// Closes the channel. For a job this closes its stdin, stdout and
+    /// stderr, which usually makes it exit.
+    pub fn close(&self) -> Result<()> {
+        self.chanclose(None)
+    }
+
+    /// Closes the stdin of the job connected to the channel, signaling the
+    /// end of its input.
+    pub fn close_stdin(&self) -> Result<()> {
+        self.chanclose(Some("stdin"))
+    }
+
+    fn chanclose(&self, stream: Option<&str>) -> Result<()> {
+        let id = self.0 as Integer;
+
+        let closed = match stream {
+            Some(stream) => {
+                crate::call_function::<_, Integer>("chanclose", (id, stream))
+            },
+            None => crate::call_function::<_, Integer>("chanclose", (id,)),
+        }?;
+
+        match closed {
+            1 => Ok(()),
+            _ => Err(Error::custom(format!("channel {id} is already closed"))),
+        }
+    }
+
+    /// Returns informations about the channel with
+    /// [`get_chan_info()`](crate::get_chan_info).
+    #[inline]
+    pub fn info(&self) -> Result<ChannelInfos> {
+        crate::get_chan_info(self.0)
+    }
+}
@@ -0,0 +1,212 @@
+use types::{Array, Dictionary, Function, Integer, Object};
+
+use crate::opts::{CreateAugroupOpts, CreateAutocmdOpts, OptionOpts};
+use crate::types::CompletionItem;
+use crate::{Buffer, Error, Result, Window};
+
+/// A source of Insert mode completions, used as the `'completefunc'` or
+/// `'omnifunc'` of a buffer.
+///
+/// Neovim calls the function set in those options twice: first to find
+/// where the completed text starts, then to get the completions of the text
+/// between that position and the cursor. The two calls are dispatched to
+/// [`find_start`](CompletionProvider::find_start) and
+/// [`complete`](CompletionProvider::complete) respectively.
+///
+/// To complete asynchronously return
+/// [`Completions::incomplete`] from `complete` and call [`complete()`]
+/// once the results are ready.
+pub trait CompletionProvider: 'static {
+    /// Returns where the completed text starts. `line` is the line of the
+    /// cursor and `col` the 0-indexed byte offset of the cursor in it.
+    ///
+    /// Defaults to the start of the keyword before the cursor.
+    #[inline]
+    fn find_start(&mut self, line: &str, col: usize) -> CompletionStart {
+        let start = line
+            .get(..col)
+            .unwrap_or(line)
+            .char_indices()
+            .rev()
+            .take_while(|&(_, ch)| ch.is_alphanumeric() || ch == '_')
+            .last()
+            .map_or(col, |(idx, _)| idx);
+
+        CompletionStart::Column(start)
+    }
+
+    /// Returns the completions of `base`, the text between the start
+    /// returned by [`find_start`](CompletionProvider::find_start) and the
+    /// cursor.
+    fn complete(&mut self, base: &str) -> Completions;
+}
+
+/// The value returned by [`CompletionProvider::find_start`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CompletionStart {
+    /// The 0-indexed byte column where the completed text starts.
+    Column(usize),
+
+    /// Cancels the completion without an error message, staying in
+    /// completion mode.
+    Cancel,
+
+    /// Cancels the completion without an error message, leaving completion
+    /// mode.
+    Leave,
+}
+
+impl From<CompletionStart> for Object {
+    #[inline]
+    fn from(start: CompletionStart) -> Self {
+        match start {
+            CompletionStart::Column(col) => (col as Integer).into(),
+            CompletionStart::Cancel => (-2).into(),
+            CompletionStart::Leave => (-3).into(),
+        }
+    }
+}
+
+/// The completions returned by [`CompletionProvider::complete`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Completions {
+    /// The items displayed in the popup menu.
+    pub items: Vec<CompletionItem>,
+
+    /// Whether the provider should be called again every time a character
+    /// is typed or deleted while the menu is open, instead of filtering the
+    /// current items.
+    pub incomplete: bool,
+}
+
+impl Completions {
+    /// Returns incomplete completions containing `items`.
+    #[inline]
+    pub fn incomplete<I>(items: I) -> Self
+    where
+        I: IntoIterator<Item = CompletionItem>,
+    {
+        Self { items: items.into_iter().collect(), incomplete: true }
+    }
+}
+
+impl From<Vec<CompletionItem>> for Completions {
+    #[inline]
+    fn from(items: Vec<CompletionItem>) -> Self {
+        Self { items, incomplete: false }
+    }
+}
+
+impl FromIterator<CompletionItem> for Completions {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = CompletionItem>>(iter: I) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
+    }
+}
+
+impl From<&Completions> for Object {
+    fn from(completions: &Completions) -> Self {
+        let words = items_to_array(&completions.items);
+
+        if completions.incomplete {
+            Dictionary::from_iter([
+                ("words", Object::from(words)),
+                ("refresh", Object::from("always")),
+            ])
+            .into()
+        } else {
+            words.into()
+        }
+    }
+}
+
+/// Binding to `complete()`.
+///
+/// Displays the popup menu with `items` completing the text starting at
+/// the 0-indexed byte column `col` of the cursor's line. Only works in
+/// Insert mode.
+pub fn complete<I>(col: usize, items: I) -> Result<()>
+where
+    I: IntoIterator<Item = CompletionItem>,
+{
+    let items = items.into_iter().collect::<Vec<_>>();
+    let col = (col + 1) as Integer;
+    crate::call_function("complete", (col, items_to_array(&items)))
+}
+
+impl Buffer {
+    /// Sets the buffer's `'completefunc'` to `provider`, which is used by
+    /// `i_CTRL-X_CTRL-U`.
+    pub fn set_completefunc<P>(&self, provider: P) -> Result<()>
+    where
+        P: CompletionProvider,
+    {
+        self.set_completion_provider("completefunc", provider)
+    }
+
+    /// Sets the buffer's `'omnifunc'` to `provider`, which is used by
+    /// `i_CTRL-X_CTRL-O`.
+    pub fn set_omnifunc<P>(&self, provider: P) -> Result<()>
+    where
+        P: CompletionProvider,
+    {
+        self.set_completion_provider("omnifunc", provider)
+    }
+
+    fn set_completion_provider<P>(
+        &self,
+        option: &str,
+        mut provider: P,
+    ) -> Result<()>
+    where
+        P: CompletionProvider,
+    {
+        let fun = Function::<(Integer, String), Object>::from_fn_mut(
+            move |(findstart, base)| {
+                if findstart == 0 {
+                    return Ok::<_, Error>(Object::from(
+                        &provider.complete(&base),
+                    ));
+                }
+
+                let line = crate::get_current_line()?;
+                let (_, col) = Window::current().get_cursor()?;
+                Ok(provider.find_start(&line, col).into())
+            },
+        );
+
+        // The options can only be set to the name of a function, so the
+        // provider is stored in a global and called through `v:lua`. The
+        // global is keyed on the buffer so that setting another provider
+        // replaces it, and the function is only referenced by the global.
+        let name = format!("_nvim_oxi_{option}_{}", self.0);
+        crate::utils::set_lua_global(&name, fun.clone())?;
+        fun.remove_from_lua_registry();
+
+        let opts = OptionOpts::builder().buffer(self.clone()).build();
+        crate::set_option_value(option, format!("v:lua.{name}"), &opts)?;
+
+        let augroup = crate::create_augroup(
+            &name,
+            &CreateAugroupOpts::builder().clear(true).build(),
+        )?;
+
+        let wipeout = CreateAutocmdOpts::builder()
+            .group(augroup)
+            .buffer(self.clone())
+            .once(true)
+            .callback(move |_| {
+                crate::utils::set_lua_global(&name, Object::nil())?;
+                Ok::<_, Error>(true)
+            })
+            .build();
+
+        crate::create_autocmd(["BufWipeout"], &wipeout)?;
+
+        Ok(())
+    }
+}
+
+fn items_to_array(items: &[CompletionItem]) -> Array {
+    items.iter().map(Dictionary::from).collect()
+}
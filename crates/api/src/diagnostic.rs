@@ -0,0 +1,92 @@
+//! Bindings to the [`vim.diagnostic`][1] Lua module.
+//!
+//! Diagnostics are grouped by namespace, which can be created with
+//! [`create_namespace()`](crate::create_namespace).
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::api::{self, diagnostic, types::*, Buffer};
+//!
+//! let namespace = api::create_namespace("my-linter");
+//!
+//! let range = Range::new(Position::new(0, 0), Position::new(0, 3));
+//! let mut diag = Diagnostic::new(range, DiagnosticSeverity::Warn, "oops");
+//! diag.source = Some("my-linter".into());
+//!
+//! diagnostic::set(namespace, &Buffer::current(), [diag])?;
+//! ```
+//!
+//! [1]: https://neovim.io/doc/user/diagnostic.html
+
+use types::{Array, Dictionary, Integer};
+
+use crate::opts::{DiagnosticConfigOpts, GetDiagnosticsOpts};
+use crate::types::Diagnostic;
+use crate::utils::call_vim_function;
+use crate::{Buffer, Result};
+
+/// Binding to [`vim.diagnostic.set()`][1].
+///
+/// Replaces the diagnostics of `buffer` in the given namespace.
+///
+/// [1]: https://neovim.io/doc/user/diagnostic.html#vim.diagnostic.set()
+pub fn set<I>(namespace: u32, buffer: &Buffer, diagnostics: I) -> Result<()>
+where
+    I: IntoIterator<Item = Diagnostic>,
+{
+    let diagnostics = diagnostics
+        .into_iter()
+        .map(|diagnostic| Dictionary::from(&diagnostic))
+        .collect::<Array>();
+
+    call_vim_function(
+        "diagnostic.set",
+        (namespace as Integer, buffer.clone(), diagnostics),
+    )
+}
+
+/// Binding to [`vim.diagnostic.get()`][1].
+///
+/// Returns the diagnostics of `buffer`, or of all the buffers if it's
+/// `None`.
+///
+/// [1]: https://neovim.io/doc/user/diagnostic.html#vim.diagnostic.get()
+pub fn get(
+    buffer: Option<&Buffer>,
+    opts: &GetDiagnosticsOpts,
+) -> Result<Vec<Diagnostic>> {
+    call_vim_function(
+        "diagnostic.get",
+        (buffer.cloned(), Dictionary::from(opts)),
+    )
+}
+
+/// Binding to [`vim.diagnostic.reset()`][1].
+///
+/// Removes the diagnostics in the given namespace, or in all of them if
+/// it's `None`, from `buffer`, or from all the buffers if it's `None`.
+///
+/// [1]: https://neovim.io/doc/user/diagnostic.html#vim.diagnostic.reset()
+pub fn reset(namespace: Option<u32>, buffer: Option<&Buffer>) -> Result<()> {
+    call_vim_function(
+        "diagnostic.reset",
+        (namespace.map(|ns| ns as Integer), buffer.cloned()),
+    )
+}
+
+/// Binding to [`vim.diagnostic.config()`][1].
+///
+/// Configures how diagnostics are displayed, either globally or for the
+/// given namespace.
+///
+/// [1]: https://neovim.io/doc/user/diagnostic.html#vim.diagnostic.config()
+pub fn config(
+    opts: &DiagnosticConfigOpts,
+    namespace: Option<u32>,
+) -> Result<()> {
+    call_vim_function(
+        "diagnostic.config",
+        (Dictionary::from(opts), namespace.map(|ns| ns as Integer)),
+    )
+}
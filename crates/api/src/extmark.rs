@@ -288,6 +288,17 @@ impl Buffer {
         };
         choose!(err, Ok(id.try_into().expect("always positive")))
     }
+
+    /// Same as [`set_extmark`](Buffer::set_extmark), but takes the position
+    /// of the extmark as a [`Position`].
+    pub fn set_extmark_at(
+        &mut self,
+        ns_id: u32,
+        pos: Position,
+        opts: &SetExtmarkOpts,
+    ) -> Result<u32> {
+        self.set_extmark(ns_id, pos.row, pos.col, opts)
+    }
 }
 
 impl crate::Window {
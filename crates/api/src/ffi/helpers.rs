@@ -2,12 +2,41 @@
     all(target_os = "windows", target_env = "msvc"),
     link(name = "nvim.exe", kind = "raw-dylib", modifiers = "+verbatim")
 )]
+use core::ffi::c_char;
+
+use types::BufHandle;
+
+/// Opaque binding to Neovim's `buf_T`.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+pub(crate) struct buf_T {
+    _private: [u8; 0],
+}
+
 extern "C" {
+    // https://github.com/neovim/neovim/blob/v0.9.0/src/nvim/api/private/helpers.c#L362
+    pub(crate) fn find_buffer_by_handle(
+        buffer: BufHandle,
+        err: *mut types::Error,
+    ) -> *mut buf_T;
+
+    // https://github.com/neovim/neovim/blob/v0.9.0/src/nvim/memline.c#L1846
+    #[cfg(not(feature = "neovim-nightly"))]
+    pub(crate) fn ml_get_buf(
+        buf: *mut buf_T,
+        lnum: i32,
+        will_change: bool,
+    ) -> *mut c_char;
+
+    // https://github.com/neovim/neovim/blob/v0.10.0/src/nvim/memline.c#L1852
+    #[cfg(feature = "neovim-nightly")]
+    pub(crate) fn ml_get_buf(buf: *mut buf_T, lnum: i32) -> *mut c_char;
+
     // https://github.com/neovim/neovim/blob/4f788f78f8b2d59a76b1a54a40af0c478eb3f929/src/nvim/api/private/helpers.c#L841
     #[cfg(feature = "neovim-nightly")]
     pub(crate) fn object_to_hl_id(
         obj: types::Object,
-        what: *const core::ffi::c_char,
+        what: *const c_char,
         err: *mut types::Error,
     ) -> types::Integer;
 }
@@ -0,0 +1,334 @@
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+
+use types::{BufHandle, Function, Integer};
+
+use crate::opts::{
+    CreateAugroupOpts, CreateAutocmdOpts, OptionOpts, OptionScope,
+};
+use crate::{Buffer, Error, Result, Window};
+
+/// The name of the Lua global used as the `'foldexpr'` of the buffers with
+/// a fold provider.
+const FOLDEXPR: &str = "_nvim_oxi_foldexpr";
+
+thread_local! {
+    static FOLD_PROVIDERS: RefCell<HashMap<BufHandle, FoldProvider>> =
+        RefCell::new(HashMap::new());
+
+    static HAS_FOLDEXPR: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A fold in a window, returned by [`Window::folds()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Fold {
+    /// The 0-indexed first line of the fold.
+    pub start: usize,
+
+    /// The 0-indexed last line of the fold, inclusive.
+    pub end: usize,
+
+    /// The nesting level of the fold, starting from 1.
+    pub level: u32,
+
+    /// Whether the fold is closed, either by itself or because one of the
+    /// folds containing it is.
+    pub is_closed: bool,
+}
+
+/// The fold level of a line, returned by the provider given to
+/// [`Buffer::set_foldexpr()`].
+///
+/// See `:h fold-expr` for the meaning of each variant.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum FoldLevel {
+    /// The line is in a fold of the given level, `0` if it isn't in a fold.
+    Level(u32),
+
+    /// The level is undefined, and it's the lowest of the level of the
+    /// lines before and after it. Like `-1`.
+    Undefined,
+
+    /// Same level as the previous line. Like `=`.
+    #[default]
+    Same,
+
+    /// Adds to the level of the previous line. Like `a1`, `a2`, etc.
+    Add(u32),
+
+    /// Subtracts from the level of the previous line. Like `s1`, `s2`, etc.
+    Sub(u32),
+
+    /// A fold of the given level starts at this line. Like `>1`, `>2`, etc.
+    Start(u32),
+
+    /// A fold of the given level ends at this line. Like `<1`, `<2`, etc.
+    End(u32),
+}
+
+impl fmt::Display for FoldLevel {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Level(level) => write!(f, "{level}"),
+            Self::Undefined => f.write_str("-1"),
+            Self::Same => f.write_str("="),
+            Self::Add(n) => write!(f, "a{n}"),
+            Self::Sub(n) => write!(f, "s{n}"),
+            Self::Start(level) => write!(f, ">{level}"),
+            Self::End(level) => write!(f, "<{level}"),
+        }
+    }
+}
+
+type ComputeLevels = dyn FnMut(&Buffer) -> Result<Vec<FoldLevel>>;
+
+struct FoldProvider {
+    compute: Box<ComputeLevels>,
+
+    /// The `changedtick` of the buffer when `levels` were computed.
+    changedtick: Option<u32>,
+
+    levels: Vec<FoldLevel>,
+}
+
+impl Window {
+    /// Returns the folds in the window, sorted by their first line and
+    /// with the outer folds before the ones they contain.
+    ///
+    /// The folds are found from the fold level of each line, so two
+    /// adjacent folds of the same level are returned as a single one.
+    pub fn folds(&self) -> Result<Vec<Fold>> {
+        let levels = self.call(|()| {
+            crate::eval::<Vec<Integer>>(
+                "map(range(1, line('$')), 'foldlevel(v:val)')",
+            )
+        })?;
+
+        let closed = self.call(|()| {
+            crate::eval::<Vec<Integer>>(
+                "map(range(1, line('$')), 'foldclosed(v:val)')",
+            )
+        })?;
+
+        Ok(compute_folds(&levels, &closed))
+    }
+
+    /// Opens the fold containing the given 0-indexed line.
+    pub fn open_fold(&mut self, line: usize) -> Result<()> {
+        self.call(move |()| crate::command(&format!("{}foldopen", line + 1)))
+    }
+
+    /// Closes the fold containing the given 0-indexed line.
+    pub fn close_fold(&mut self, line: usize) -> Result<()> {
+        self.call(move |()| crate::command(&format!("{}foldclose", line + 1)))
+    }
+
+    /// Opens the fold containing the given 0-indexed line if it's closed,
+    /// or closes it if it's open.
+    pub fn toggle_fold(&mut self, line: usize) -> Result<()> {
+        self.call(move |()| {
+            let lnum = line as Integer + 1;
+            let closed =
+                crate::call_function::<_, Integer>("foldclosed", (lnum,))?;
+            let cmd = if closed == -1 { "foldclose" } else { "foldopen" };
+            crate::command(&format!("{lnum}{cmd}"))
+        })
+    }
+}
+
+impl Buffer {
+    /// Folds the buffer with the levels returned by `provider`, which is
+    /// given the buffer and returns the [`FoldLevel`] of each of its lines.
+    ///
+    /// The buffer's windows have their `'foldmethod'` set to `expr` and
+    /// their `'foldexpr'` to a function returning the levels. `provider`
+    /// is only called again after the buffer changes, and the lines past
+    /// the end of the levels it returns are considered
+    /// [`Same`](FoldLevel::Same).
+    pub fn set_foldexpr<F, E>(&self, mut provider: F) -> Result<()>
+    where
+        F: FnMut(&Buffer) -> std::result::Result<Vec<FoldLevel>, E> + 'static,
+        E: StdError + 'static,
+    {
+        let compute = move |buf: &Buffer| provider(buf).map_err(Error::custom);
+
+        FOLD_PROVIDERS.with(|providers| {
+            providers.borrow_mut().insert(
+                self.0,
+                FoldProvider {
+                    compute: Box::new(compute),
+                    changedtick: None,
+                    levels: Vec::new(),
+                },
+            )
+        });
+
+        if !HAS_FOLDEXPR.with(|has| has.replace(true)) {
+            let foldexpr = Function::<(), String>::from_fn(|()| foldexpr());
+            crate::utils::set_lua_global(FOLDEXPR, foldexpr)?;
+        }
+
+        let windows = crate::call_function::<_, Vec<Integer>>(
+            "win_findbuf",
+            (self.0 as Integer,),
+        )?;
+
+        for window in windows {
+            set_fold_options(Window::from(window as i32))?;
+        }
+
+        let augroup = crate::create_augroup(
+            &format!("nvim_oxi_foldexpr_{}", self.0),
+            &CreateAugroupOpts::builder().clear(true).build(),
+        )?;
+
+        let enter = CreateAutocmdOpts::builder()
+            .group(augroup)
+            .buffer(self.clone())
+            .callback(|_| {
+                set_fold_options(Window::current())?;
+                Ok::<_, Error>(false)
+            })
+            .build();
+
+        crate::create_autocmd(["BufWinEnter"], &enter)?;
+
+        let buffer = self.0;
+        let wipeout = CreateAutocmdOpts::builder()
+            .group(augroup)
+            .buffer(self.clone())
+            .once(true)
+            .callback(move |_| {
+                FOLD_PROVIDERS
+                    .with(|providers| providers.borrow_mut().remove(&buffer));
+                Ok::<_, Error>(true)
+            })
+            .build();
+
+        crate::create_autocmd(["BufWipeout"], &wipeout)?;
+
+        Ok(())
+    }
+}
+
+fn set_fold_options(window: Window) -> Result<()> {
+    #[cfg(not(feature = "neovim-nightly"))]
+    let opts =
+        OptionOpts::builder().window(window).scope(OptionScope::Local).build();
+    #[cfg(feature = "neovim-nightly")]
+    let opts =
+        OptionOpts::builder().win(window).scope(OptionScope::Local).build();
+
+    crate::set_option_value("foldmethod", "expr", &opts)?;
+    crate::set_option_value("foldexpr", format!("v:lua.{FOLDEXPR}()"), &opts)
+}
+
+/// Returns the fold level of `v:lnum` in the current buffer.
+fn foldexpr() -> Result<String> {
+    let buffer = Buffer::current();
+    let lnum = crate::get_vvar::<Integer>("lnum")?;
+
+    // The provider is taken out of the map while it's called, so that it
+    // can set the fold provider of another buffer.
+    let Some(mut provider) = FOLD_PROVIDERS
+        .with(|providers| providers.borrow_mut().remove(&buffer.0))
+    else {
+        return Ok(FoldLevel::Level(0).to_string());
+    };
+
+    let changedtick = buffer.get_changedtick()?;
+
+    let res = if provider.changedtick == Some(changedtick) {
+        Ok(())
+    } else {
+        (provider.compute)(&buffer).map(|levels| {
+            provider.levels = levels;
+            provider.changedtick = Some(changedtick);
+        })
+    };
+
+    let level = usize::try_from(lnum - 1)
+        .ok()
+        .and_then(|idx| provider.levels.get(idx).copied())
+        .unwrap_or_default();
+
+    FOLD_PROVIDERS.with(|providers| {
+        providers.borrow_mut().entry(buffer.0).or_insert(provider);
+    });
+
+    res.map(|()| level.to_string())
+}
+
+/// Finds the folds from the fold level and the result of `foldclosed()` of
+/// each line.
+fn compute_folds(levels: &[Integer], closed: &[Integer]) -> Vec<Fold> {
+    let max_level = levels.iter().copied().max().unwrap_or(0);
+
+    let mut folds = Vec::new();
+
+    for level in 1..=max_level {
+        let mut start = None;
+
+        for (line, &line_level) in
+            levels.iter().chain(core::iter::once(&0)).enumerate()
+        {
+            match (start, line_level >= level) {
+                (None, true) => start = Some(line),
+                (Some(first), false) => {
+                    folds.push(Fold {
+                        start: first,
+                        end: line - 1,
+                        level: level as u32,
+                        is_closed: closed[first] != -1,
+                    });
+                    start = None;
+                },
+                _ => {},
+            }
+        }
+    }
+
+    folds.sort_by_key(|fold| (fold.start, fold.level));
+    folds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fold_level_display() {
+        assert_eq!("0", FoldLevel::Level(0).to_string());
+        assert_eq!("-1", FoldLevel::Undefined.to_string());
+        assert_eq!("=", FoldLevel::Same.to_string());
+        assert_eq!("a2", FoldLevel::Add(2).to_string());
+        assert_eq!("s1", FoldLevel::Sub(1).to_string());
+        assert_eq!(">1", FoldLevel::Start(1).to_string());
+        assert_eq!("<3", FoldLevel::End(3).to_string());
+    }
+
+    #[test]
+    fn compute_nested_folds() {
+        let levels = [0, 1, 2, 2, 1, 0, 1];
+        let closed = [-1, -1, 3, 3, -1, -1, 7];
+
+        let fold = |start, end, level, is_closed| Fold {
+            start,
+            end,
+            level,
+            is_closed,
+        };
+
+        assert_eq!(
+            vec![
+                fold(1, 4, 1, false),
+                fold(2, 3, 2, true),
+                fold(6, 6, 1, true),
+            ],
+            compute_folds(&levels, &closed)
+        );
+    }
+}
@@ -0,0 +1,386 @@
+use core::fmt;
+use std::error::Error as StdError;
+use std::path::Path;
+use std::time::Duration;
+
+use types::{Array, Dictionary, Function, Integer, Object};
+
+use crate::{Channel, Error, Result};
+
+type OnOutput = Box<dyn FnMut(JobOutput) -> Result<()>>;
+type OnExit = Box<dyn FnOnce(i32) -> Result<()>>;
+
+/// The arguments passed by Neovim to the `on_stdout` and `on_stderr`
+/// callbacks of `jobstart()`: the job id, the data and the event name.
+type OutputArgs = (Integer, Vec<String>, String);
+
+/// Lines read from the stdout or stderr of a [`Job`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct JobOutput {
+    /// The complete lines read since the last chunk, without their
+    /// trailing newline.
+    pub lines: Vec<String>,
+
+    /// Whether the stream was closed. In that case `lines` contains the
+    /// last line if it didn't end with a newline.
+    pub is_eof: bool,
+}
+
+/// How a call to [`Job::wait()`] returned.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum JobWaitStatus {
+    /// The job exited with the given code, which is `128 + n` if it was
+    /// killed by signal `n`.
+    Exited(i32),
+
+    /// The job was still running after the timeout.
+    TimedOut,
+
+    /// The wait was interrupted by the user with `CTRL-C`.
+    Interrupted,
+}
+
+/// A job started with [`jobstart()`][1].
+///
+/// [1]: https://neovim.io/doc/user/builtin.html#jobstart()
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Job {
+    channel: Channel,
+}
+
+impl Job {
+    #[inline(always)]
+    /// Creates a new [`JobBuilder`] for a job running `cmd`, which is made
+    /// of the program to run followed by its arguments.
+    pub fn builder<I, S>(cmd: I) -> JobBuilder
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        JobBuilder {
+            cmd: cmd.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the id of the job, which is also the id of its channel.
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.channel.id()
+    }
+
+    /// Returns the channel connected to the stdin, stdout and stderr of the
+    /// job.
+    #[inline]
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    /// Sends raw data to the job's stdin.
+    #[inline]
+    pub fn send(&self, data: &str) -> Result<()> {
+        self.channel.send(data)
+    }
+
+    /// Returns the process id of the job.
+    pub fn pid(&self) -> Result<u32> {
+        let pid =
+            crate::call_function::<_, Integer>("jobpid", (self.id_int(),))?;
+        Ok(pid.try_into()?)
+    }
+
+    /// Waits for the job to exit for at most `timeout`, or indefinitely if
+    /// it's `None`.
+    ///
+    /// The output of the job is processed and its callbacks are called
+    /// while waiting.
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<JobWaitStatus> {
+        let timeout = timeout.map_or(-1, |timeout| timeout.as_millis() as i64);
+        let ids = Array::from_iter([self.id_int()]);
+
+        let status = crate::call_function::<_, Vec<Integer>>(
+            "jobwait",
+            (ids, timeout),
+        )?;
+
+        match status.first().copied() {
+            Some(-1) => Ok(JobWaitStatus::TimedOut),
+            Some(-2) => Ok(JobWaitStatus::Interrupted),
+            Some(code) if code >= 0 => {
+                Ok(JobWaitStatus::Exited(code.try_into()?))
+            },
+            _ => Err(self.invalid()),
+        }
+    }
+
+    /// Stops the job by sending it `SIGTERM`, followed by `SIGKILL` if it's
+    /// still running after a while. Does nothing if the job has already
+    /// exited.
+    pub fn stop(&self) -> Result<()> {
+        crate::call_function::<_, Integer>("jobstop", (self.id_int(),))?;
+        Ok(())
+    }
+
+    #[inline]
+    fn id_int(&self) -> Integer {
+        self.id() as Integer
+    }
+
+    #[inline]
+    fn invalid(&self) -> Error {
+        Error::custom(format!("{} is not a valid job id", self.id()))
+    }
+}
+
+/// A builder for a [`Job`], created with [`Job::builder()`].
+#[derive(Default)]
+pub struct JobBuilder {
+    cmd: Vec<String>,
+    opts: Dictionary,
+    env: Dictionary,
+    on_stdout: Option<OnOutput>,
+    on_stderr: Option<OnOutput>,
+    on_exit: Option<OnExit>,
+}
+
+impl fmt::Debug for JobBuilder {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JobBuilder")
+            .field("cmd", &self.cmd)
+            .field("opts", &self.opts)
+            .field("env", &self.env)
+            .finish_non_exhaustive()
+    }
+}
+
+impl JobBuilder {
+    /// Directory the job is started in. Defaults to the current working
+    /// directory.
+    #[inline]
+    pub fn cwd(&mut self, cwd: impl AsRef<Path>) -> &mut Self {
+        let cwd = cwd.as_ref().display().to_string();
+        self.opts.insert("cwd", cwd);
+        self
+    }
+
+    /// Sets an environment variable of the job.
+    #[inline]
+    pub fn env(&mut self, name: &str, value: &str) -> &mut Self {
+        self.env.insert(name, value);
+        self
+    }
+
+    /// Whether to only pass the variables set with
+    /// [`env`](JobBuilder::env) to the job, instead of adding them to the
+    /// environment of Neovim.
+    #[inline]
+    pub fn clear_env(&mut self, clear_env: bool) -> &mut Self {
+        self.opts.insert("clear_env", clear_env);
+        self
+    }
+
+    /// Whether to keep the job running after Neovim exits.
+    #[inline]
+    pub fn detach(&mut self, detach: bool) -> &mut Self {
+        self.opts.insert("detach", detach);
+        self
+    }
+
+    /// Whether to connect the job to a pseudo-terminal instead of pipes.
+    /// Its stdout and stderr are then both read by
+    /// [`on_stdout`](JobBuilder::on_stdout).
+    #[inline]
+    pub fn pty(&mut self, pty: bool) -> &mut Self {
+        self.opts.insert("pty", pty);
+        self
+    }
+
+    /// Width and height of the pseudo-terminal set with
+    /// [`pty`](JobBuilder::pty).
+    #[inline]
+    pub fn pty_size(&mut self, width: u32, height: u32) -> &mut Self {
+        self.opts.insert("width", width);
+        self.opts.insert("height", height);
+        self
+    }
+
+    /// Whether to communicate with the job over msgpack-rpc. Can't be used
+    /// together with [`on_stdout`](JobBuilder::on_stdout).
+    #[inline]
+    pub fn rpc(&mut self, rpc: bool) -> &mut Self {
+        self.opts.insert("rpc", rpc);
+        self
+    }
+
+    /// Function called with the lines printed by the job to its stdout.
+    pub fn on_stdout<F, E>(&mut self, mut fun: F) -> &mut Self
+    where
+        F: FnMut(JobOutput) -> std::result::Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let fun = move |output| fun(output).map_err(Error::custom);
+        self.on_stdout = Some(Box::new(fun));
+        self
+    }
+
+    /// Function called with the lines printed by the job to its stderr.
+    pub fn on_stderr<F, E>(&mut self, mut fun: F) -> &mut Self
+    where
+        F: FnMut(JobOutput) -> std::result::Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let fun = move |output| fun(output).map_err(Error::custom);
+        self.on_stderr = Some(Box::new(fun));
+        self
+    }
+
+    /// Function called with the exit code of the job once it exits.
+    pub fn on_exit<F, E>(&mut self, fun: F) -> &mut Self
+    where
+        F: FnOnce(i32) -> std::result::Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let fun = move |code| fun(code).map_err(Error::custom);
+        self.on_exit = Some(Box::new(fun));
+        self
+    }
+
+    /// Starts the job.
+    pub fn start(&mut self) -> Result<Job> {
+        let mut opts = std::mem::take(&mut self.opts);
+        let env = std::mem::take(&mut self.env);
+
+        if !env.is_empty() {
+            opts.insert("env", env);
+        }
+
+        let mut callbacks = Vec::new();
+
+        if let Some(on_stdout) = self.on_stdout.take() {
+            let fun = output_callback(on_stdout);
+            opts.insert("on_stdout", fun.clone());
+            callbacks.push(fun);
+        }
+
+        if let Some(on_stderr) = self.on_stderr.take() {
+            let fun = output_callback(on_stderr);
+            opts.insert("on_stderr", fun.clone());
+            callbacks.push(fun);
+        }
+
+        let on_exit = self.on_exit.take().map(|on_exit| {
+            Function::<(Integer, Integer, String), ()>::from_fn_once(
+                move |(_, code, _)| on_exit(code.try_into()?),
+            )
+        });
+
+        if let Some(fun) = &on_exit {
+            opts.insert("on_exit", fun.clone());
+        }
+
+        let cmd = self.cmd.iter().map(String::as_str).collect::<Array>();
+        let id = crate::call_function::<_, Integer>(
+            "jobstart",
+            (cmd, Object::from(opts)),
+        );
+
+        // Neovim holds on to the callbacks until the job exits.
+        callbacks.into_iter().for_each(Function::remove_from_lua_registry);
+        if let Some(fun) = on_exit {
+            fun.remove_from_lua_registry();
+        }
+
+        match id? {
+            0 => Err(Error::custom("invalid arguments passed to jobstart()")),
+            -1 => Err(Error::custom(format!(
+                "{:?} is not executable",
+                self.cmd.first().map(String::as_str).unwrap_or_default()
+            ))),
+            id => Ok(Job { channel: Channel::from(u32::try_from(id)?) }),
+        }
+    }
+}
+
+fn output_callback(mut on_output: OnOutput) -> Function<OutputArgs, ()> {
+    let mut lines = LineBuffer::default();
+    Function::from_fn_mut(move |(_, data, _): OutputArgs| {
+        match lines.push(data) {
+            Some(output) => on_output(output),
+            None => Ok(()),
+        }
+    })
+}
+
+/// Joins the lines split across the chunks of data read from a job.
+///
+/// Neovim splits each chunk on newlines, so its first item continues the
+/// last line of the previous chunk and its last item is the start of a
+/// line which isn't complete yet. A chunk of `[""]` means the stream was
+/// closed.
+#[derive(Default)]
+struct LineBuffer {
+    partial: String,
+}
+
+impl LineBuffer {
+    fn push(&mut self, mut data: Vec<String>) -> Option<JobOutput> {
+        if data.len() == 1 && data[0].is_empty() {
+            let last = std::mem::take(&mut self.partial);
+            let lines = if last.is_empty() { Vec::new() } else { vec![last] };
+            return Some(JobOutput { lines, is_eof: true });
+        }
+
+        let tail = data.pop()?;
+
+        if data.is_empty() {
+            self.partial.push_str(&tail);
+            return None;
+        }
+
+        data[0].insert_str(0, &std::mem::replace(&mut self.partial, tail));
+
+        Some(JobOutput { lines: data, is_eof: false })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(data: &[&str]) -> Vec<String> {
+        data.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn line_buffer_joins_chunks() {
+        let mut buf = LineBuffer::default();
+
+        assert_eq!(None, buf.push(chunk(&["fo"])));
+
+        assert_eq!(
+            Some(JobOutput { lines: chunk(&["foo", "bar"]), is_eof: false }),
+            buf.push(chunk(&["o", "bar", "ba"]))
+        );
+
+        assert_eq!(
+            Some(JobOutput { lines: chunk(&["baz"]), is_eof: false }),
+            buf.push(chunk(&["z", ""]))
+        );
+
+        assert_eq!(
+            Some(JobOutput { lines: vec![], is_eof: true }),
+            buf.push(chunk(&[""]))
+        );
+    }
+
+    #[test]
+    fn line_buffer_flushes_on_eof() {
+        let mut buf = LineBuffer::default();
+        assert_eq!(None, buf.push(chunk(&["no newline"])));
+        assert_eq!(
+            Some(JobOutput { lines: chunk(&["no newline"]), is_eof: true }),
+            buf.push(chunk(&[""]))
+        );
+    }
+}
@@ -0,0 +1,466 @@
+//! Mappings whose right-hand side is either a string or a Rust closure.
+//!
+//! A single call can map the same keys in several modes, globally or in a
+//! buffer, and optionally through a `<Plug>` mapping that other plugins and
+//! users can remap.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::api::{keymap::{self, Action, Keymap}, types::Mode};
+//!
+//! keymap::set(Mode::Normal, "<Leader>w", "<Cmd>write<CR>")?;
+//!
+//! let keymap = Keymap::builder(
+//!     vec![Mode::Normal, Mode::Visual],
+//!     "<Leader>h",
+//!     || {
+//!         nvim_oxi::print!("hello");
+//!         Ok::<_, std::convert::Infallible>(())
+//!     },
+//! )
+//! .desc("Say hello")
+//! .plug("hello")
+//! .set()?;
+//!
+//! // Removes both `<Leader>h` and `<Plug>(hello)` in both modes.
+//! keymap.del()?;
+//! ```
+
+use core::fmt;
+use std::error::Error as StdError;
+use std::rc::Rc;
+
+use types::{Function, LuaRef};
+
+use crate::opts::SetKeymapOpts;
+use crate::types::{KeymapInfos, Mode, OneOrMore};
+use crate::{Buffer, Error, Result, ToFunction};
+
+type Callback = Rc<dyn Fn() -> Result<()>>;
+type ExprCallback = Rc<dyn Fn() -> Result<String>>;
+
+/// The right-hand side of a mapping.
+#[derive(Clone)]
+pub struct Action(Rhs);
+
+#[derive(Clone)]
+enum Rhs {
+    Keys(String),
+    Callback(Callback),
+    Expr(ExprCallback),
+}
+
+impl Default for Action {
+    #[inline]
+    fn default() -> Self {
+        Self(Rhs::Keys(String::new()))
+    }
+}
+
+impl fmt::Debug for Action {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Rhs::Keys(keys) => f.debug_tuple("Keys").field(keys).finish(),
+            Rhs::Callback(_) => f.write_str("Callback"),
+            Rhs::Expr(_) => f.write_str("Expr"),
+        }
+    }
+}
+
+impl Action {
+    /// An `expr` mapping whose keys are the string returned by `fun`. The
+    /// keycodes in it like `<CR>` are replaced, unless
+    /// [`replace_keycodes`](KeymapBuilder::replace_keycodes) is set to
+    /// `false`.
+    pub fn expr<F, E>(fun: F) -> Self
+    where
+        F: Fn() -> std::result::Result<String, E> + 'static,
+        E: StdError + 'static,
+    {
+        Self(Rhs::Expr(Rc::new(move || fun().map_err(Error::custom))))
+    }
+}
+
+impl From<&str> for Action {
+    #[inline]
+    fn from(keys: &str) -> Self {
+        Self(Rhs::Keys(keys.to_owned()))
+    }
+}
+
+impl From<String> for Action {
+    #[inline]
+    fn from(keys: String) -> Self {
+        Self(Rhs::Keys(keys))
+    }
+}
+
+impl<F, E> From<F> for Action
+where
+    F: Fn() -> std::result::Result<(), E> + 'static,
+    E: StdError + 'static,
+{
+    #[inline]
+    fn from(fun: F) -> Self {
+        Self(Rhs::Callback(Rc::new(move || fun().map_err(Error::custom))))
+    }
+}
+
+/// A mapping created with [`set()`] or [`KeymapBuilder::set()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Keymap {
+    modes: Vec<Mode>,
+    lhs: String,
+    buffer: Option<Buffer>,
+    plug: Option<String>,
+}
+
+impl Keymap {
+    #[inline(always)]
+    /// Creates a new [`KeymapBuilder`] mapping `lhs` to `action` in all the
+    /// given `modes`.
+    ///
+    /// Like `vim.keymap.set()`, the mapping is not recursive unless
+    /// [`remap`](KeymapBuilder::remap) is set or the action is a string
+    /// starting with `<Plug>`.
+    pub fn builder<M, A>(modes: M, lhs: &str, action: A) -> KeymapBuilder
+    where
+        M: Into<OneOrMore<Mode>>,
+        A: Into<Action>,
+    {
+        let modes = match modes.into() {
+            OneOrMore::One(mode) => vec![mode],
+            OneOrMore::List(modes) => modes,
+        };
+
+        KeymapBuilder {
+            modes,
+            lhs: lhs.to_owned(),
+            action: action.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the modes the mapping is set in.
+    #[inline]
+    pub fn modes(&self) -> &[Mode] {
+        &self.modes
+    }
+
+    /// Returns the left-hand side of the mapping.
+    #[inline]
+    pub fn lhs(&self) -> &str {
+        &self.lhs
+    }
+
+    /// Returns the buffer the mapping is local to, if any.
+    #[inline]
+    pub fn buffer(&self) -> Option<&Buffer> {
+        self.buffer.as_ref()
+    }
+
+    /// Returns the left-hand side of the `<Plug>` mapping running the
+    /// action, e.g. `<Plug>(foo)`, if one was created.
+    #[inline]
+    pub fn plug(&self) -> Option<&str> {
+        self.plug.as_deref()
+    }
+
+    /// Deletes the mapping and its `<Plug>` mapping in all its modes.
+    pub fn del(&self) -> Result<()> {
+        if !self.lhs.is_empty() {
+            del(self.modes.clone(), &self.lhs, self.buffer.as_ref())?;
+        }
+
+        if let Some(plug) = &self.plug {
+            del(self.modes.clone(), plug, self.buffer.as_ref())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A builder for a [`Keymap`], created with [`Keymap::builder()`] or from
+/// the [`KeymapInfos`] returned by [`get_keymap()`](crate::get_keymap).
+#[derive(Clone, Debug, Default)]
+pub struct KeymapBuilder {
+    modes: Vec<Mode>,
+    lhs: String,
+    action: Action,
+    buffer: Option<Buffer>,
+    desc: Option<String>,
+    expr: bool,
+    nowait: bool,
+    plug: Option<String>,
+    remap: Option<bool>,
+    replace_keycodes: Option<bool>,
+    script: bool,
+    silent: bool,
+    unique: bool,
+}
+
+impl KeymapBuilder {
+    /// Makes the mapping local to `buffer`.
+    #[inline]
+    pub fn buffer(&mut self, buffer: Buffer) -> &mut Self {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    /// A description for the mapping.
+    #[inline]
+    pub fn desc(&mut self, desc: &str) -> &mut Self {
+        self.desc = Some(desc.to_owned());
+        self
+    }
+
+    /// Whether a string action is a Vimscript expression evaluating to the
+    /// keys of the mapping. Closures returning the keys are created with
+    /// [`Action::expr()`] instead.
+    #[inline]
+    pub fn expr(&mut self, expr: bool) -> &mut Self {
+        self.expr = expr;
+        self
+    }
+
+    /// For buffer-local mappings, whether Neovim should wait for more
+    /// characters to be typed if there's a global mapping that could also
+    /// match. See `:h map-nowait` for more details.
+    #[inline]
+    pub fn nowait(&mut self, nowait: bool) -> &mut Self {
+        self.nowait = nowait;
+        self
+    }
+
+    /// Maps the action to `<Plug>(name)` and `lhs` to that `<Plug>`
+    /// mapping, so that users can map the action to other keys. If `lhs` is
+    /// empty only the `<Plug>` mapping is created.
+    #[inline]
+    pub fn plug(&mut self, name: &str) -> &mut Self {
+        self.plug = Some(format!("<Plug>({name})"));
+        self
+    }
+
+    /// Whether the keys of the action should be remapped.
+    #[inline]
+    pub fn remap(&mut self, remap: bool) -> &mut Self {
+        self.remap = Some(remap);
+        self
+    }
+
+    /// Whether to replace the keycodes in the keys returned by an `expr`
+    /// mapping. Defaults to `true` for `expr` mappings.
+    #[inline]
+    pub fn replace_keycodes(&mut self, replace_keycodes: bool) -> &mut Self {
+        self.replace_keycodes = Some(replace_keycodes);
+        self
+    }
+
+    /// Whether to remap characters in the right-hand side by expanding the
+    /// `<sid>` script tag.
+    #[inline]
+    pub fn script(&mut self, script: bool) -> &mut Self {
+        self.script = script;
+        self
+    }
+
+    /// Whether the mapping should be silent.
+    #[inline]
+    pub fn silent(&mut self, silent: bool) -> &mut Self {
+        self.silent = silent;
+        self
+    }
+
+    /// If `true` setting the mapping will fail if another mapping with the
+    /// same left-hand side already exists.
+    #[inline]
+    pub fn unique(&mut self, unique: bool) -> &mut Self {
+        self.unique = unique;
+        self
+    }
+
+    /// Sets the mapping in all its modes.
+    pub fn set(&mut self) -> Result<Keymap> {
+        let builder = std::mem::take(self);
+
+        let keymap = Keymap {
+            modes: builder.modes.clone(),
+            lhs: builder.lhs.clone(),
+            buffer: builder.buffer.clone(),
+            plug: builder.plug.clone(),
+        };
+
+        let action_lhs = keymap.plug.as_deref().unwrap_or(&keymap.lhs);
+
+        for &mode in &keymap.modes {
+            builder.set_one(mode, action_lhs, &builder.action, None)?;
+        }
+
+        if let Some(plug) = &keymap.plug {
+            if !keymap.lhs.is_empty() {
+                let action = Action::from(plug.as_str());
+                for &mode in &keymap.modes {
+                    builder.set_one(mode, &keymap.lhs, &action, Some(true))?;
+                }
+            }
+        }
+
+        Ok(keymap)
+    }
+
+    fn set_one(
+        &self,
+        mode: Mode,
+        lhs: &str,
+        action: &Action,
+        remap: Option<bool>,
+    ) -> Result<()> {
+        let mut opts = SetKeymapOpts::builder();
+
+        let is_expr = self.expr || matches!(action.0, Rhs::Expr(_));
+
+        let rhs = match &action.0 {
+            Rhs::Keys(keys) => {
+                opts.noremap(!remap.or(self.remap).unwrap_or(is_plug(keys)));
+                keys.as_str()
+            },
+            Rhs::Callback(fun) => {
+                let fun = Rc::clone(fun);
+                opts.noremap(true).callback(LuaCallback::new(move |()| fun()));
+                ""
+            },
+            Rhs::Expr(fun) => {
+                let fun = Rc::clone(fun);
+                opts.noremap(!self.remap.unwrap_or(false))
+                    .callback(LuaCallback::new(move |()| fun()));
+                ""
+            },
+        };
+
+        if let Some(desc) = &self.desc {
+            opts.desc(desc.as_str());
+        }
+
+        if is_expr {
+            opts.expr(true)
+                .replace_keycodes(self.replace_keycodes.unwrap_or(true));
+        }
+
+        let opts = opts
+            .nowait(self.nowait)
+            .script(self.script)
+            .silent(self.silent)
+            .unique(self.unique)
+            .build();
+
+        match &self.buffer {
+            Some(buffer) => buffer.clone().set_keymap(mode, lhs, rhs, &opts),
+            None => crate::set_keymap(mode, lhs, rhs, &opts),
+        }
+    }
+}
+
+impl From<KeymapInfos> for KeymapBuilder {
+    /// Converts a mapping returned by [`get_keymap()`](crate::get_keymap)
+    /// or [`Buffer::get_keymap()`] back into a builder. Buffer-local
+    /// mappings are set in the buffer they were defined in.
+    fn from(infos: KeymapInfos) -> Self {
+        let action = match (infos.callback, infos.rhs) {
+            (Some(fun), _) if infos.expr => Action::expr(move || {
+                luajit::function::call::<(), String>(fun.lua_ref(), ())
+            }),
+            (Some(fun), _) => Action::from(move || fun.call(())),
+            (None, rhs) => Action::from(rhs.unwrap_or_default()),
+        };
+
+        Self {
+            modes: vec![infos.mode],
+            lhs: infos.lhs,
+            action,
+            buffer: infos.buffer,
+            desc: infos.desc,
+            expr: infos.expr,
+            nowait: infos.nowait,
+            remap: Some(!infos.noremap),
+            replace_keycodes: Some(infos.replace_keycodes),
+            script: infos.script,
+            silent: infos.silent,
+            ..Default::default()
+        }
+    }
+}
+
+/// Maps `lhs` to `action` in all the given `modes`, using the defaults of
+/// [`Keymap::builder()`].
+#[inline]
+pub fn set<M, A>(modes: M, lhs: &str, action: A) -> Result<Keymap>
+where
+    M: Into<OneOrMore<Mode>>,
+    A: Into<Action>,
+{
+    Keymap::builder(modes, lhs, action).set()
+}
+
+/// Deletes the mappings of `lhs` in all the given `modes`, either the
+/// global ones or the ones local to `buffer`.
+pub fn del<M>(modes: M, lhs: &str, buffer: Option<&Buffer>) -> Result<()>
+where
+    M: Into<OneOrMore<Mode>>,
+{
+    let modes = match modes.into() {
+        OneOrMore::One(mode) => vec![mode],
+        OneOrMore::List(modes) => modes,
+    };
+
+    for mode in modes {
+        match buffer {
+            Some(buffer) => buffer.clone().del_keymap(mode, lhs)?,
+            None => crate::del_keymap(mode, lhs)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// A Lua function passed to `nvim_set_keymap()`, which takes ownership of
+/// it. A new one is created for every mode since Neovim frees it when the
+/// mapping is deleted.
+struct LuaCallback(LuaRef);
+
+impl LuaCallback {
+    fn new<F, R>(fun: F) -> Self
+    where
+        F: Fn(()) -> Result<R> + 'static,
+        R: luajit::Pushable,
+    {
+        Self(Function::from_fn(fun).lua_ref())
+    }
+}
+
+impl ToFunction<(), ()> for LuaCallback {
+    #[inline]
+    fn into_luaref(self) -> LuaRef {
+        self.0
+    }
+}
+
+/// Whether `keys` start with a `<Plug>` mapping, in which case they have
+/// to be remapped.
+fn is_plug(keys: &str) -> bool {
+    keys.get(..6).is_some_and(|start| start.eq_ignore_ascii_case("<plug>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_plug_case_insensitive() {
+        assert!(is_plug("<Plug>(foo)"));
+        assert!(is_plug("<plug>(foo)"));
+        assert!(!is_plug("<Plu"));
+        assert!(!is_plug("foo<Plug>(foo)"));
+    }
+}
@@ -15,32 +15,65 @@
 
 mod autocmd;
 mod buffer;
+mod buffer_edit;
+mod buffer_mirror;
+mod channel;
 mod command;
+mod completion;
 mod deprecated;
+pub mod diagnostic;
 mod error;
 mod extmark;
 mod ffi;
+mod fold;
+mod job;
+pub mod keymap;
+#[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "neovim-0-9", feature = "neovim-nightly")))
+)]
+pub mod lsp;
+mod lua_object;
 mod options;
 pub mod opts;
+mod quickfix;
+mod register;
 pub(crate) mod serde_utils;
+pub mod signs;
+pub mod statusline;
 mod tabpage;
+mod terminal;
+pub mod theme;
 mod trait_utils;
+pub mod treesitter;
 pub mod types;
+pub mod ui;
 pub(crate) mod utils;
 mod vim;
 mod vimscript;
+pub mod widgets;
 mod win_config;
 mod window;
 
 pub use autocmd::*;
 pub use buffer::*;
+pub use buffer_edit::*;
+pub use buffer_mirror::*;
+pub use channel::*;
 pub use command::*;
+pub use completion::*;
 pub use deprecated::*;
 pub use error::Error;
 use error::Result;
 pub use extmark::*;
+pub use fold::*;
+pub use job::*;
 pub use options::*;
+pub use quickfix::*;
+pub use register::*;
 pub use tabpage::*;
+pub use terminal::*;
 pub use trait_utils::*;
 pub use vim::*;
 pub use vimscript::*;
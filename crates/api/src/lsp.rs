@@ -0,0 +1,492 @@
+//! Language servers running in the same process as Neovim.
+//!
+//! A type implementing [`LanguageServer`] is started with [`start()`], which
+//! passes [`vim.lsp.start()`][1] a `cmd` function returning the RPC client
+//! instead of the command of an external process. The requests and
+//! notifications sent by Neovim are dispatched to the server as JSON
+//! values, while the responses and the server's notifications are delivered
+//! through `vim.schedule()`, just like the messages of a real server.
+//!
+//! Only this in-process transport is supported. Messages are exchanged with
+//! Neovim's LSP client as Lua tables, so there's no `Content-Length` framing
+//! involved, and servers can't be run as separate processes talking over
+//! stdio.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::api::{lsp::{self, *}, opts::LspStartOpts};
+//! use serde_json::json;
+//!
+//! struct Hello;
+//!
+//! impl LanguageServer for Hello {
+//!     fn initialize(&mut self, _: Value, _: &Client) -> ServerResult {
+//!         Ok(json!({ "capabilities": { "hoverProvider": true } }))
+//!     }
+//!
+//!     fn request(
+//!         &mut self,
+//!         method: &str,
+//!         _: Value,
+//!         _: &Client,
+//!     ) -> ServerResult {
+//!         match method {
+//!             "textDocument/hover" => Ok(json!({ "contents": "Hello!" })),
+//!             _ => Err(ResponseError::method_not_found(method)),
+//!         }
+//!     }
+//! }
+//!
+//! let opts = LspStartOpts::builder().name("hello").build();
+//! let client_id = lsp::start(Hello, &opts)?;
+//! ```
+//!
+//! [1]: https://neovim.io/doc/user/lsp.html#vim.lsp.start()
+
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use std::error::Error as StdError;
+use std::rc::Rc;
+
+use serde::Serialize;
+pub use serde_json::Value;
+use types::serde::Serializer;
+use types::{Dictionary, Function, Integer, Object};
+
+use crate::lua_object::LuaObject;
+use crate::opts::LspStartOpts;
+use crate::utils::{call_vim_function, schedule};
+use crate::{Error, Result};
+
+/// The result of a request handled by a [`LanguageServer`].
+pub type ServerResult<T = Value> = std::result::Result<T, ResponseError>;
+
+/// A language server running inside Neovim.
+///
+/// The server is only sent the `initialize` request once, and no other
+/// requests before that. After the `shutdown` request every other request
+/// fails with [`ResponseError::INVALID_REQUEST`].
+pub trait LanguageServer: 'static {
+    /// Handles the `initialize` request, returning the `InitializeResult`
+    /// containing the capabilities of the server.
+    fn initialize(&mut self, params: Value, client: &Client) -> ServerResult;
+
+    /// Handles the `initialized` notification.
+    #[inline]
+    fn initialized(&mut self, client: &Client) {
+        let _ = client;
+    }
+
+    /// Handles every other request, like the `textDocument/*` and
+    /// `workspace/*` ones.
+    ///
+    /// Fails with [`ResponseError::METHOD_NOT_FOUND`] by default.
+    #[inline]
+    fn request(
+        &mut self,
+        method: &str,
+        params: Value,
+        client: &Client,
+    ) -> ServerResult {
+        let _ = (params, client);
+        Err(ResponseError::method_not_found(method))
+    }
+
+    /// Handles every other notification, like `textDocument/didOpen` or
+    /// `workspace/didChangeConfiguration`.
+    #[inline]
+    fn notification(&mut self, method: &str, params: Value, client: &Client) {
+        let _ = (method, params, client);
+    }
+
+    /// Handles the `shutdown` request.
+    #[inline]
+    fn shutdown(&mut self) -> ServerResult<()> {
+        Ok(())
+    }
+}
+
+/// The error of a failed request, i.e. the `ResponseError` of the
+/// specification.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ResponseError {
+    /// The error code, either one of the constants defined on this type or
+    /// an application-specific one.
+    pub code: i64,
+
+    /// A short description of the error.
+    pub message: String,
+
+    /// Additional informations about the error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl ResponseError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    pub const SERVER_NOT_INITIALIZED: i64 = -32002;
+    pub const REQUEST_FAILED: i64 = -32803;
+
+    #[inline]
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), data: None }
+    }
+
+    /// An error for a method the server doesn't support.
+    #[inline]
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(Self::METHOD_NOT_FOUND, format!("unknown method {method}"))
+    }
+
+    /// An error for parameters that couldn't be deserialized.
+    #[inline]
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    /// An error for a request the server failed to handle.
+    #[inline]
+    pub fn internal_error(message: impl Into<String>) -> Self {
+        Self::new(Self::INTERNAL_ERROR, message)
+    }
+}
+
+impl fmt::Display for ResponseError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+impl StdError for ResponseError {}
+
+/// Allows using `?` on the result of `serde_json::from_value(params)`.
+impl From<serde_json::Error> for ResponseError {
+    #[inline]
+    fn from(err: serde_json::Error) -> Self {
+        Self::invalid_params(err.to_string())
+    }
+}
+
+/// The Neovim client a [`LanguageServer`] is connected to.
+#[derive(Clone)]
+pub struct Client {
+    dispatchers: LuaObject,
+    state: Rc<Cell<State>>,
+}
+
+impl fmt::Debug for Client {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client").field("state", &self.state.get()).finish()
+    }
+}
+
+impl Client {
+    /// Sends a notification to the client, like
+    /// `textDocument/publishDiagnostics` or `window/showMessage`.
+    ///
+    /// The notification is delivered by the main event loop once the
+    /// current message has been handled. Notifications sent after the
+    /// server has exited are discarded.
+    pub fn notify(&self, method: &str, params: Value) -> Result<()> {
+        let dispatchers = self.dispatchers.clone();
+        let state = Rc::clone(&self.state);
+        let method = method.to_owned();
+
+        schedule(move |()| {
+            if state.get() == State::Exited {
+                return Ok(());
+            }
+            dispatchers.call_field(
+                "notification",
+                (method, json_to_object(params)),
+                0,
+            )
+        })
+    }
+
+    /// Returns whether the server has exited.
+    #[inline]
+    pub fn is_closing(&self) -> bool {
+        self.state.get() == State::Exited
+    }
+}
+
+/// The lifecycle of a server.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum State {
+    /// The server hasn't received the `initialize` request yet.
+    Uninitialized,
+
+    /// The server has been initialized.
+    Running,
+
+    /// The server has received the `shutdown` request.
+    ShutDown,
+
+    /// The server has received the `exit` notification or has been
+    /// terminated.
+    Exited,
+}
+
+/// The RPC client returned by the `cmd` function.
+struct Rpc<S> {
+    server: RefCell<S>,
+    client: Client,
+    next_id: Cell<Integer>,
+}
+
+impl<S: LanguageServer> Rpc<S> {
+    fn request(&self, method: &str, params: Value) -> ServerResult {
+        let client = &self.client;
+
+        let mut server = self.server.try_borrow_mut().map_err(|_| {
+            ResponseError::internal_error(
+                "the server is already handling a message",
+            )
+        })?;
+
+        match (client.state.get(), method) {
+            (State::Uninitialized, "initialize") => {
+                let result = server.initialize(params, client)?;
+                client.state.set(State::Running);
+                Ok(result)
+            },
+
+            (State::Uninitialized, _) => Err(ResponseError::new(
+                ResponseError::SERVER_NOT_INITIALIZED,
+                "the server hasn't been initialized",
+            )),
+
+            (State::Running, "initialize") => Err(ResponseError::new(
+                ResponseError::INVALID_REQUEST,
+                "the server has already been initialized",
+            )),
+
+            (State::Running, "shutdown") => {
+                server.shutdown()?;
+                client.state.set(State::ShutDown);
+                Ok(Value::Null)
+            },
+
+            (State::Running, _) => server.request(method, params, client),
+
+            (State::ShutDown | State::Exited, _) => Err(ResponseError::new(
+                ResponseError::INVALID_REQUEST,
+                "the server has been shut down",
+            )),
+        }
+    }
+
+    fn notification(&self, method: &str, params: Value) -> Result<()> {
+        let client = &self.client;
+
+        match (client.state.get(), method) {
+            (State::Exited, _) => Ok(()),
+
+            (_, "exit") => self.exit(),
+
+            (State::Running, "initialized") => {
+                self.server
+                    .try_borrow_mut()
+                    .map_err(Error::custom)?
+                    .initialized(client);
+                Ok(())
+            },
+
+            (State::Running, _) => {
+                self.server
+                    .try_borrow_mut()
+                    .map_err(Error::custom)?
+                    .notification(method, params, client);
+                Ok(())
+            },
+
+            // Notifications other than `exit` are dropped before the
+            // `initialize` request and after the `shutdown` one.
+            (State::Uninitialized | State::ShutDown, _) => Ok(()),
+        }
+    }
+
+    /// Marks the server as exited and tells the client about it. The exit
+    /// code is 1 if the server wasn't shut down first.
+    fn exit(&self) -> Result<()> {
+        let client = &self.client;
+
+        let code = match client.state.replace(State::Exited) {
+            State::Exited => return Ok(()),
+            State::ShutDown => 0,
+            _ => 1,
+        } as Integer;
+
+        let dispatchers = client.dispatchers.clone();
+
+        schedule(move |()| dispatchers.call_field("on_exit", (code, 0), 0))
+    }
+}
+
+/// Starts `server` and attaches it to the current buffer, returning the id
+/// of the client connected to it, like [`vim.lsp.start()`][1].
+///
+/// If a client with the same [`name`](crate::opts::LspStartOptsBuilder::name)
+/// and [`root_dir`](crate::opts::LspStartOptsBuilder::root_dir) is already
+/// running it's reused and `server` is never initialized.
+///
+/// [1]: https://neovim.io/doc/user/lsp.html#vim.lsp.start()
+pub fn start<S>(server: S, opts: &LspStartOpts) -> Result<Option<u32>>
+where
+    S: LanguageServer,
+{
+    let cmd = Function::<Dispatchers, Dictionary>::from_fn_once(
+        move |Dispatchers(dispatchers)| {
+            Ok::<_, Error>(rpc_client(server, dispatchers))
+        },
+    );
+
+    let mut config = Dictionary::from(opts);
+    config.insert("cmd", cmd);
+
+    let id: Option<Integer> = call_vim_function("lsp.start", (config,))?;
+
+    Ok(id.map(|id| id as u32))
+}
+
+/// The `dispatchers` table passed to the `cmd` function.
+///
+/// Newer versions of Neovim also pass the client config as a second
+/// argument, which is discarded since arguments are popped from the top of
+/// the stack.
+struct Dispatchers(LuaObject);
+
+impl luajit::Poppable for Dispatchers {
+    unsafe fn pop(
+        lstate: *mut luajit::ffi::lua_State,
+    ) -> core::result::Result<Self, luajit::Error> {
+        if luajit::ffi::lua_gettop(lstate) > 1 {
+            luajit::ffi::lua_settop(lstate, 1);
+        }
+        LuaObject::pop(lstate).map(Self)
+    }
+}
+
+/// Creates the RPC client returned by the `cmd` function, i.e. a table with
+/// the `request`, `notify`, `is_closing` and `terminate` functions.
+fn rpc_client<S>(server: S, dispatchers: LuaObject) -> Dictionary
+where
+    S: LanguageServer,
+{
+    let rpc = Rc::new(Rpc {
+        server: RefCell::new(server),
+        client: Client {
+            dispatchers,
+            state: Rc::new(Cell::new(State::Uninitialized)),
+        },
+        next_id: Cell::new(1),
+    });
+
+    let request = {
+        let rpc = Rc::clone(&rpc);
+        Function::<Request, (bool, Option<Integer>)>::from_fn(
+            move |(method, params, callback, notify_reply_callback)| {
+                if rpc.client.is_closing() {
+                    return Ok::<_, Error>((false, None));
+                }
+
+                let id = rpc.next_id.get();
+                rpc.next_id.set(id + 1);
+
+                let response = decode_params(params)
+                    .map_err(|err| {
+                        ResponseError::invalid_params(err.to_string())
+                    })
+                    .and_then(|params| rpc.request(&method, params));
+
+                let (err, result) = match response {
+                    Ok(result) => (Object::nil(), json_to_object(result)),
+                    Err(err) => (error_to_object(&err), Object::nil()),
+                };
+
+                schedule(move |()| {
+                    callback.call((err, result)).map_err(Error::custom)?;
+                    if let Some(notify_reply) = notify_reply_callback {
+                        notify_reply.call(id).map_err(Error::custom)?;
+                    }
+                    Ok(())
+                })?;
+
+                Ok((true, Some(id)))
+            },
+        )
+    };
+
+    let notify = {
+        let rpc = Rc::clone(&rpc);
+        Function::<(String, Option<LuaObject>), bool>::from_fn(
+            move |(method, params)| {
+                if rpc.client.is_closing() {
+                    return Ok::<_, Error>(false);
+                }
+                rpc.notification(&method, decode_params(params)?)?;
+                Ok(true)
+            },
+        )
+    };
+
+    let is_closing = {
+        let rpc = Rc::clone(&rpc);
+        Function::<(), bool>::from_fn(move |()| {
+            Ok::<_, Error>(rpc.client.is_closing())
+        })
+    };
+
+    let terminate = Function::<(), ()>::from_fn(move |()| rpc.exit());
+
+    Dictionary::from_iter([
+        ("request", Object::from(request)),
+        ("notify", Object::from(notify)),
+        ("is_closing", Object::from(is_closing)),
+        ("terminate", Object::from(terminate)),
+    ])
+}
+
+/// The arguments of the `request` function of the RPC client, i.e. the
+/// method, its parameters, the callback called with the response and the
+/// one called after it.
+type Request = (
+    String,
+    Option<LuaObject>,
+    Function<(Object, Object), ()>,
+    Option<Function<Integer, ()>>,
+);
+
+/// Decodes the parameters of a message.
+///
+/// They're encoded with `vim.json.encode()` since they can contain values
+/// that can't be converted to an [`Object`], like `vim.NIL`.
+fn decode_params(params: Option<LuaObject>) -> Result<Value> {
+    let params = match params {
+        Some(params) => params,
+        None => return Ok(Value::Null),
+    };
+
+    let json: String = call_vim_function("json.encode", (params,))?;
+
+    serde_json::from_str(&json).map_err(Error::custom)
+}
+
+fn error_to_object(err: &ResponseError) -> Object {
+    err.serialize(Serializer::new()).unwrap_or_default()
+}
+
+/// Converts a JSON value into an [`Object`]. `null`s become `nil`.
+pub(crate) fn json_to_object(value: Value) -> Object {
+    // Serializing a `Value` can only fail on integers that don't fit in an
+    // `i64`, which can't be represented in Lua anyway.
+    value.serialize(Serializer::new()).unwrap_or_default()
+}
@@ -0,0 +1,234 @@
+use std::ffi::{c_int, CString};
+use std::marker::PhantomData;
+use std::result::Result as StdResult;
+
+use luajit::{ffi::*, Poppable, Pushable};
+
+use crate::utils::{pcall, push_vim_field};
+use crate::{Error, Result};
+
+/// A Lua value stored in the registry, which keeps it alive until the
+/// object is dropped.
+///
+/// This is used to hold on to values that can't be converted to an
+/// [`Object`](types::Object), like userdata or tables with metatables.
+pub(crate) struct LuaObject {
+    lua_ref: c_int,
+
+    // Lua values can only be accessed from the thread running Neovim.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl LuaObject {
+    /// Returns the value at the given path in the `vim` global table, e.g.
+    /// `"treesitter.query"` for `vim.treesitter.query`.
+    pub(crate) fn from_vim_field(path: &str) -> Result<Self> {
+        unsafe {
+            luajit::with_state(|lstate| {
+                let top = lua_gettop(lstate);
+                let ret = push_vim_field(lstate, path)
+                    .and_then(|()| Self::pop(lstate).map_err(Error::custom));
+                lua_settop(lstate, top);
+                ret
+            })
+        }
+    }
+
+    /// Returns the value of the global variable `name`, e.g. `"tostring"`.
+    pub(crate) fn from_global(name: &str) -> Result<Self> {
+        let name = CString::new(name).map_err(Error::custom)?;
+
+        unsafe {
+            luajit::with_state(|lstate| {
+                lua_getglobal(lstate, name.as_ptr());
+                Self::pop(lstate).map_err(Error::custom)
+            })
+        }
+    }
+
+    /// Calls the object as a function.
+    pub(crate) fn call<A, R>(&self, args: A, nresults: c_int) -> Result<R>
+    where
+        A: Pushable,
+        R: Poppable,
+    {
+        unsafe {
+            self.with_pushed(|lstate| {
+                let nargs = args.push(lstate).map_err(Error::custom)?;
+                call_and_pop(lstate, nargs, nresults)
+            })
+        }
+    }
+
+    /// Calls the method `name` of the object, i.e. `object:name(args...)`,
+    /// popping `nresults` values off the stack as `R`.
+    pub(crate) fn call_method<A, R>(
+        &self,
+        name: &str,
+        args: A,
+        nresults: c_int,
+    ) -> Result<R>
+    where
+        A: Pushable,
+        R: Poppable,
+    {
+        let name = CString::new(name).map_err(Error::custom)?;
+
+        unsafe {
+            self.with_pushed(|lstate| {
+                self.push_field(lstate, &name)?;
+                lua_pushvalue(lstate, -2);
+                let nargs = args.push(lstate).map_err(Error::custom)?;
+                call_and_pop(lstate, nargs + 1, nresults)
+            })
+        }
+    }
+
+    /// Calls the function stored in the field `name` of the object, i.e.
+    /// `object.name(args...)`.
+    pub(crate) fn call_field<A, R>(
+        &self,
+        name: &str,
+        args: A,
+        nresults: c_int,
+    ) -> Result<R>
+    where
+        A: Pushable,
+        R: Poppable,
+    {
+        let name = CString::new(name).map_err(Error::custom)?;
+
+        unsafe {
+            self.with_pushed(|lstate| {
+                self.push_field(lstate, &name)?;
+                let nargs = args.push(lstate).map_err(Error::custom)?;
+                call_and_pop(lstate, nargs, nresults)
+            })
+        }
+    }
+
+    /// Returns the field `name` of the object.
+    pub(crate) fn get<R: Poppable>(&self, name: &str) -> Result<R> {
+        let name = CString::new(name).map_err(Error::custom)?;
+
+        unsafe {
+            self.with_pushed(|lstate| {
+                lua_getfield(lstate, -1, name.as_ptr());
+                R::pop(lstate).map_err(Error::custom)
+            })
+        }
+    }
+
+    /// Pushes the field `name` of the object at the top of the stack,
+    /// checking that it's a function.
+    unsafe fn push_field(
+        &self,
+        lstate: *mut lua_State,
+        name: &CString,
+    ) -> Result<()> {
+        lua_getfield(lstate, -1, name.as_ptr());
+
+        if lua_type(lstate, -1) == LUA_TFUNCTION {
+            Ok(())
+        } else {
+            Err(Error::custom(format!(
+                "{} is not a function",
+                name.to_string_lossy()
+            )))
+        }
+    }
+
+    /// Pushes the object onto the stack and calls `fun`, restoring the stack
+    /// afterwards.
+    fn with_pushed<F, R>(&self, fun: F) -> Result<R>
+    where
+        F: FnOnce(*mut lua_State) -> Result<R>,
+    {
+        unsafe {
+            luajit::with_state(|lstate| {
+                let top = lua_gettop(lstate);
+                lua_rawgeti(lstate, LUA_REGISTRYINDEX, self.lua_ref);
+                let ret = fun(lstate);
+                lua_settop(lstate, top);
+                ret
+            })
+        }
+    }
+}
+
+/// Calls the function on the stack below its `nargs` arguments and pops its
+/// `nresults` return values as `R`.
+unsafe fn call_and_pop<R: Poppable>(
+    lstate: *mut lua_State,
+    nargs: c_int,
+    nresults: c_int,
+) -> Result<R> {
+    pcall(lstate, nargs, nresults)?;
+
+    // Without any results `R` would be popped from the values below the
+    // function, like the object itself.
+    if nresults == 0 {
+        lua_pushnil(lstate);
+    }
+
+    R::pop(lstate).map_err(Error::custom)
+}
+
+impl Clone for LuaObject {
+    fn clone(&self) -> Self {
+        unsafe {
+            luajit::with_state(|lstate| {
+                lua_rawgeti(lstate, LUA_REGISTRYINDEX, self.lua_ref);
+                Self {
+                    lua_ref: luaL_ref(lstate, LUA_REGISTRYINDEX),
+                    _not_send: PhantomData,
+                }
+            })
+        }
+    }
+}
+
+impl Drop for LuaObject {
+    fn drop(&mut self) {
+        unsafe {
+            luajit::with_state(|lstate| {
+                luaL_unref(lstate, LUA_REGISTRYINDEX, self.lua_ref)
+            })
+        }
+    }
+}
+
+impl core::fmt::Debug for LuaObject {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("LuaObject").field(&self.lua_ref).finish()
+    }
+}
+
+impl Poppable for LuaObject {
+    unsafe fn pop(lstate: *mut lua_State) -> StdResult<Self, luajit::Error> {
+        match lua_type(lstate, -1) {
+            LUA_TNONE => Err(luajit::Error::PopEmptyStack),
+
+            LUA_TNIL => {
+                lua_pop(lstate, 1);
+                Err(luajit::Error::pop_error("LuaObject", "value is nil"))
+            },
+
+            // Pops the value off the stack.
+            _ => Ok(Self {
+                lua_ref: luaL_ref(lstate, LUA_REGISTRYINDEX),
+                _not_send: PhantomData,
+            }),
+        }
+    }
+}
+
+impl Pushable for LuaObject {
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> StdResult<c_int, luajit::Error> {
+        lua_rawgeti(lstate, LUA_REGISTRYINDEX, self.lua_ref);
+        Ok(1)
+    }
+}
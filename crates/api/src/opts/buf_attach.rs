@@ -16,6 +16,9 @@ use crate::ToFunction;
 /// [`utf_sizes`](BufAttachOptsBuilder::utf_sizes) was `true`);
 /// - `i`: deleted UTF-16 codeunits (if
 /// [`utf_sizes`](BufAttachOptsBuilder::utf_sizes) was `true`);
+///
+/// The tuple can be converted into a [`LinesChange`](crate::types::LinesChange)
+/// to access its elements by name.
 pub type OnLinesArgs = (
     String,
     Buffer,
@@ -41,6 +44,9 @@ pub type OnLinesArgs = (
 /// - `j`: number of rows added;
 /// - `k`: number of columns added;
 /// - `l`: number of bytes added;
+///
+/// The tuple can be converted into a [`BytesChange`](crate::types::BytesChange)
+/// to access its elements by name.
 pub type OnBytesArgs = (
     String,
     Buffer,
@@ -0,0 +1,156 @@
+use types::{Dictionary, Integer, Object};
+
+use crate::types::DiagnosticSeverity;
+
+/// Options passed to [`diagnostic::config()`](crate::diagnostic::config).
+/// Only the options that are set are modified.
+#[derive(Clone, Debug, Default)]
+pub struct DiagnosticConfigOpts {
+    float: Object,
+    severity_sort: Object,
+    signs: Object,
+    underline: Object,
+    update_in_insert: Object,
+    virtual_text: Object,
+}
+
+impl DiagnosticConfigOpts {
+    #[inline(always)]
+    /// Creates a new [`DiagnosticConfigOptsBuilder`].
+    pub fn builder() -> DiagnosticConfigOptsBuilder {
+        DiagnosticConfigOptsBuilder::default()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct DiagnosticConfigOptsBuilder(DiagnosticConfigOpts);
+
+impl DiagnosticConfigOptsBuilder {
+    /// Whether to enable the floating window opened by
+    /// `vim.diagnostic.open_float()`.
+    #[inline]
+    pub fn float(&mut self, float: bool) -> &mut Self {
+        self.0.float = float.into();
+        self
+    }
+
+    /// Whether to sort diagnostics by severity, which affects the order in
+    /// which signs and virtual text are displayed. If `reverse` is `true`
+    /// the most severe diagnostics are displayed last.
+    #[inline]
+    pub fn severity_sort(&mut self, sort: bool, reverse: bool) -> &mut Self {
+        self.0.severity_sort = if sort && reverse {
+            Dictionary::from_iter([("reverse", true)]).into()
+        } else {
+            sort.into()
+        };
+        self
+    }
+
+    /// Whether to display signs in the sign column.
+    #[inline]
+    pub fn signs(&mut self, signs: bool) -> &mut Self {
+        self.0.signs = signs.into();
+        self
+    }
+
+    /// Whether to underline the range of the diagnostics.
+    #[inline]
+    pub fn underline(&mut self, underline: bool) -> &mut Self {
+        self.0.underline = underline.into();
+        self
+    }
+
+    /// Whether to update diagnostics in Insert mode. If `false`, they're
+    /// updated on `InsertLeave`.
+    #[inline]
+    pub fn update_in_insert(&mut self, update: bool) -> &mut Self {
+        self.0.update_in_insert = update.into();
+        self
+    }
+
+    /// Whether to display the messages of the diagnostics as virtual text.
+    #[inline]
+    pub fn virtual_text(&mut self, virtual_text: bool) -> &mut Self {
+        self.0.virtual_text = virtual_text.into();
+        self
+    }
+
+    /// Enables virtual text with the given options.
+    #[inline]
+    pub fn virtual_text_opts(
+        &mut self,
+        opts: &DiagnosticVirtualTextOpts,
+    ) -> &mut Self {
+        self.0.virtual_text = Dictionary::from(opts).into();
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> DiagnosticConfigOpts {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl From<&DiagnosticConfigOpts> for Dictionary {
+    fn from(opts: &DiagnosticConfigOpts) -> Self {
+        [
+            ("float", &opts.float),
+            ("severity_sort", &opts.severity_sort),
+            ("signs", &opts.signs),
+            ("underline", &opts.underline),
+            ("update_in_insert", &opts.update_in_insert),
+            ("virtual_text", &opts.virtual_text),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.is_nil())
+        .map(|(key, value)| (key, value.clone()))
+        .collect()
+    }
+}
+
+/// Options for the virtual text of diagnostics, passed to
+/// [`DiagnosticConfigOptsBuilder::virtual_text_opts()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct DiagnosticVirtualTextOpts {
+    /// Only display virtual text for diagnostics at least this severe.
+    pub min_severity: Option<DiagnosticSeverity>,
+
+    /// The text prepended to the message.
+    pub prefix: Option<String>,
+
+    /// Whether to include the source of the diagnostic in the message.
+    pub source: Option<bool>,
+
+    /// The number of spaces between the end of the line and the virtual
+    /// text.
+    pub spacing: Option<u32>,
+
+    /// The text appended to the message.
+    pub suffix: Option<String>,
+}
+
+impl From<&DiagnosticVirtualTextOpts> for Dictionary {
+    fn from(opts: &DiagnosticVirtualTextOpts) -> Self {
+        let mut dict = Dictionary::new();
+
+        if let Some(min) = opts.min_severity {
+            let severity = Dictionary::from_iter([("min", min)]);
+            dict.insert("severity", severity);
+        }
+        if let Some(prefix) = &opts.prefix {
+            dict.insert("prefix", prefix.as_str());
+        }
+        if let Some(source) = opts.source {
+            dict.insert("source", source);
+        }
+        if let Some(spacing) = opts.spacing {
+            dict.insert("spacing", spacing as Integer);
+        }
+        if let Some(suffix) = &opts.suffix {
+            dict.insert("suffix", suffix.as_str());
+        }
+
+        dict
+    }
+}
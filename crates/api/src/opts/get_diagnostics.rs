@@ -0,0 +1,77 @@
+use types::{Dictionary, Integer, Object};
+
+use crate::types::DiagnosticSeverity;
+
+/// Options passed to [`diagnostic::get()`](crate::diagnostic::get).
+#[derive(Clone, Debug, Default)]
+pub struct GetDiagnosticsOpts {
+    lnum: Object,
+    namespace: Object,
+    severity: Object,
+}
+
+impl GetDiagnosticsOpts {
+    #[inline(always)]
+    /// Creates a new [`GetDiagnosticsOptsBuilder`].
+    pub fn builder() -> GetDiagnosticsOptsBuilder {
+        GetDiagnosticsOptsBuilder::default()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct GetDiagnosticsOptsBuilder(GetDiagnosticsOpts);
+
+impl GetDiagnosticsOptsBuilder {
+    /// Only return the diagnostics on the given 0-indexed line.
+    #[inline]
+    pub fn lnum(&mut self, lnum: usize) -> &mut Self {
+        self.0.lnum = (lnum as Integer).into();
+        self
+    }
+
+    /// Only return the diagnostics in the given namespace.
+    #[inline]
+    pub fn namespace(&mut self, namespace: u32) -> &mut Self {
+        self.0.namespace = (namespace as Integer).into();
+        self
+    }
+
+    /// Only return the diagnostics with the given severity.
+    #[inline]
+    pub fn severity(&mut self, severity: DiagnosticSeverity) -> &mut Self {
+        self.0.severity = severity.into();
+        self
+    }
+
+    /// Only return the diagnostics with a severity between `min` and `max`,
+    /// both inclusive.
+    #[inline]
+    pub fn severity_range(
+        &mut self,
+        min: DiagnosticSeverity,
+        max: DiagnosticSeverity,
+    ) -> &mut Self {
+        self.0.severity =
+            Dictionary::from_iter([("min", min), ("max", max)]).into();
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> GetDiagnosticsOpts {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl From<&GetDiagnosticsOpts> for Dictionary {
+    fn from(opts: &GetDiagnosticsOpts) -> Self {
+        [
+            ("lnum", &opts.lnum),
+            ("namespace", &opts.namespace),
+            ("severity", &opts.severity),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.is_nil())
+        .map(|(key, value)| (key, value.clone()))
+        .collect()
+    }
+}
@@ -0,0 +1,63 @@
+use types::{Dictionary, Object};
+
+/// Options passed to [`ui::input()`](crate::ui::input).
+#[derive(Clone, Debug, Default)]
+pub struct InputOpts {
+    pub(crate) completion: Object,
+    pub(crate) default: Object,
+    pub(crate) prompt: Object,
+}
+
+impl InputOpts {
+    #[inline(always)]
+    /// Creates a new [`InputOptsBuilder`].
+    pub fn builder() -> InputOptsBuilder {
+        InputOptsBuilder(InputOpts::default())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InputOptsBuilder(InputOpts);
+
+impl InputOptsBuilder {
+    /// The kind of completion used for the input, like the `-complete`
+    /// argument of user commands, e.g. `"file"` or `"customlist,MyFunc"`.
+    #[inline]
+    pub fn completion(&mut self, completion: &str) -> &mut Self {
+        self.0.completion = completion.into();
+        self
+    }
+
+    /// The text the input starts with.
+    #[inline]
+    pub fn default(&mut self, default: &str) -> &mut Self {
+        self.0.default = default.into();
+        self
+    }
+
+    /// The text displayed before the input.
+    #[inline]
+    pub fn prompt(&mut self, prompt: &str) -> &mut Self {
+        self.0.prompt = prompt.into();
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> InputOpts {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl From<&InputOpts> for Dictionary {
+    fn from(opts: &InputOpts) -> Self {
+        [
+            ("completion", &opts.completion),
+            ("default", &opts.default),
+            ("prompt", &opts.prompt),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.is_nil())
+        .map(|(key, value)| (key, value.clone()))
+        .collect()
+    }
+}
@@ -0,0 +1,79 @@
+use serde_json::Value;
+use types::{Dictionary, Object};
+
+use crate::lsp::json_to_object;
+
+/// Options passed to [`lsp::start()`](crate::lsp::start), i.e. the
+/// configuration of the client connected to the server.
+#[derive(Clone, Debug, Default)]
+pub struct LspStartOpts {
+    init_options: Object,
+    name: Object,
+    root_dir: Object,
+    settings: Object,
+}
+
+impl LspStartOpts {
+    #[inline(always)]
+    /// Creates a new [`LspStartOptsBuilder`].
+    pub fn builder() -> LspStartOptsBuilder {
+        LspStartOptsBuilder::default()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct LspStartOptsBuilder(LspStartOpts);
+
+impl LspStartOptsBuilder {
+    /// The `initializationOptions` sent to the server in the `initialize`
+    /// request.
+    #[inline]
+    pub fn init_options(&mut self, init_options: Value) -> &mut Self {
+        self.0.init_options = json_to_object(init_options);
+        self
+    }
+
+    /// The name of the client, used in log messages. A running client with
+    /// the same name and root directory is reused instead of starting a new
+    /// server.
+    #[inline]
+    pub fn name(&mut self, name: &str) -> &mut Self {
+        self.0.name = name.into();
+        self
+    }
+
+    /// The directory sent to the server as the root of the workspace.
+    #[inline]
+    pub fn root_dir(&mut self, root_dir: &str) -> &mut Self {
+        self.0.root_dir = root_dir.into();
+        self
+    }
+
+    /// The settings returned to the server when it asks for the
+    /// `workspace/configuration`.
+    #[inline]
+    pub fn settings(&mut self, settings: Value) -> &mut Self {
+        self.0.settings = json_to_object(settings);
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> LspStartOpts {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl From<&LspStartOpts> for Dictionary {
+    fn from(opts: &LspStartOpts) -> Self {
+        [
+            ("init_options", &opts.init_options),
+            ("name", &opts.name),
+            ("root_dir", &opts.root_dir),
+            ("settings", &opts.settings),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.is_nil())
+        .map(|(key, value)| (key, value.clone()))
+        .collect()
+    }
+}
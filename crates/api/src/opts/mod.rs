@@ -9,6 +9,7 @@ mod create_augroup;
 mod create_autocmd;
 mod create_command;
 mod decoration_provider;
+mod diagnostic_config;
 mod echo;
 mod eval_statusline;
 #[cfg(feature = "neovim-nightly")]
@@ -17,19 +18,25 @@ mod exec_autocmds;
 mod get_autocmds;
 mod get_commands;
 mod get_context;
+mod get_diagnostics;
 mod get_extmark_by_id;
 mod get_extmarks;
 mod get_mark;
 mod get_text;
+mod input;
+#[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+mod lsp_start;
 mod notify;
 mod open_term;
 mod option;
 mod parse_cmd;
+mod select;
 mod select_popup_menu_item;
 mod set_extmark;
 mod set_highlight;
 mod set_keymap;
 mod set_mark;
+mod set_qflist;
 #[cfg(feature = "neovim-nightly")]
 mod win_text_height;
 
@@ -41,6 +48,7 @@ pub use create_augroup::*;
 pub use create_autocmd::*;
 pub use create_command::*;
 pub use decoration_provider::*;
+pub use diagnostic_config::*;
 pub use echo::*;
 pub use eval_statusline::*;
 #[cfg(feature = "neovim-nightly")]
@@ -49,18 +57,24 @@ pub use exec_autocmds::*;
 pub use get_autocmds::*;
 pub use get_commands::*;
 pub use get_context::*;
+pub use get_diagnostics::*;
 pub use get_extmark_by_id::*;
 pub use get_extmarks::*;
 pub use get_mark::*;
 pub use get_text::*;
+pub use input::*;
+#[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+pub use lsp_start::*;
 pub use notify::*;
 pub use open_term::*;
 pub use option::*;
 pub use parse_cmd::*;
+pub use select::*;
 pub use select_popup_menu_item::*;
 pub use set_extmark::*;
 pub use set_highlight::*;
 pub use set_keymap::*;
 pub use set_mark::*;
+pub use set_qflist::*;
 #[cfg(feature = "neovim-nightly")]
 pub use win_text_height::*;
@@ -0,0 +1,128 @@
+use core::fmt;
+use std::rc::Rc;
+
+use types::{Dictionary, Object};
+
+/// The function used to display the items passed to `ui::select()`.
+pub(crate) type FormatItem<T> = Rc<dyn Fn(&T) -> String>;
+
+/// Options passed to [`ui::select()`](crate::ui::select).
+pub struct SelectOpts<T> {
+    pub(crate) prompt: Object,
+    kind: Object,
+    pub(crate) format_item: FormatItem<T>,
+}
+
+impl<T: fmt::Display + 'static> SelectOpts<T> {
+    #[inline(always)]
+    /// Creates a new [`SelectOptsBuilder`] displaying the items with their
+    /// [`Display`](fmt::Display) implementation.
+    pub fn builder() -> SelectOptsBuilder<T> {
+        SelectOptsBuilder::default()
+    }
+}
+
+impl<T> SelectOpts<T> {
+    #[inline(always)]
+    /// Creates a new [`SelectOptsBuilder`] displaying the items with
+    /// `format_item`, for items which don't implement
+    /// [`Display`](fmt::Display).
+    pub fn builder_with<F>(format_item: F) -> SelectOptsBuilder<T>
+    where
+        F: Fn(&T) -> String + 'static,
+    {
+        SelectOptsBuilder(Self::with_format_item(Rc::new(format_item)))
+    }
+
+    #[inline]
+    fn with_format_item(format_item: FormatItem<T>) -> Self {
+        Self { prompt: Object::nil(), kind: Object::nil(), format_item }
+    }
+}
+
+impl<T> Clone for SelectOpts<T> {
+    fn clone(&self) -> Self {
+        Self {
+            prompt: self.prompt.clone(),
+            kind: self.kind.clone(),
+            format_item: self.format_item.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Display + 'static> Default for SelectOpts<T> {
+    fn default() -> Self {
+        Self::with_format_item(Rc::new(|item: &T| item.to_string()))
+    }
+}
+
+impl<T> fmt::Debug for SelectOpts<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelectOpts")
+            .field("prompt", &self.prompt)
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
+
+pub struct SelectOptsBuilder<T>(SelectOpts<T>);
+
+impl<T> Clone for SelectOptsBuilder<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: fmt::Display + 'static> Default for SelectOptsBuilder<T> {
+    fn default() -> Self {
+        Self(SelectOpts::default())
+    }
+}
+
+impl<T> SelectOptsBuilder<T> {
+    /// The text displayed above the items.
+    #[inline]
+    pub fn prompt(&mut self, prompt: &str) -> &mut Self {
+        self.0.prompt = prompt.into();
+        self
+    }
+
+    /// A hint about the kind of the items, which implementations of
+    /// `vim.ui.select()` can use to display them differently, e.g.
+    /// `"codeaction"`.
+    #[inline]
+    pub fn kind(&mut self, kind: &str) -> &mut Self {
+        self.0.kind = kind.into();
+        self
+    }
+
+    /// The function used to display the items, which defaults to their
+    /// [`Display`](fmt::Display) implementation.
+    #[inline]
+    pub fn format_item<F>(&mut self, fun: F) -> &mut Self
+    where
+        F: Fn(&T) -> String + 'static,
+    {
+        self.0.format_item = Rc::new(fun);
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> SelectOpts<T> {
+        let format_item = Rc::clone(&self.0.format_item);
+        std::mem::replace(
+            &mut self.0,
+            SelectOpts::with_format_item(format_item),
+        )
+    }
+}
+
+impl<T> From<&SelectOpts<T>> for Dictionary {
+    fn from(opts: &SelectOpts<T>) -> Self {
+        [("prompt", &opts.prompt), ("kind", &opts.kind)]
+            .into_iter()
+            .filter(|(_, value)| !value.is_nil())
+            .map(|(key, value)| (key, value.clone()))
+            .collect()
+    }
+}
@@ -1,7 +1,7 @@
 use types::{Array, Integer};
 
 use crate::trait_utils::StringOrListOfStrings;
-use crate::types::{ExtmarkHlMode, ExtmarkVirtTextPosition};
+use crate::types::{ExtmarkHlMode, ExtmarkVirtTextPosition, Position};
 
 /// Options passed to [`Buffer::set_extmark()`](crate::Buffer::set_extmark).
 #[cfg(not(feature = "neovim-nightly"))]
@@ -496,6 +496,16 @@ pub struct SetExtmarkOpts {
     scoped: types::Boolean,
 }
 
+impl SetExtmarkOptsBuilder {
+    /// Ending position of the mark, exclusive. Equivalent to setting both
+    /// [`end_row`](SetExtmarkOptsBuilder::end_row) and
+    /// [`end_col`](SetExtmarkOptsBuilder::end_col).
+    #[inline]
+    pub fn end(&mut self, end: Position) -> &mut Self {
+        self.end_row(end.row).end_col(end.col)
+    }
+}
+
 #[inline]
 fn set_virt_lines<Txt, Hl, Cnk, ChunkyCnk>(
     field: &mut Array,
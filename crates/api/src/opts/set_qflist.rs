@@ -0,0 +1,116 @@
+use types::{Array, Dictionary, Integer, Object};
+
+use crate::trait_utils::StringOrFunction;
+use crate::types::{QfItem, QfTextFuncArgs};
+
+/// Options passed to [`QfList::set()`](crate::QfList::set), i.e. the `what`
+/// argument of `setqflist()`. Only the attributes that are set are modified.
+#[derive(Clone, Debug, Default)]
+pub struct SetQfListOpts {
+    context: Object,
+    id: Object,
+    idx: Object,
+    items: Object,
+    nr: Object,
+    quickfixtextfunc: Object,
+    title: Object,
+}
+
+impl SetQfListOpts {
+    #[inline(always)]
+    /// Creates a new [`SetQfListOptsBuilder`].
+    pub fn builder() -> SetQfListOptsBuilder {
+        SetQfListOptsBuilder::default()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SetQfListOptsBuilder(SetQfListOpts);
+
+impl SetQfListOptsBuilder {
+    /// Any value attached to the list.
+    #[inline]
+    pub fn context(&mut self, context: impl Into<Object>) -> &mut Self {
+        self.0.context = context.into();
+        self
+    }
+
+    /// Modifies the list with the given unique id instead of the current
+    /// one.
+    #[inline]
+    pub fn id(&mut self, id: u32) -> &mut Self {
+        self.0.id = (id as Integer).into();
+        self
+    }
+
+    /// The 1-indexed index of the current item in the list.
+    #[inline]
+    pub fn idx(&mut self, idx: usize) -> &mut Self {
+        self.0.idx = (idx as Integer).into();
+        self
+    }
+
+    /// The items of the list.
+    #[inline]
+    pub fn items<I>(&mut self, items: I) -> &mut Self
+    where
+        I: IntoIterator<Item = QfItem>,
+    {
+        self.0.items = items
+            .into_iter()
+            .map(|item| Dictionary::from(&item))
+            .collect::<Array>()
+            .into();
+        self
+    }
+
+    /// Modifies the list at the given 1-indexed position in the stack
+    /// instead of the current one.
+    #[inline]
+    pub fn nr(&mut self, nr: u32) -> &mut Self {
+        self.0.nr = (nr as Integer).into();
+        self
+    }
+
+    /// The function used to display the items of the list in the quickfix
+    /// window. It's passed a [`QfTextFuncArgs`] and should return one line
+    /// for every item in the given range.
+    #[inline]
+    pub fn quickfixtextfunc<F>(&mut self, fun: F) -> &mut Self
+    where
+        F: StringOrFunction<QfTextFuncArgs, Vec<String>>,
+    {
+        self.0.quickfixtextfunc = fun.to_object();
+        self
+    }
+
+    /// The title of the list.
+    #[inline]
+    pub fn title(&mut self, title: impl Into<types::String>) -> &mut Self {
+        self.0.title = title.into().into();
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> SetQfListOpts {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl From<&SetQfListOpts> for Dictionary {
+    fn from(opts: &SetQfListOpts) -> Self {
+        [
+            ("context", &opts.context),
+            ("id", &opts.id),
+            ("idx", &opts.idx),
+            ("items", &opts.items),
+            ("nr", &opts.nr),
+            ("quickfixtextfunc", &opts.quickfixtextfunc),
+            ("title", &opts.title),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.is_nil())
+        .map(|(key, value)| (key, value.clone()))
+        .collect()
+    }
+}
@@ -0,0 +1,196 @@
+use types::{Array, Dictionary, Integer, Object};
+
+use crate::opts::SetQfListOpts;
+use crate::types::{QfAction, QfItem, QfListId, QfListInfos};
+use crate::{Error, Result, Window};
+
+/// The quickfix list or the location list of a window.
+///
+/// Both kinds of lists are stacks of up to ten lists, only one of which is
+/// the current one. The methods reading and modifying a list operate on the
+/// current list unless stated otherwise.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum QfList {
+    /// The global quickfix list.
+    Quickfix,
+
+    /// The location list of a window.
+    Location(Window),
+}
+
+impl QfList {
+    /// Replaces, appends to or creates a list with the given items, like
+    /// `setqflist(items, action)`.
+    pub fn set_items<I>(&self, items: I, action: QfAction) -> Result<()>
+    where
+        I: IntoIterator<Item = QfItem>,
+    {
+        let items = items
+            .into_iter()
+            .map(|item| Dictionary::from(&item))
+            .collect::<Array>();
+
+        self.setqflist(items.into(), action, None)
+    }
+
+    /// Modifies the attributes of a list, like
+    /// `setqflist([], action, what)`.
+    ///
+    /// The list is selected by the
+    /// [`id`](crate::opts::SetQfListOptsBuilder::id) or
+    /// [`nr`](crate::opts::SetQfListOptsBuilder::nr) of the options,
+    /// defaulting to the current one. With [`QfAction::New`] a new list is
+    /// created instead.
+    pub fn set(&self, action: QfAction, opts: &SetQfListOpts) -> Result<()> {
+        self.setqflist(Array::new().into(), action, Some(opts.into()))
+    }
+
+    /// Returns the items of the current list.
+    pub fn items(&self) -> Result<Vec<QfItem>> {
+        match self {
+            Self::Quickfix => crate::call_function("getqflist", Array::new()),
+            Self::Location(win) => {
+                crate::call_function("getloclist", (win.0,))
+            },
+        }
+    }
+
+    /// Returns all the informations about a list in the stack, including
+    /// its items.
+    ///
+    /// If the list doesn't exist the returned [`id`](QfListInfos::id) is
+    /// zero.
+    pub fn infos(&self, id: QfListId) -> Result<QfListInfos> {
+        let mut what = Dictionary::from(id);
+        what.insert("all", 1 as Integer);
+
+        match self {
+            Self::Quickfix => crate::call_function("getqflist", (what,)),
+            Self::Location(win) => {
+                crate::call_function("getloclist", (win.0, what))
+            },
+        }
+    }
+
+    /// Jumps to the next valid item, like `:cnext` or `:lnext`.
+    pub fn next(&self) -> Result<()> {
+        self.exec("next")
+    }
+
+    /// Jumps to the previous valid item, like `:cprevious` or
+    /// `:lprevious`.
+    pub fn previous(&self) -> Result<()> {
+        self.exec("previous")
+    }
+
+    /// Jumps to the first item, like `:cfirst` or `:lfirst`.
+    pub fn first(&self) -> Result<()> {
+        self.exec("first")
+    }
+
+    /// Jumps to the last item, like `:clast` or `:llast`.
+    pub fn last(&self) -> Result<()> {
+        self.exec("last")
+    }
+
+    /// Jumps to the item at the given 1-indexed position, like `:cc {nr}`
+    /// or `:ll {nr}`.
+    pub fn go_to(&self, nr: usize) -> Result<()> {
+        match self {
+            Self::Quickfix => crate::command(&format!("cc {nr}")),
+            Self::Location(_) => self.exec(&format!("l {nr}")),
+        }
+    }
+
+    /// Opens the window displaying the list, like `:copen` or `:lopen`.
+    pub fn open(&self) -> Result<()> {
+        self.exec("open")
+    }
+
+    /// Closes the window displaying the list, like `:cclose` or `:lclose`.
+    pub fn close(&self) -> Result<()> {
+        self.exec("close")
+    }
+
+    fn setqflist(
+        &self,
+        items: Object,
+        action: QfAction,
+        what: Option<Dictionary>,
+    ) -> Result<()> {
+        let mut args = Array::new();
+
+        if let Self::Location(win) = self {
+            args.push(win.0);
+        }
+
+        args.push(items);
+        args.push(action);
+
+        if let Some(what) = what {
+            args.push(what);
+        }
+
+        let func = match self {
+            Self::Quickfix => "setqflist",
+            Self::Location(_) => "setloclist",
+        };
+
+        match crate::call_function::<_, Integer>(func, args)? {
+            -1 => Err(Error::custom(format!("{func}() failed"))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Executes the quickfix command with the given suffix, e.g. `cnext` for
+    /// `"next"`. Location list commands are executed in their window.
+    fn exec(&self, suffix: &str) -> Result<()> {
+        match self {
+            Self::Quickfix => crate::command(&format!("c{suffix}")),
+            Self::Location(win) => {
+                let command = format!("l{suffix}");
+                win.call(move |()| crate::command(&command))
+            },
+        }
+    }
+}
+
+/// Sets the items of the quickfix list, like `setqflist(items, action)`.
+///
+/// See [`QfList::set_items`] for more details.
+pub fn set_qflist<I>(items: I, action: QfAction) -> Result<()>
+where
+    I: IntoIterator<Item = QfItem>,
+{
+    QfList::Quickfix.set_items(items, action)
+}
+
+/// Returns the items of the current quickfix list, like `getqflist()`.
+pub fn get_qflist() -> Result<Vec<QfItem>> {
+    QfList::Quickfix.items()
+}
+
+impl Window {
+    /// Sets the items of the window's location list, like
+    /// `setloclist(win, items, action)`.
+    ///
+    /// See [`QfList::set_items`] for more details.
+    pub fn set_loclist<I>(&self, items: I, action: QfAction) -> Result<()>
+    where
+        I: IntoIterator<Item = QfItem>,
+    {
+        self.loclist().set_items(items, action)
+    }
+
+    /// Returns the items of the window's current location list, like
+    /// `getloclist(win)`.
+    pub fn get_loclist(&self) -> Result<Vec<QfItem>> {
+        self.loclist().items()
+    }
+
+    /// Returns the window's location list.
+    #[inline]
+    pub fn loclist(&self) -> QfList {
+        QfList::Location(self.clone())
+    }
+}
@@ -0,0 +1,219 @@
+use types::{self as nvim, Array, Integer};
+
+use crate::types::RegisterContents;
+use crate::{Error, Result};
+
+/// A Vim register.
+///
+/// See `:h registers` for the meaning of each register.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Register {
+    /// The unnamed register `"`.
+    Unnamed,
+
+    /// One of the numbered registers `0` to `9`.
+    Numbered(RegisterNumber),
+
+    /// The small delete register `-`.
+    SmallDelete,
+
+    /// One of the named registers `a` to `z`.
+    Named(RegisterName),
+
+    /// The read-only register `.` containing the last inserted text.
+    LastInserted,
+
+    /// The read-only register `%` containing the name of the current file.
+    CurrentFile,
+
+    /// The register `#` containing the name of the alternate file.
+    AlternateFile,
+
+    /// The read-only register `:` containing the last executed command.
+    LastCommand,
+
+    /// The expression register `=`.
+    Expression,
+
+    /// The selection register `*`, i.e. the primary selection on X11.
+    Selection,
+
+    /// The clipboard register `+`.
+    Clipboard,
+
+    /// The black hole register `_`, which is always empty.
+    BlackHole,
+
+    /// The register `/` containing the last search pattern.
+    LastSearch,
+}
+
+/// The number of a [`Numbered`](Register::Numbered) register, between `0`
+/// and `9`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct RegisterNumber(u8);
+
+impl RegisterNumber {
+    /// Returns the number `n`, or `None` if it's greater than `9`.
+    #[inline]
+    pub const fn new(n: u8) -> Option<Self> {
+        if n <= 9 {
+            Some(Self(n))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number as a `u8`.
+    #[inline]
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+/// The name of a [`Named`](Register::Named) register, between `a` and `z`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct RegisterName(char);
+
+impl RegisterName {
+    /// Returns the name `ch` converted to lowercase, or `None` if it's not
+    /// an ASCII letter.
+    #[inline]
+    pub const fn new(ch: char) -> Option<Self> {
+        if ch.is_ascii_alphabetic() {
+            Some(Self(ch.to_ascii_lowercase()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the name as a lowercase `char`.
+    #[inline]
+    pub const fn get(self) -> char {
+        self.0
+    }
+}
+
+impl Register {
+    /// Returns the numbered register `n`, or `None` if `n` is greater than
+    /// `9`.
+    #[inline]
+    pub const fn numbered(n: u8) -> Option<Self> {
+        match RegisterNumber::new(n) {
+            Some(n) => Some(Self::Numbered(n)),
+            None => None,
+        }
+    }
+
+    /// Returns the named register `ch`, or `None` if `ch` is not an ASCII
+    /// letter. Uppercase letters return the corresponding lowercase
+    /// register.
+    #[inline]
+    pub const fn named(ch: char) -> Option<Self> {
+        match RegisterName::new(ch) {
+            Some(name) => Some(Self::Named(name)),
+            None => None,
+        }
+    }
+
+    /// Returns the register with the given name, or `None` if it's not a
+    /// valid register name. Uppercase letters return the corresponding
+    /// [`Named`](Register::Named) register.
+    pub fn from_char(ch: char) -> Option<Self> {
+        let reg = match ch {
+            '"' => Self::Unnamed,
+            '0'..='9' => Self::Numbered(RegisterNumber(ch as u8 - b'0')),
+            '-' => Self::SmallDelete,
+            'a'..='z' | 'A'..='Z' => {
+                Self::Named(RegisterName(ch.to_ascii_lowercase()))
+            },
+            '.' => Self::LastInserted,
+            '%' => Self::CurrentFile,
+            '#' => Self::AlternateFile,
+            ':' => Self::LastCommand,
+            '=' => Self::Expression,
+            '*' => Self::Selection,
+            '+' => Self::Clipboard,
+            '_' => Self::BlackHole,
+            '/' => Self::LastSearch,
+            _ => return None,
+        };
+
+        Some(reg)
+    }
+
+    /// Returns the name of the register.
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Unnamed => '"',
+            Self::Numbered(n) => (b'0' + n.get()) as char,
+            Self::SmallDelete => '-',
+            Self::Named(name) => name.get(),
+            Self::LastInserted => '.',
+            Self::CurrentFile => '%',
+            Self::AlternateFile => '#',
+            Self::LastCommand => ':',
+            Self::Expression => '=',
+            Self::Selection => '*',
+            Self::Clipboard => '+',
+            Self::BlackHole => '_',
+            Self::LastSearch => '/',
+        }
+    }
+
+    /// Returns the contents of the register and its type, like
+    /// `getreg(regname, 1, 1)` and `getregtype(regname)`.
+    pub fn get(&self) -> Result<RegisterContents> {
+        let name = self.as_char();
+        let lines = crate::call_function("getreg", (name, 1, 1))?;
+        let kind = crate::call_function("getregtype", (name,))?;
+        Ok(RegisterContents { lines, kind })
+    }
+
+    /// Replaces the contents of the register, like
+    /// `setreg(regname, lines, regtype)`.
+    ///
+    /// With [`Guess`](crate::types::RegisterType::Guess) the type is
+    /// inferred from the contents, and with a
+    /// [`BlockwiseVisual`](crate::types::RegisterType::BlockwiseVisual)
+    /// type without a width the width of the block is the width of its
+    /// longest line.
+    pub fn set(&self, contents: &RegisterContents) -> Result<()> {
+        self.setreg(contents, false)
+    }
+
+    /// Appends to the contents of the register, like writing to an
+    /// uppercase register. The type of the register is changed to the type
+    /// of `contents` unless it's
+    /// [`Guess`](crate::types::RegisterType::Guess).
+    pub fn append(&self, contents: &RegisterContents) -> Result<()> {
+        self.setreg(contents, true)
+    }
+
+    fn setreg(&self, contents: &RegisterContents, append: bool) -> Result<()> {
+        let lines = contents
+            .lines
+            .iter()
+            .map(|line| nvim::String::from(line.as_str()))
+            .collect::<Array>();
+
+        let mut options = nvim::String::from(contents.kind.clone())
+            .to_string_lossy()
+            .into_owned();
+
+        if append {
+            options.push('a');
+        }
+
+        let name = self.as_char();
+
+        match crate::call_function::<_, Integer>(
+            "setreg",
+            (name, lines, options),
+        )? {
+            0 => Ok(()),
+            _ => Err(Error::custom(format!("couldn't set register {name}"))),
+        }
+    }
+}
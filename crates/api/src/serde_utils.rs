@@ -1,7 +1,9 @@
 //! Utility functions for deserializing values coming from Neovim.
 
 use serde::de::{self, Deserialize, Deserializer, Error, IntoDeserializer};
-use types::Object;
+use types::{BufHandle, Object};
+
+use crate::Buffer;
 
 pub(crate) fn bool_from_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
 where
@@ -18,6 +20,18 @@ where
     }
 }
 
+pub(crate) fn buffer_from_int<'de, D>(
+    deserializer: D,
+) -> Result<Option<Buffer>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match BufHandle::deserialize(deserializer)? {
+        0 => Ok(None),
+        handle => Ok(Some(Buffer::from(handle))),
+    }
+}
+
 pub(crate) fn char_from_string<'de, D>(
     deserializer: D,
 ) -> Result<Option<char>, D::Error>
@@ -0,0 +1,291 @@
+//! Signs displayed in the sign column, placed as extmarks.
+//!
+//! Signs are placed in groups, each group being the extmark namespace with
+//! the same name. Placing a sign returns the id of its extmark, which can be
+//! used to remove it.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::api::{signs::Sign, Buffer};
+//!
+//! let added = Sign::builder().text("+").text_hl("DiffAdd").build();
+//! let removed = Sign::builder().text("-").text_hl("DiffDelete").build();
+//!
+//! let mut buffer = Buffer::current();
+//!
+//! // Replaces all the signs of the group in one go.
+//! buffer.set_signs("git", [(0, &added), (4, &removed)])?;
+//!
+//! for placed in buffer.get_signs("git")? {
+//!     println!("{}: {:?}", placed.line, placed.sign.text());
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use crate::opts::{GetExtmarksOpts, SetExtmarkOpts};
+use crate::types::{ExtmarkInfos, ExtmarkPosition};
+use crate::{Buffer, Result};
+
+/// How a sign is displayed.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Sign {
+    text: Option<String>,
+    text_hl: Option<String>,
+    number_hl: Option<String>,
+    line_hl: Option<String>,
+    cursorline_hl: Option<String>,
+    priority: Option<u32>,
+}
+
+impl Sign {
+    #[inline(always)]
+    /// Creates a new [`SignBuilder`].
+    pub fn builder() -> SignBuilder {
+        SignBuilder::default()
+    }
+
+    /// Returns the text displayed in the sign column.
+    #[inline]
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// Returns the highlight group of the text.
+    #[inline]
+    pub fn text_hl(&self) -> Option<&str> {
+        self.text_hl.as_deref()
+    }
+
+    /// Returns the highlight group of the line number.
+    #[inline]
+    pub fn number_hl(&self) -> Option<&str> {
+        self.number_hl.as_deref()
+    }
+
+    /// Returns the highlight group of the whole line.
+    #[inline]
+    pub fn line_hl(&self) -> Option<&str> {
+        self.line_hl.as_deref()
+    }
+
+    /// Returns the highlight group of the line when the cursor is on it.
+    #[inline]
+    pub fn cursorline_hl(&self) -> Option<&str> {
+        self.cursorline_hl.as_deref()
+    }
+
+    /// Returns the priority of the sign.
+    #[inline]
+    pub fn priority(&self) -> Option<u32> {
+        self.priority
+    }
+
+    /// Decodes the sign placed by an extmark, returning `None` if it
+    /// doesn't display a sign.
+    fn from_extmark(infos: ExtmarkInfos) -> Option<Self> {
+        let sign = Self {
+            // Neovim pads the text to two cells.
+            text: infos.sign_text.map(|text| text.trim_end().to_owned()),
+            text_hl: infos.sign_hl_group,
+            number_hl: infos.number_hl_group,
+            line_hl: infos.line_hl_group,
+            cursorline_hl: infos.cursorline_hl_group,
+            priority: None,
+        };
+
+        if sign == Self::default() {
+            return None;
+        }
+
+        Some(Self { priority: infos.priority, ..sign })
+    }
+
+    fn to_extmark_opts(&self) -> SetExtmarkOpts {
+        let mut opts = SetExtmarkOpts::builder();
+
+        if let Some(text) = &self.text {
+            opts.sign_text(text);
+        }
+        if let Some(hl) = &self.text_hl {
+            opts.sign_hl_group(hl.as_str());
+        }
+        if let Some(hl) = &self.number_hl {
+            opts.number_hl_group(hl.as_str());
+        }
+        if let Some(hl) = &self.line_hl {
+            opts.line_hl_group(hl.as_str());
+        }
+        if let Some(hl) = &self.cursorline_hl {
+            opts.cursorline_hl_group(hl.as_str());
+        }
+        if let Some(priority) = self.priority {
+            opts.priority(priority);
+        }
+
+        opts.build()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SignBuilder(Sign);
+
+impl SignBuilder {
+    /// Text displayed in the sign column. Should take up 1-2 display cells.
+    #[inline]
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.0.text = Some(text.to_owned());
+        self
+    }
+
+    /// Highlight group of the text.
+    #[inline]
+    pub fn text_hl(&mut self, hl_group: &str) -> &mut Self {
+        self.0.text_hl = Some(hl_group.to_owned());
+        self
+    }
+
+    /// Highlight group of the line number.
+    #[inline]
+    pub fn number_hl(&mut self, hl_group: &str) -> &mut Self {
+        self.0.number_hl = Some(hl_group.to_owned());
+        self
+    }
+
+    /// Highlight group of the whole line.
+    #[inline]
+    pub fn line_hl(&mut self, hl_group: &str) -> &mut Self {
+        self.0.line_hl = Some(hl_group.to_owned());
+        self
+    }
+
+    /// Highlight group of the line when the cursor is on it and
+    /// `'cursorline'` is enabled.
+    #[inline]
+    pub fn cursorline_hl(&mut self, hl_group: &str) -> &mut Self {
+        self.0.cursorline_hl = Some(hl_group.to_owned());
+        self
+    }
+
+    /// Priority of the sign. When several signs are placed on the same
+    /// line the ones with the highest priority are displayed.
+    #[inline]
+    pub fn priority(&mut self, priority: u32) -> &mut Self {
+        self.0.priority = Some(priority);
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> Sign {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// A sign placed in a buffer, returned by
+/// [`Buffer::get_signs()`](crate::Buffer::get_signs).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PlacedSign {
+    /// The id of the sign's extmark.
+    pub id: u32,
+
+    /// The 0-indexed line the sign is placed on.
+    pub line: usize,
+
+    pub sign: Sign,
+}
+
+impl Buffer {
+    /// Places a sign on the given 0-indexed line, returning its id.
+    pub fn place_sign(
+        &mut self,
+        group: &str,
+        line: usize,
+        sign: &Sign,
+    ) -> Result<u32> {
+        let ns_id = crate::create_namespace(group);
+        self.set_extmark(ns_id, line, 0, &sign.to_extmark_opts())
+    }
+
+    /// Removes the sign with the given id.
+    pub fn unplace_sign(&mut self, group: &str, id: u32) -> Result<()> {
+        self.del_extmark(crate::create_namespace(group), id)
+    }
+
+    /// Removes all the signs of a group.
+    pub fn clear_signs(&mut self, group: &str) -> Result<()> {
+        self.clear_namespace(crate::create_namespace(group), ..)
+    }
+
+    /// Replaces all the signs of a group with the given `(line, sign)`
+    /// pairs, where lines are 0-indexed.
+    pub fn set_signs<'a, I>(&mut self, group: &str, signs: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (usize, &'a Sign)>,
+    {
+        let ns_id = crate::create_namespace(group);
+        self.clear_namespace(ns_id, ..)?;
+
+        // The same few signs are usually placed on lots of lines.
+        let mut opts = HashMap::<&Sign, SetExtmarkOpts>::new();
+
+        for (line, sign) in signs {
+            let opts =
+                opts.entry(sign).or_insert_with(|| sign.to_extmark_opts());
+            self.set_extmark(ns_id, line, 0, opts)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the signs of a group, sorted by line. The signs placed
+    /// without a priority have the default one of extmarks.
+    pub fn get_signs(&self, group: &str) -> Result<Vec<PlacedSign>> {
+        let ns_id = crate::create_namespace(group);
+        let start = ExtmarkPosition::ByTuple((0, 0));
+        let end = ExtmarkPosition::ByTuple((self.line_count()?, 0));
+        let opts = GetExtmarksOpts::builder().details(true).build();
+
+        let signs = self
+            .get_extmarks(ns_id, start, end, &opts)?
+            .filter_map(|(id, line, _, infos)| {
+                let sign = Sign::from_extmark(infos?)?;
+                Some(PlacedSign { id, line, sign })
+            })
+            .collect();
+
+        Ok(signs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extmark_infos(sign_text: Option<&str>) -> ExtmarkInfos {
+        let mut dict = types::Dictionary::new();
+        dict.insert("right_gravity", true);
+        dict.insert("priority", 10);
+        if let Some(text) = sign_text {
+            dict.insert("sign_text", text);
+            dict.insert("sign_hl_group", "DiffAdd");
+        }
+        types::conversion::FromObject::from_object(dict.into()).unwrap()
+    }
+
+    #[test]
+    fn from_extmark() {
+        let sign = Sign::from_extmark(extmark_infos(Some("+ "))).unwrap();
+        let expected = Sign::builder()
+            .text("+")
+            .text_hl("DiffAdd")
+            .priority(10)
+            .build();
+        assert_eq!(expected, sign);
+    }
+
+    #[test]
+    fn from_extmark_without_sign() {
+        assert_eq!(None, Sign::from_extmark(extmark_infos(None)));
+    }
+}
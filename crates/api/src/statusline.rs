@@ -0,0 +1,576 @@
+//! Statuslines, winbars and tablines built from Rust components.
+//!
+//! A [`Line`] is a sequence of [`Segment`]s, each with its own highlight
+//! group, click handler and truncation priority, separated by alignment
+//! points. It's rendered to the format string expected by the
+//! `'statusline'`, `'winbar'` and `'tabline'` options, and can be installed
+//! as one of them with [`install()`].
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::api::{self, statusline::{self, Line, Segment, Target}};
+//!
+//! statusline::install(Target::Statusline, |ctx| {
+//!     let name = ctx.buffer.get_name()?;
+//!
+//!     let file = Segment::builder(name.display().to_string())
+//!         .hl("Directory")
+//!         .on_click(|_click| {
+//!             api::command("buffers")?;
+//!             Ok::<_, api::Error>(())
+//!         })
+//!         .build();
+//!
+//!     let line = Line::builder()
+//!         .segment(file)
+//!         .align()
+//!         .segment(Segment::builder("[+]").priority(1).build())
+//!         .build();
+//!
+//!     Ok::<_, api::Error>(line)
+//! })?;
+//! ```
+
+use core::cell::{Cell, RefCell};
+use core::cmp::Reverse;
+use core::fmt;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::rc::{Rc, Weak};
+
+use types::{Function, Integer, WinHandle};
+
+use crate::opts::{EvalStatuslineOpts, OptionOpts};
+use crate::types::{MouseButton, StatuslineInfos};
+use crate::{Buffer, Error, Result, Window};
+
+/// The name of the Lua global dispatching the clicks on a [`Segment`].
+const CLICK_DISPATCHER: &str = "_nvim_oxi_statusline_click";
+
+thread_local! {
+    static CLICK_HANDLERS: RefCell<HashMap<u32, Weak<ClickFn>>> =
+        RefCell::new(HashMap::new());
+
+    static NEXT_CLICK_ID: Cell<u32> = const { Cell::new(1) };
+
+    static HAS_CLICK_DISPATCHER: Cell<bool> =
+        const { Cell::new(false) };
+
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+type ClickFn = dyn Fn(Click) -> Result<()>;
+
+/// A mouse click on a [`Segment`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Click {
+    /// The number of clicks, e.g. 2 for a double click.
+    pub clicks: u32,
+
+    /// The button that was clicked.
+    pub button: MouseButton,
+
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+/// A piece of text in a [`Line`].
+#[derive(Clone, Default)]
+pub struct Segment {
+    text: String,
+    hl: Option<String>,
+    on_click: Option<(u32, Rc<ClickFn>)>,
+    priority: Option<u32>,
+}
+
+impl fmt::Debug for Segment {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Segment")
+            .field("text", &self.text)
+            .field("hl", &self.hl)
+            .field("on_click", &self.on_click.is_some())
+            .field("priority", &self.priority)
+            .finish()
+    }
+}
+
+impl Segment {
+    #[inline(always)]
+    /// Creates a new [`SegmentBuilder`] for a segment displaying `text`.
+    pub fn builder(text: impl Into<String>) -> SegmentBuilder {
+        SegmentBuilder(Self { text: text.into(), ..Default::default() })
+    }
+
+    /// Returns the text displayed by the segment.
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl From<&str> for Segment {
+    #[inline]
+    fn from(text: &str) -> Self {
+        Self::builder(text).build()
+    }
+}
+
+impl From<String> for Segment {
+    #[inline]
+    fn from(text: String) -> Self {
+        Self::builder(text).build()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SegmentBuilder(Segment);
+
+impl SegmentBuilder {
+    /// Highlight group used to display the segment. Defaults to the one of
+    /// the line, e.g. `StatusLine` or `WinBar`.
+    #[inline]
+    pub fn hl(&mut self, group: &str) -> &mut Self {
+        self.0.hl = Some(group.to_owned());
+        self
+    }
+
+    /// Function called when the segment is clicked.
+    ///
+    /// The segment, or the [`Line`] containing it, has to be kept alive for
+    /// the function to be called. Lines installed with [`install()`] are
+    /// kept until they're rendered again.
+    pub fn on_click<F, E>(&mut self, fun: F) -> &mut Self
+    where
+        F: Fn(Click) -> std::result::Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let id = NEXT_CLICK_ID.with(|next| next.replace(next.get() + 1));
+        let fun = move |click| fun(click).map_err(Error::custom);
+        self.0.on_click = Some((id, Rc::new(fun)));
+        self
+    }
+
+    /// Priority of the segment when the line is too long to fit its
+    /// window. Segments are dropped starting from the one with the lowest
+    /// priority, and the rightmost one among those with the same priority.
+    ///
+    /// Segments without a priority are never dropped.
+    #[inline]
+    pub fn priority(&mut self, priority: u32) -> &mut Self {
+        self.0.priority = Some(priority);
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> Segment {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// An element of a [`Line`].
+#[derive(Clone, Debug)]
+pub enum Component {
+    /// Some text, rendered in order.
+    Segment(Segment),
+
+    /// Separates the parts of the line aligned to the left, center and
+    /// right, like `%=`.
+    Align,
+
+    /// Where Neovim truncates the line if it still doesn't fit after
+    /// dropping segments, like `%<`.
+    Truncate,
+}
+
+impl From<Segment> for Component {
+    #[inline]
+    fn from(segment: Segment) -> Self {
+        Self::Segment(segment)
+    }
+}
+
+/// A statusline, winbar or tabline made of [`Component`]s.
+#[derive(Clone, Debug, Default)]
+pub struct Line {
+    components: Vec<Component>,
+}
+
+impl Line {
+    #[inline(always)]
+    /// Creates a new [`LineBuilder`].
+    pub fn builder() -> LineBuilder {
+        LineBuilder::default()
+    }
+
+    /// Returns the components of the line.
+    #[inline]
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    /// Renders the line to a format string for the `'statusline'`,
+    /// `'winbar'` and `'tabline'` options, dropping the segments with the
+    /// lowest [`priority`](SegmentBuilder::priority) until its text fits in
+    /// `max_width` cells.
+    ///
+    /// The click handlers of the segments in the returned string can be
+    /// called as long as the line is alive.
+    pub fn render(&self, max_width: usize) -> Result<String> {
+        let widths = self
+            .components
+            .iter()
+            .map(|component| match component {
+                Component::Segment(segment) => crate::strwidth(&segment.text),
+                _ => Ok(0),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut width = widths.iter().sum::<usize>();
+        let mut dropped = vec![false; self.components.len()];
+
+        while width > max_width {
+            let lowest = self
+                .components
+                .iter()
+                .enumerate()
+                .filter(|&(idx, _)| !dropped[idx])
+                .filter_map(|(idx, component)| match component {
+                    Component::Segment(Segment {
+                        priority: Some(priority),
+                        ..
+                    }) => Some((idx, *priority)),
+                    _ => None,
+                })
+                .min_by_key(|&(idx, priority)| (priority, Reverse(idx)));
+
+            let Some((idx, _)) = lowest else { break };
+            dropped[idx] = true;
+            width -= widths[idx];
+        }
+
+        let mut line = String::new();
+
+        for (component, _) in
+            self.components.iter().zip(dropped).filter(|(_, d)| !d)
+        {
+            match component {
+                Component::Segment(segment) => {
+                    render_segment(&mut line, segment)?
+                },
+                Component::Align => line.push_str("%="),
+                Component::Truncate => line.push_str("%<"),
+            }
+        }
+
+        Ok(line)
+    }
+
+    /// Renders the line for a statusline `max_width` cells wide and
+    /// evaluates it with [`eval_statusline()`](crate::eval_statusline).
+    pub fn eval(&self, max_width: usize) -> Result<StatuslineInfos> {
+        let opts = EvalStatuslineOpts::builder()
+            .maxwidth(max_width as u32)
+            .highlights(true)
+            .build();
+
+        crate::eval_statusline(&self.render(max_width)?, &opts)
+    }
+}
+
+impl FromIterator<Component> for Line {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Component>>(iter: I) -> Self {
+        Self { components: iter.into_iter().collect() }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LineBuilder(Line);
+
+impl LineBuilder {
+    /// Appends a [`Segment`] to the line.
+    #[inline]
+    pub fn segment(&mut self, segment: impl Into<Segment>) -> &mut Self {
+        self.0.components.push(Component::Segment(segment.into()));
+        self
+    }
+
+    /// Appends an [`Align`](Component::Align) separator to the line.
+    #[inline]
+    pub fn align(&mut self) -> &mut Self {
+        self.0.components.push(Component::Align);
+        self
+    }
+
+    /// Appends a [`Truncate`](Component::Truncate) point to the line.
+    #[inline]
+    pub fn truncate(&mut self) -> &mut Self {
+        self.0.components.push(Component::Truncate);
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> Line {
+        std::mem::take(&mut self.0)
+    }
+}
+
+fn render_segment(line: &mut String, segment: &Segment) -> Result<()> {
+    if let Some((id, fun)) = &segment.on_click {
+        register_click_handler(*id, fun)?;
+        line.push_str(&format!("%{id}@v:lua.{CLICK_DISPATCHER}@"));
+    }
+
+    if let Some(hl) = &segment.hl {
+        line.push_str(&format!("%#{hl}#"));
+    }
+
+    line.push_str(&segment.text.replace('%', "%%"));
+
+    if segment.hl.is_some() {
+        line.push_str("%*");
+    }
+
+    if segment.on_click.is_some() {
+        line.push_str("%X");
+    }
+
+    Ok(())
+}
+
+fn register_click_handler(id: u32, fun: &Rc<ClickFn>) -> Result<()> {
+    CLICK_HANDLERS.with(|handlers| {
+        let mut handlers = handlers.borrow_mut();
+        handlers.retain(|_, fun| fun.strong_count() > 0);
+        handlers.insert(id, Rc::downgrade(fun));
+    });
+
+    if HAS_CLICK_DISPATCHER.with(|has| has.replace(true)) {
+        return Ok(());
+    }
+
+    type Args = (Integer, Integer, String, String);
+
+    let dispatcher =
+        Function::<Args, ()>::from_fn(|(id, clicks, button, modifiers)| {
+            let button = match button.as_str() {
+                "l" => MouseButton::Left,
+                "m" => MouseButton::Middle,
+                "r" => MouseButton::Right,
+                other => {
+                    return Err(Error::custom(format!(
+                        "unknown mouse button {other:?}"
+                    )))
+                },
+            };
+
+            let click = Click {
+                clicks: clicks.try_into()?,
+                button,
+                shift: modifiers.contains('s'),
+                ctrl: modifiers.contains('c'),
+                alt: modifiers.contains('a'),
+                meta: modifiers.contains('m'),
+            };
+
+            let fun = CLICK_HANDLERS.with(|handlers| {
+                let id = u32::try_from(id).ok()?;
+                handlers.borrow().get(&id).and_then(Weak::upgrade)
+            });
+
+            match fun {
+                Some(fun) => fun(click),
+                None => Ok(()),
+            }
+        });
+
+    crate::utils::set_lua_global(CLICK_DISPATCHER, dispatcher)
+}
+
+/// The options a [`Line`] can be installed as.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Target {
+    Statusline,
+    Winbar,
+    Tabline,
+}
+
+impl Target {
+    #[inline]
+    fn option(self) -> &'static str {
+        match self {
+            Self::Statusline => "statusline",
+            Self::Winbar => "winbar",
+            Self::Tabline => "tabline",
+        }
+    }
+}
+
+/// The window a [`Line`] is rendered for, passed to the function given to
+/// [`install()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Context {
+    /// The window the line belongs to. For the tabline this is the current
+    /// window.
+    pub window: Window,
+
+    /// The buffer displayed in the window.
+    pub buffer: Buffer,
+
+    /// Whether the window is the current one.
+    pub is_current: bool,
+
+    /// The width available to the line.
+    pub width: usize,
+}
+
+impl Context {
+    fn new(target: Target) -> Result<Self> {
+        let window = match target {
+            Target::Tabline => Window::current(),
+            _ => {
+                let winid = crate::get_var::<Integer>("statusline_winid")?;
+                Window::from(WinHandle::try_from(winid)?)
+            },
+        };
+
+        let columns = || {
+            let opts = OptionOpts::default();
+            crate::get_option_value::<Integer>("columns", &opts)
+        };
+
+        let width = match target {
+            Target::Tabline => columns()?,
+            Target::Statusline if is_global_statusline()? => columns()?,
+            _ => window.get_width()?.into(),
+        };
+
+        Ok(Self {
+            buffer: window.get_buf()?,
+            is_current: window == Window::current(),
+            width: width.try_into()?,
+            window,
+        })
+    }
+}
+
+#[inline]
+fn is_global_statusline() -> Result<bool> {
+    let opts = OptionOpts::default();
+    Ok(crate::get_option_value::<Integer>("laststatus", &opts)? == 3)
+}
+
+/// What a cached line was rendered for.
+#[derive(Clone, PartialEq)]
+struct CacheKey {
+    buffer: Buffer,
+    changedtick: u32,
+    is_current: bool,
+    width: usize,
+    generation: u64,
+}
+
+struct Cached {
+    key: CacheKey,
+    rendered: String,
+
+    /// Keeps the click handlers of the rendered line alive.
+    _line: Line,
+}
+
+/// Sets the `'statusline'`, `'winbar'` or `'tabline'` option to a line
+/// built by `provider`.
+///
+/// The line of each window is only rebuilt when its buffer changes, when
+/// the window is resized or changes focus, or after calling
+/// [`invalidate()`], so `provider` is called a lot less often than Neovim
+/// redraws the screen.
+pub fn install<F, E>(target: Target, provider: F) -> Result<()>
+where
+    F: Fn(&Context) -> std::result::Result<Line, E> + 'static,
+    E: StdError + 'static,
+{
+    let cache = RefCell::new(HashMap::<WinHandle, Cached>::new());
+
+    let fun = Function::<(), String>::from_fn(move |()| {
+        let ctx = Context::new(target)?;
+
+        let key = CacheKey {
+            changedtick: ctx.buffer.get_changedtick()?,
+            buffer: ctx.buffer.clone(),
+            is_current: ctx.is_current,
+            width: ctx.width,
+            generation: GENERATION.with(Cell::get),
+        };
+
+        if let Some(cached) = cache.borrow().get(&ctx.window.0) {
+            if cached.key == key {
+                return Ok::<_, Error>(cached.rendered.clone());
+            }
+        }
+
+        let line = provider(&ctx).map_err(Error::custom)?;
+        let rendered = line.render(ctx.width)?;
+
+        let mut cache = cache.borrow_mut();
+        cache.retain(|&handle, _| Window::from(handle).is_valid());
+        cache.insert(
+            ctx.window.0,
+            Cached { key, rendered: rendered.clone(), _line: line },
+        );
+
+        Ok(rendered)
+    });
+
+    // `%!` expressions can't call Lua functions directly, so the function
+    // is stored in a global and called through `v:lua`. The global is keyed
+    // on the target so that installing another provider replaces it, and
+    // the function is only referenced by the global.
+    let name = format!("_nvim_oxi_{}", target.option());
+    crate::utils::set_lua_global(&name, fun.clone())?;
+    fun.remove_from_lua_registry();
+
+    crate::set_option_value(
+        target.option(),
+        format!("%!v:lua.{name}()"),
+        &OptionOpts::default(),
+    )
+}
+
+/// Discards the lines cached by [`install()`] and redraws the
+/// statuslines, winbars and the tabline.
+///
+/// This is needed when a line depends on something other than the window
+/// and its buffer, like the current mode.
+pub fn invalidate() -> Result<()> {
+    GENERATION.with(|generation| generation.set(generation.get() + 1));
+    crate::command("redrawstatus!")?;
+    crate::command("redrawtabline")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_builder() {
+        let segment =
+            Segment::builder("foo").hl("Comment").priority(2).build();
+        assert_eq!("foo", segment.text());
+        assert_eq!(Some("Comment"), segment.hl.as_deref());
+        assert_eq!(Some(2), segment.priority);
+        assert!(segment.on_click.is_none());
+    }
+
+    #[test]
+    fn click_ids_are_unique() {
+        let ok = |_| Ok::<_, Error>(());
+        let first = Segment::builder("a").on_click(ok).build();
+        let second = Segment::builder("b").on_click(ok).build();
+        assert_ne!(first.on_click.unwrap().0, second.on_click.unwrap().0);
+    }
+}
@@ -0,0 +1,358 @@
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::io;
+use std::rc::Rc;
+use std::sync::mpsc;
+
+use types::Integer;
+
+use crate::opts::{
+    BufDeleteOpts, CreateAugroupOpts, CreateAutocmdOpts, OnInputArgs,
+    OpenTermOpts,
+};
+use crate::{Buffer, Channel, Error, Result, Window};
+
+type OnInput = Box<dyn FnMut(&[u8]) -> Result<()>>;
+type OnResize = Box<dyn FnMut(TerminalSize) -> Result<()>>;
+
+/// The size of a [`Terminal`], in screen cells.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TerminalSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Where the input typed in a [`Terminal`] is delivered.
+#[derive(Default)]
+struct Input {
+    callbacks: Vec<OnInput>,
+    senders: Vec<mpsc::Sender<Vec<u8>>>,
+}
+
+/// A terminal emulator not connected to any process, created with
+/// [`open_term()`](crate::open_term).
+///
+/// The output written to it with the [`Write`](std::io::Write)
+/// implementation is displayed in its buffer, escape sequences included,
+/// and the keys typed in Terminal mode are sent to the callbacks
+/// registered with [`on_input`](Terminal::on_input).
+///
+/// Cloning a terminal returns another handle to the same instance.
+#[derive(Clone)]
+pub struct Terminal(Rc<Inner>);
+
+struct Inner {
+    buffer: Buffer,
+    channel: Channel,
+    augroup: u32,
+    is_closed: Cell<bool>,
+    input: Rc<RefCell<Input>>,
+    on_resize: RefCell<Vec<OnResize>>,
+    size: Cell<Option<TerminalSize>>,
+    /// The last byte written to the terminal, to know whether a line feed
+    /// at the start of the next write follows a carriage return.
+    last_written: Cell<Option<u8>>,
+}
+
+impl fmt::Debug for Terminal {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Terminal")
+            .field("buffer", &self.0.buffer)
+            .field("channel", &self.0.channel)
+            .field("size", &self.0.size.get())
+            .field("is_closed", &self.0.is_closed.get())
+            .finish()
+    }
+}
+
+impl Terminal {
+    /// Opens a terminal in a new scratch buffer, which isn't displayed in
+    /// any window.
+    pub fn open() -> Result<Self> {
+        Self::open_in(crate::create_buf(false, true)?)
+    }
+
+    /// Opens a terminal in an existing, empty buffer.
+    pub fn open_in(buffer: Buffer) -> Result<Self> {
+        let input = Rc::new(RefCell::new(Input::default()));
+
+        let on_input = {
+            let input = Rc::clone(&input);
+            move |(_, _, _, data): OnInputArgs| dispatch_input(&input, &data)
+        };
+
+        let opts = OpenTermOpts::builder().on_input(on_input).build();
+        let channel = Channel::from(crate::open_term(&buffer, &opts)?);
+
+        let augroup = crate::create_augroup(
+            &format!("nvim_oxi_terminal_{}", buffer.0),
+            &CreateAugroupOpts::builder().clear(true).build(),
+        )?;
+
+        let terminal = Self(Rc::new(Inner {
+            buffer,
+            channel,
+            augroup,
+            is_closed: Cell::new(false),
+            input,
+            on_resize: RefCell::new(Vec::new()),
+            size: Cell::new(None),
+            last_written: Cell::new(None),
+        }));
+
+        terminal.create_autocmds()?;
+        terminal.update_size()?;
+
+        Ok(terminal)
+    }
+
+    /// Returns the buffer of the terminal.
+    #[inline]
+    pub fn buffer(&self) -> Buffer {
+        self.0.buffer.clone()
+    }
+
+    /// Returns the channel the output is sent to.
+    #[inline]
+    pub fn channel(&self) -> Channel {
+        self.0.channel
+    }
+
+    /// Returns the size of the terminal, which is the one of the smallest
+    /// window displaying it, or `None` if it's not displayed.
+    #[inline]
+    pub fn size(&self) -> Option<TerminalSize> {
+        self.0.size.get()
+    }
+
+    /// Returns whether the terminal has been closed.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed.get()
+    }
+
+    /// Registers a function called with the keys typed in the terminal.
+    pub fn on_input<F, E>(&self, mut fun: F)
+    where
+        F: FnMut(&[u8]) -> std::result::Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let fun = move |data: &[u8]| fun(data).map_err(Error::custom);
+        self.0.input.borrow_mut().callbacks.push(Box::new(fun));
+    }
+
+    /// Returns a receiver of the keys typed in the terminal, which can be
+    /// read from another thread.
+    pub fn input_receiver(&self) -> mpsc::Receiver<Vec<u8>> {
+        let (sender, receiver) = mpsc::channel();
+        self.0.input.borrow_mut().senders.push(sender);
+        receiver
+    }
+
+    /// Registers a function called with the new size of the terminal
+    /// every time it changes.
+    pub fn on_resize<F, E>(&self, mut fun: F)
+    where
+        F: FnMut(TerminalSize) -> std::result::Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let fun = move |size| fun(size).map_err(Error::custom);
+        self.0.on_resize.borrow_mut().push(Box::new(fun));
+    }
+
+    /// Closes the terminal, wiping its buffer and deleting its
+    /// autocommands. Does nothing if the terminal is already closed.
+    pub fn close(&self) -> Result<()> {
+        if self.0.is_closed.replace(true) {
+            return Ok(());
+        }
+
+        let mut res = crate::del_augroup_by_id(self.0.augroup);
+
+        // Wiping the buffer also closes the channel.
+        let buffer = self.buffer();
+        if buffer.is_valid() {
+            let opts = BufDeleteOpts::builder().force(true).build();
+            res = res.and(buffer.delete(&opts));
+        }
+
+        res
+    }
+
+    fn create_autocmds(&self) -> Result<()> {
+        let group = self.0.augroup;
+
+        let weak = Rc::downgrade(&self.0);
+        let resize = CreateAutocmdOpts::builder()
+            .group(group)
+            .callback(move |_| {
+                if let Some(inner) = weak.upgrade() {
+                    Terminal(inner).update_size()?;
+                }
+                Ok::<_, Error>(false)
+            })
+            .build();
+
+        // `WinScrolled` is also triggered when a window is resized.
+        crate::create_autocmd(
+            ["BufWinEnter", "VimResized", "WinScrolled"],
+            &resize,
+        )?;
+
+        let weak = Rc::downgrade(&self.0);
+        let wipeout = CreateAutocmdOpts::builder()
+            .group(group)
+            .buffer(self.buffer())
+            .callback(move |_| {
+                if let Some(inner) = weak.upgrade() {
+                    if !inner.is_closed.replace(true) {
+                        crate::del_augroup_by_id(inner.augroup)?;
+                    }
+                }
+                Ok::<_, Error>(true)
+            })
+            .build();
+
+        crate::create_autocmd(["BufWipeout"], &wipeout)?;
+
+        Ok(())
+    }
+
+    /// Computes the size of the terminal, calling the resize callbacks if
+    /// it changed.
+    fn update_size(&self) -> Result<()> {
+        let windows = crate::call_function::<_, Vec<Integer>>(
+            "win_findbuf",
+            (self.0.buffer.0 as Integer,),
+        )?;
+
+        let mut size = None::<TerminalSize>;
+
+        for window in windows {
+            let window = Window::from(window as i32);
+            let width = window.get_width()?;
+            let height = window.get_height()?;
+            size = Some(match size {
+                Some(size) => TerminalSize {
+                    width: size.width.min(width),
+                    height: size.height.min(height),
+                },
+                None => TerminalSize { width, height },
+            });
+        }
+
+        if size == self.0.size.replace(size) {
+            return Ok(());
+        }
+
+        let Some(size) = size else { return Ok(()) };
+
+        let mut callbacks = self.0.on_resize.take();
+        let res = callbacks.iter_mut().try_for_each(|fun| fun(size));
+
+        // Keep the callbacks registered while these were running.
+        let mut on_resize = self.0.on_resize.borrow_mut();
+        callbacks.append(&mut on_resize);
+        *on_resize = callbacks;
+
+        res
+    }
+}
+
+impl io::Write for Terminal {
+    /// Writes the output to the terminal. Line feeds not preceded by a
+    /// carriage return are turned into `\r\n`, like a tty would.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.is_closed() {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the terminal is closed",
+            ));
+        }
+
+        let Some(&last) = buf.last() else { return Ok(0) };
+
+        self.0
+            .channel
+            .send_bytes(&to_crlf(buf, self.0.last_written.get()))
+            .map_err(|err| io::Error::other(err.to_string()))?;
+
+        self.0.last_written.set(Some(last));
+
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn dispatch_input(input: &RefCell<Input>, data: &types::String) -> Result<()> {
+    let data = data.as_bytes();
+
+    input
+        .borrow_mut()
+        .senders
+        .retain(|sender| sender.send(data.to_vec()).is_ok());
+
+    // The callbacks are taken out while they run so that they can register
+    // new ones.
+    let mut callbacks = std::mem::take(&mut input.borrow_mut().callbacks);
+    let res = callbacks.iter_mut().try_for_each(|fun| fun(data));
+
+    let mut input = input.borrow_mut();
+    callbacks.append(&mut input.callbacks);
+    input.callbacks = callbacks;
+
+    res
+}
+
+/// Turns the line feeds not preceded by a carriage return into `\r\n`,
+/// `prev` being the byte written before `buf`.
+fn to_crlf(buf: &[u8], prev: Option<u8>) -> Cow<'_, [u8]> {
+    let is_lone_lf = |idx: usize| {
+        let prev = if idx == 0 { prev } else { Some(buf[idx - 1]) };
+        buf[idx] == b'\n' && prev != Some(b'\r')
+    };
+
+    if !(0..buf.len()).any(is_lone_lf) {
+        return Cow::Borrowed(buf);
+    }
+
+    let mut out = Vec::with_capacity(buf.len() + 8);
+    for (idx, &byte) in buf.iter().enumerate() {
+        if is_lone_lf(idx) {
+            out.push(b'\r');
+        }
+        out.push(byte);
+    }
+
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_crlf_lone_lf() {
+        assert_eq!(&b"foo\r\nbar\r\n"[..], &*to_crlf(b"foo\nbar\n", None));
+        assert_eq!(&b"\r\n"[..], &*to_crlf(b"\n", None));
+        assert_eq!(&b"\r\n"[..], &*to_crlf(b"\n", Some(b'a')));
+    }
+
+    #[test]
+    fn to_crlf_cr_in_previous_write() {
+        assert_eq!(&b"\nfoo"[..], &*to_crlf(b"\nfoo", Some(b'\r')));
+    }
+
+    #[test]
+    fn to_crlf_borrows_when_unchanged() {
+        let buf = b"\x1b[31mred\x1b[0m\r\n";
+        assert!(matches!(to_crlf(buf, None), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,608 @@
+//! Colorschemes defined in Rust.
+//!
+//! A [`Theme`] is made of a palette of named colors and of highlight groups
+//! using them. It can be applied with [`Theme::load()`], or turned into a
+//! Lua colorscheme with [`Theme::to_lua()`] so that it can be used without
+//! the plugin.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::api::theme::{Background, Highlight, Theme};
+//!
+//! let theme = Theme::builder("midnight")
+//!     .background(Background::Dark)
+//!     .color("fg", "#c0caf5")
+//!     .color("bg", "#1a1b26")
+//!     .color("gray", "#565f89")
+//!     .group("Normal", Highlight::builder().fg("fg").bg("bg").build())
+//!     .group("Comment", Highlight::builder().fg("gray").italic().build())
+//!     .link("@comment", "Comment")
+//!     .build();
+//!
+//! theme.load()?;
+//! std::fs::write("colors/midnight.lua", theme.to_lua())?;
+//! ```
+
+use core::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::opts::{
+    ExecAutocmdsOpts, OptionOpts, SetHighlightOpts, SetHighlightOptsBuilder,
+};
+use crate::{Error, Result};
+
+thread_local! {
+    /// The groups defined by the last loaded theme in each namespace, which
+    /// `:highlight clear` doesn't clear.
+    static NS_GROUPS: RefCell<HashMap<u32, Vec<String>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// The value of the `'background'` option set by a [`Theme`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Background {
+    Dark,
+    Light,
+}
+
+impl Background {
+    #[inline]
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Dark => "dark",
+            Self::Light => "light",
+        }
+    }
+}
+
+/// The definition of a highlight group in a [`Theme`].
+///
+/// Colors are either the name of a color in the theme's palette, or
+/// anything accepted by [`set_hl()`](crate::set_hl), like `"#ff0000"`,
+/// `"Red"` or `"NONE"`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Highlight {
+    fg: Option<String>,
+    bg: Option<String>,
+    sp: Option<String>,
+    link: Option<String>,
+    blend: Option<u8>,
+    bold: bool,
+    italic: bool,
+    nocombine: bool,
+    reverse: bool,
+    standout: bool,
+    strikethrough: bool,
+    undercurl: bool,
+    underdashed: bool,
+    underdotted: bool,
+    underdouble: bool,
+    underline: bool,
+}
+
+impl Highlight {
+    #[inline(always)]
+    /// Creates a new [`HighlightBuilder`].
+    pub fn builder() -> HighlightBuilder {
+        HighlightBuilder::default()
+    }
+
+    /// A highlight linking to the group `target`.
+    #[inline]
+    pub fn link(target: &str) -> Self {
+        Self { link: Some(target.to_owned()), ..Default::default() }
+    }
+
+    /// Returns the style flags of the highlight set to `true`, using the
+    /// names of the keys passed to `nvim_set_hl()`.
+    fn flags(&self) -> impl Iterator<Item = &'static str> {
+        [
+            ("bold", self.bold),
+            ("italic", self.italic),
+            ("nocombine", self.nocombine),
+            ("reverse", self.reverse),
+            ("standout", self.standout),
+            ("strikethrough", self.strikethrough),
+            ("undercurl", self.undercurl),
+            ("underdashed", self.underdashed),
+            ("underdotted", self.underdotted),
+            ("underdouble", self.underdouble),
+            ("underline", self.underline),
+        ]
+        .into_iter()
+        .filter_map(|(flag, is_set)| is_set.then_some(flag))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct HighlightBuilder(Highlight);
+
+macro_rules! flag {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[inline]
+        pub fn $name(&mut self) -> &mut Self {
+            self.0.$name = true;
+            self
+        }
+    };
+}
+
+impl HighlightBuilder {
+    /// The foreground color.
+    #[inline]
+    pub fn fg(&mut self, color: &str) -> &mut Self {
+        self.0.fg = Some(color.to_owned());
+        self
+    }
+
+    /// The background color.
+    #[inline]
+    pub fn bg(&mut self, color: &str) -> &mut Self {
+        self.0.bg = Some(color.to_owned());
+        self
+    }
+
+    /// The color of underlines and undercurls.
+    #[inline]
+    pub fn sp(&mut self, color: &str) -> &mut Self {
+        self.0.sp = Some(color.to_owned());
+        self
+    }
+
+    /// Links the group to `target`. The other attributes are ignored.
+    #[inline]
+    pub fn link(&mut self, target: &str) -> &mut Self {
+        self.0.link = Some(target.to_owned());
+        self
+    }
+
+    /// The blend level of floating windows and the popup menu, between 0
+    /// and 100.
+    #[inline]
+    pub fn blend(&mut self, blend: u8) -> &mut Self {
+        self.0.blend = Some(blend);
+        self
+    }
+
+    flag!(bold);
+    flag!(italic);
+    flag!(nocombine);
+    flag!(reverse);
+    flag!(standout);
+    flag!(strikethrough);
+    flag!(undercurl);
+    flag!(underdashed);
+    flag!(underdotted);
+    flag!(underdouble);
+    flag!(underline);
+
+    #[inline]
+    pub fn build(&mut self) -> Highlight {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// A colorscheme.
+///
+/// The groups are defined in the global namespace, except for the ones
+/// added with [`ns_group`](ThemeBuilder::ns_group) which are defined in
+/// namespaces created with [`create_namespace()`](crate::create_namespace).
+/// Those can be activated globally with
+/// [`activate_ns()`](Theme::activate_ns), or in a window by passing their
+/// [`ns_id()`](Theme::ns_id) to [`Window::set_hl()`](crate::Window::set_hl).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    name: String,
+    background: Option<Background>,
+    palette: BTreeMap<String, String>,
+    groups: BTreeMap<String, Highlight>,
+    namespaces: BTreeMap<String, BTreeMap<String, Highlight>>,
+}
+
+impl Theme {
+    #[inline(always)]
+    /// Creates a new [`ThemeBuilder`] for a theme called `name`, which is
+    /// the value `g:colors_name` is set to.
+    pub fn builder(name: &str) -> ThemeBuilder {
+        ThemeBuilder(Self { name: name.to_owned(), ..Default::default() })
+    }
+
+    /// Returns the name of the theme.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the definition of a group in the global namespace.
+    #[inline]
+    pub fn group(&self, name: &str) -> Option<&Highlight> {
+        self.groups.get(name)
+    }
+
+    /// Returns the id of the namespace called `name`, or `None` if the theme
+    /// doesn't define any group in it.
+    #[inline]
+    pub fn ns_id(&self, name: &str) -> Option<u32> {
+        self.namespaces
+            .contains_key(name)
+            .then(|| crate::create_namespace(name))
+    }
+
+    /// Makes the namespace called `name` the active one with
+    /// [`set_hl_ns()`](crate::set_hl_ns), so that its groups are used in
+    /// the windows which don't have a namespace of their own.
+    #[cfg(feature = "neovim-nightly")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "neovim-nightly")))]
+    pub fn activate_ns(&self, name: &str) -> Result<()> {
+        let ns_id = self.ns_id(name).ok_or_else(|| {
+            Error::custom(format!(
+                "theme {:?} has no namespace {name:?}",
+                self.name
+            ))
+        })?;
+        crate::set_hl_ns(ns_id)
+    }
+
+    /// Applies the theme like `:colorscheme` would.
+    ///
+    /// The existing highlights are cleared, including the groups defined in
+    /// namespaces by a previously loaded theme, `'background'` and
+    /// `g:colors_name` are set, the groups are defined and the
+    /// `ColorScheme` event is triggered. Nothing is changed if one of the
+    /// colors is invalid.
+    pub fn load(&self) -> Result<()> {
+        let groups = self.resolve_all(&self.groups)?;
+
+        let namespaces = self
+            .namespaces
+            .iter()
+            .map(|(ns, groups)| Ok((ns, self.resolve_all(groups)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        crate::command("highlight clear")?;
+        clear_ns_groups()?;
+
+        if crate::call_function::<_, i64>("exists", ("syntax_on",))? == 1 {
+            crate::command("syntax reset")?;
+        }
+
+        if let Some(background) = self.background {
+            let opts = OptionOpts::default();
+            crate::set_option_value("background", background.as_str(), &opts)?;
+        }
+
+        crate::set_var("colors_name", self.name.as_str())?;
+
+        for (name, opts) in &groups {
+            crate::set_hl(0, name, opts)?;
+        }
+
+        for (ns, groups) in &namespaces {
+            let ns_id = crate::create_namespace(ns);
+            let names = groups.iter().map(|(name, _)| name.clone()).collect();
+            NS_GROUPS
+                .with(|ns_groups| ns_groups.borrow_mut().insert(ns_id, names));
+            for (name, opts) in groups {
+                crate::set_hl(ns_id, name, opts)?;
+            }
+        }
+
+        let opts =
+            ExecAutocmdsOpts::builder().patterns(self.name.as_str()).build();
+
+        crate::exec_autocmds(["ColorScheme"], &opts)
+    }
+
+    /// Returns the theme as a Lua colorscheme which can be saved as
+    /// `colors/<name>.lua` in the `'runtimepath'`.
+    pub fn to_lua(&self) -> String {
+        let mut lua = String::new();
+
+        lua.push_str("vim.cmd(\"highlight clear\")\n");
+        lua.push_str("if vim.fn.exists(\"syntax_on\") == 1 then\n");
+        lua.push_str("  vim.cmd(\"syntax reset\")\n");
+        lua.push_str("end\n\n");
+
+        if let Some(background) = self.background {
+            let _ = writeln!(
+                lua,
+                "vim.o.background = \"{}\"",
+                background.as_str()
+            );
+        }
+
+        let _ =
+            writeln!(lua, "vim.g.colors_name = {}\n", lua_string(&self.name));
+
+        lua.push_str("local hl = vim.api.nvim_set_hl\n\n");
+
+        for (name, hl) in &self.groups {
+            self.write_lua_group(&mut lua, "0", name, hl);
+        }
+
+        for (ns, groups) in &self.namespaces {
+            let _ = writeln!(
+                lua,
+                "\nlocal ns = vim.api.nvim_create_namespace({})",
+                lua_string(ns)
+            );
+            for (name, hl) in groups {
+                self.write_lua_group(&mut lua, "ns", name, hl);
+            }
+        }
+
+        let _ = writeln!(
+            lua,
+            "\nvim.api.nvim_exec_autocmds(\"ColorScheme\", \
+             {{ pattern = {} }})",
+            lua_string(&self.name)
+        );
+
+        lua
+    }
+
+    /// Writes the Lua colorscheme returned by [`to_lua()`](Theme::to_lua)
+    /// to `path`.
+    pub fn export(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_lua())
+    }
+
+    /// Returns the value of a color, looking it up in the palette first.
+    fn color<'a>(&'a self, color: &'a str) -> &'a str {
+        self.palette.get(color).map(String::as_str).unwrap_or(color)
+    }
+
+    /// Returns the value of a color, checking that it's valid.
+    fn checked_color<'a>(
+        &'a self,
+        color: &'a Option<String>,
+    ) -> Result<Option<&'a str>> {
+        let Some(color) = color.as_deref().map(|color| self.color(color))
+        else {
+            return Ok(None);
+        };
+        validate_color(color)?;
+        Ok(Some(color))
+    }
+
+    fn resolve_all(
+        &self,
+        groups: &BTreeMap<String, Highlight>,
+    ) -> Result<Vec<(String, SetHighlightOpts)>> {
+        groups
+            .iter()
+            .map(|(name, hl)| Ok((name.clone(), self.resolve(hl)?)))
+            .collect()
+    }
+
+    /// Converts a highlight into the options passed to `set_hl()`, checking
+    /// that its colors are valid.
+    fn resolve(&self, hl: &Highlight) -> Result<SetHighlightOpts> {
+        let mut opts = SetHighlightOpts::builder();
+
+        if let Some(link) = &hl.link {
+            return Ok(opts.link(link).build());
+        }
+
+        if let Some(fg) = self.checked_color(&hl.fg)? {
+            opts.foreground(fg);
+        }
+        if let Some(bg) = self.checked_color(&hl.bg)? {
+            opts.background(bg);
+        }
+        if let Some(sp) = self.checked_color(&hl.sp)? {
+            opts.special(sp);
+        }
+        if let Some(blend) = hl.blend {
+            opts.blend(blend);
+        }
+
+        type Builder = SetHighlightOptsBuilder;
+        type SetFlag = fn(&mut Builder, bool) -> &mut Builder;
+
+        let flags: [(bool, SetFlag); 11] = [
+            (hl.bold, SetHighlightOptsBuilder::bold),
+            (hl.italic, SetHighlightOptsBuilder::italic),
+            (hl.nocombine, SetHighlightOptsBuilder::nocombine),
+            (hl.reverse, SetHighlightOptsBuilder::reverse),
+            (hl.standout, SetHighlightOptsBuilder::standout),
+            (hl.strikethrough, SetHighlightOptsBuilder::strikethrough),
+            (hl.undercurl, SetHighlightOptsBuilder::undercurl),
+            (hl.underdashed, SetHighlightOptsBuilder::underdashed),
+            (hl.underdotted, SetHighlightOptsBuilder::underdotted),
+            (hl.underdouble, SetHighlightOptsBuilder::underdouble),
+            (hl.underline, SetHighlightOptsBuilder::underline),
+        ];
+
+        for (is_set, set) in flags {
+            if is_set {
+                set(&mut opts, true);
+            }
+        }
+
+        Ok(opts.build())
+    }
+
+    fn write_lua_group(
+        &self,
+        lua: &mut String,
+        ns: &str,
+        name: &str,
+        hl: &Highlight,
+    ) {
+        let mut fields = Vec::new();
+
+        if let Some(link) = &hl.link {
+            fields.push(format!("link = {}", lua_string(link)));
+        } else {
+            for (key, color) in
+                [("fg", &hl.fg), ("bg", &hl.bg), ("sp", &hl.sp)]
+            {
+                if let Some(color) = color {
+                    let color = lua_string(self.color(color));
+                    fields.push(format!("{key} = {color}"));
+                }
+            }
+            if let Some(blend) = hl.blend {
+                fields.push(format!("blend = {blend}"));
+            }
+            fields.extend(hl.flags().map(|flag| format!("{flag} = true")));
+        }
+
+        let _ = writeln!(
+            lua,
+            "hl({ns}, {}, {{ {} }})",
+            lua_string(name),
+            fields.join(", ")
+        );
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ThemeBuilder(Theme);
+
+impl ThemeBuilder {
+    /// Sets `'background'` when the theme is loaded.
+    #[inline]
+    pub fn background(&mut self, background: Background) -> &mut Self {
+        self.0.background = Some(background);
+        self
+    }
+
+    /// Adds a color called `name` to the palette.
+    #[inline]
+    pub fn color(&mut self, name: &str, value: &str) -> &mut Self {
+        self.0.palette.insert(name.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Defines a highlight group, replacing any previous definition.
+    #[inline]
+    pub fn group(&mut self, name: &str, hl: Highlight) -> &mut Self {
+        self.0.groups.insert(name.to_owned(), hl);
+        self
+    }
+
+    /// Links the group `name` to `target`.
+    #[inline]
+    pub fn link(&mut self, name: &str, target: &str) -> &mut Self {
+        self.group(name, Highlight::link(target))
+    }
+
+    /// Defines a highlight group in the namespace called `namespace`.
+    #[inline]
+    pub fn ns_group(
+        &mut self,
+        namespace: &str,
+        name: &str,
+        hl: Highlight,
+    ) -> &mut Self {
+        self.0
+            .namespaces
+            .entry(namespace.to_owned())
+            .or_default()
+            .insert(name.to_owned(), hl);
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> Theme {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// Clears the groups defined in namespaces by the last loaded theme.
+fn clear_ns_groups() -> Result<()> {
+    let ns_groups = NS_GROUPS.with(|ns_groups| ns_groups.take());
+    let opts = SetHighlightOpts::default();
+    for (ns_id, names) in ns_groups {
+        for name in names {
+            crate::set_hl(ns_id, &name, &opts)?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `color` can be passed to `set_hl()`.
+fn validate_color(color: &str) -> Result<()> {
+    if color.eq_ignore_ascii_case("none") {
+        return Ok(());
+    }
+
+    match color.strip_prefix('#') {
+        Some(hex)
+            if hex.len() == 6
+                && hex.bytes().all(|b| b.is_ascii_hexdigit()) =>
+        {
+            Ok(())
+        },
+        Some(_) => Err(Error::custom(format!("invalid color {color:?}"))),
+        None => crate::get_color_by_name(color).map(|_| ()),
+    }
+}
+
+/// Quotes a string as a Lua string literal.
+fn lua_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            ch => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_lua() {
+        let theme = Theme::builder("test")
+            .background(Background::Dark)
+            .color("fg", "#c0caf5")
+            .group("Normal", Highlight::builder().fg("fg").bg("NONE").build())
+            .group("Comment", Highlight::builder().fg("Gray").italic().build())
+            .link("@comment", "Comment")
+            .ns_group("float", "Normal", Highlight::builder().bold().build())
+            .build();
+
+        let expected = r##"vim.cmd("highlight clear")
+if vim.fn.exists("syntax_on") == 1 then
+  vim.cmd("syntax reset")
+end
+
+vim.o.background = "dark"
+vim.g.colors_name = "test"
+
+local hl = vim.api.nvim_set_hl
+
+hl(0, "@comment", { link = "Comment" })
+hl(0, "Comment", { fg = "Gray", italic = true })
+hl(0, "Normal", { fg = "#c0caf5", bg = "NONE" })
+
+local ns = vim.api.nvim_create_namespace("float")
+hl(ns, "Normal", { bold = true })
+
+vim.api.nvim_exec_autocmds("ColorScheme", { pattern = "test" })
+"##;
+
+        assert_eq!(theme.to_lua(), expected);
+    }
+
+    #[test]
+    fn lua_string_escapes() {
+        assert_eq!(lua_string(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(lua_string("a\nb"), r#""a\nb""#);
+    }
+}
@@ -0,0 +1,632 @@
+//! Bindings to the [`vim.treesitter`][1] Lua module.
+//!
+//! The parsers, trees, nodes and queries returned by this module are handles
+//! to the corresponding Lua objects, which are kept alive in the Lua
+//! registry until the handles are dropped. Nothing is copied out of Lua until
+//! one of their methods is called.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::api::{treesitter::{self, Query}, Buffer};
+//!
+//! let buffer = Buffer::current();
+//! let parser = treesitter::get_parser(&buffer, Some("rust"))?;
+//! let root = parser.parse()?[0].root()?;
+//!
+//! let query = Query::parse("rust", "(function_item name: (identifier) @name)")?;
+//!
+//! for capture in query.iter_captures(&root, &buffer, ..)? {
+//!     let capture = capture?;
+//!     println!("{}: {}", capture.name, capture.node.text(&buffer)?);
+//! }
+//! ```
+//!
+//! [1]: https://neovim.io/doc/user/treesitter.html
+
+use core::ops::{Bound, RangeBounds};
+use std::rc::Rc;
+use std::result::Result as StdResult;
+
+use luajit::{ffi::*, Poppable};
+use types::{Dictionary, Function, Object};
+
+use crate::lua_object::LuaObject;
+use crate::types::{Position, Range};
+use crate::{Buffer, Error, Result};
+
+/// Binding to [`vim.treesitter.get_parser()`][1].
+///
+/// Returns the parser for `buffer`, creating it if needed. If `lang` is
+/// `None` the language is inferred from the buffer's `filetype`.
+///
+/// [1]: https://neovim.io/doc/user/treesitter.html#vim.treesitter.get_parser()
+pub fn get_parser(
+    buffer: &Buffer,
+    lang: Option<&str>,
+) -> Result<LanguageTree> {
+    let treesitter = LuaObject::from_vim_field("treesitter")?;
+
+    treesitter
+        .call_field::<_, Option<LuaObject>>(
+            "get_parser",
+            (buffer.clone(), lang.map(ToOwned::to_owned)),
+            1,
+        )?
+        .map(LanguageTree)
+        .ok_or_else(|| {
+            Error::custom(format!("no treesitter parser for {buffer}"))
+        })
+}
+
+/// A handle to a [`LanguageTree`][1], i.e. the parser of a buffer together
+/// with the parsers of the languages injected in it.
+///
+/// [1]: https://neovim.io/doc/user/treesitter.html#treesitter-languagetree
+#[derive(Clone, Debug)]
+pub struct LanguageTree(LuaObject);
+
+impl LanguageTree {
+    /// Returns the language of the tree.
+    pub fn lang(&self) -> Result<String> {
+        self.0.call_method("lang", (), 1)
+    }
+
+    /// Parses the buffer if it changed since the last parse, returning the
+    /// trees of the root language.
+    pub fn parse(&self) -> Result<Vec<Tree>> {
+        self.0.call_method("parse", (), 1)
+    }
+
+    /// Returns the trees of the root language from the last parse, without
+    /// parsing the buffer again.
+    pub fn trees(&self) -> Result<Vec<Tree>> {
+        self.0.call_method("trees", (), 1)
+    }
+
+    /// Returns whether the trees are up to date with the contents of the
+    /// buffer.
+    pub fn is_valid(&self) -> Result<bool> {
+        self.0.call_method("is_valid", (), 1)
+    }
+
+    /// Returns the language tree of the innermost language injected at
+    /// `range`.
+    pub fn language_for_range(&self, range: Range) -> Result<LanguageTree> {
+        self.0
+            .call_method("language_for_range", (range_to_lua(range),), 1)
+            .map(LanguageTree)
+    }
+
+    /// Registers a callback executed every time the tree changes after a
+    /// parse, with the ranges whose structure changed and the new tree.
+    pub fn on_changedtree<F>(&self, mut fun: F) -> Result<()>
+    where
+        F: FnMut(Vec<Range>, Tree) -> Result<()> + 'static,
+    {
+        let callback = Function::from_fn_mut(
+            move |(changes, tree): (Vec<Vec<usize>>, Tree)| {
+                let changes = changes.iter().map(|c| range_from_lua(c));
+                fun(changes.collect::<Result<_>>()?, tree)
+            },
+        );
+
+        let cbs = Dictionary::from_iter([("on_changedtree", callback)]);
+
+        self.0.call_method("register_cbs", (cbs,), 0)
+    }
+}
+
+/// A handle to a syntax tree.
+#[derive(Clone, Debug)]
+pub struct Tree(LuaObject);
+
+impl Tree {
+    /// Returns the root node of the tree.
+    pub fn root(&self) -> Result<Node> {
+        self.0.call_method("root", (), 1)
+    }
+}
+
+/// A handle to a node of a syntax tree.
+#[derive(Clone, Debug)]
+pub struct Node(LuaObject);
+
+impl Node {
+    /// Returns the type of the node, e.g. `"function_item"`.
+    pub fn kind(&self) -> Result<String> {
+        self.0.call_method("type", (), 1)
+    }
+
+    /// Returns a unique identifier of the node within its tree.
+    pub fn id(&self) -> Result<String> {
+        self.0.call_method("id", (), 1)
+    }
+
+    /// Returns whether the node is named, i.e. it's not an anonymous node
+    /// like a punctuation token.
+    pub fn is_named(&self) -> Result<bool> {
+        self.0.call_method("named", (), 1)
+    }
+
+    /// Returns whether the node was inserted by the parser to recover from
+    /// an error.
+    pub fn is_missing(&self) -> Result<bool> {
+        self.0.call_method("missing", (), 1)
+    }
+
+    /// Returns whether the node is a syntax error or contains one.
+    pub fn has_error(&self) -> Result<bool> {
+        self.0.call_method("has_error", (), 1)
+    }
+
+    /// Returns the range of the node.
+    pub fn range(&self) -> Result<Range> {
+        let (start_row, start_col, end_row, end_col) =
+            self.0.call_method("range", (), 4)?;
+
+        Ok(Range::new(
+            Position::new(start_row, start_col),
+            Position::new(end_row, end_col),
+        ))
+    }
+
+    /// Returns the range of the node as byte offsets from the start of the
+    /// buffer.
+    pub fn byte_range(&self) -> Result<core::ops::Range<usize>> {
+        let (_, _, start): (usize, usize, usize) =
+            self.0.call_method("start", (), 3)?;
+        let (_, _, end): (usize, usize, usize) =
+            self.0.call_method("end_", (), 3)?;
+        Ok(start..end)
+    }
+
+    /// Returns the text of the node in `buffer`.
+    pub fn text(&self, buffer: &Buffer) -> Result<String> {
+        LuaObject::from_vim_field("treesitter")?.call_field(
+            "get_node_text",
+            (self.0.clone(), buffer.clone()),
+            1,
+        )
+    }
+
+    /// Returns the S-expression representing the node.
+    pub fn sexpr(&self) -> Result<String> {
+        self.0.call_method("sexpr", (), 1)
+    }
+
+    /// Returns the parent of the node, or `None` if it's the root.
+    pub fn parent(&self) -> Result<Option<Node>> {
+        self.0.call_method("parent", (), 1)
+    }
+
+    /// Returns the number of children of the node.
+    pub fn child_count(&self) -> Result<usize> {
+        self.0.call_method("child_count", (), 1)
+    }
+
+    /// Returns the number of named children of the node.
+    pub fn named_child_count(&self) -> Result<usize> {
+        self.0.call_method("named_child_count", (), 1)
+    }
+
+    /// Returns the child at the given 0-indexed position.
+    pub fn child(&self, idx: usize) -> Result<Option<Node>> {
+        self.0.call_method("child", (idx,), 1)
+    }
+
+    /// Returns the named child at the given 0-indexed position.
+    pub fn named_child(&self, idx: usize) -> Result<Option<Node>> {
+        self.0.call_method("named_child", (idx,), 1)
+    }
+
+    /// Returns an iterator over the children of the node.
+    pub fn children(&self) -> Result<Children> {
+        Ok(Children::new(self.clone(), self.child_count()?, false))
+    }
+
+    /// Returns an iterator over the named children of the node.
+    pub fn named_children(&self) -> Result<Children> {
+        Ok(Children::new(self.clone(), self.named_child_count()?, true))
+    }
+
+    /// Returns the children of the node with the given field name.
+    pub fn field(&self, name: &str) -> Result<Vec<Node>> {
+        self.0.call_method("field", (name.to_owned(),), 1)
+    }
+
+    /// Returns the next sibling of the node.
+    pub fn next_sibling(&self) -> Result<Option<Node>> {
+        self.0.call_method("next_sibling", (), 1)
+    }
+
+    /// Returns the previous sibling of the node.
+    pub fn prev_sibling(&self) -> Result<Option<Node>> {
+        self.0.call_method("prev_sibling", (), 1)
+    }
+
+    /// Returns the next named sibling of the node.
+    pub fn next_named_sibling(&self) -> Result<Option<Node>> {
+        self.0.call_method("next_named_sibling", (), 1)
+    }
+
+    /// Returns the previous named sibling of the node.
+    pub fn prev_named_sibling(&self) -> Result<Option<Node>> {
+        self.0.call_method("prev_named_sibling", (), 1)
+    }
+
+    /// Returns the smallest node spanning `range`.
+    pub fn descendant_for_range(&self, range: Range) -> Result<Option<Node>> {
+        self.descendant("descendant_for_range", range)
+    }
+
+    /// Returns the smallest named node spanning `range`.
+    pub fn named_descendant_for_range(
+        &self,
+        range: Range,
+    ) -> Result<Option<Node>> {
+        self.descendant("named_descendant_for_range", range)
+    }
+
+    fn descendant(&self, method: &str, range: Range) -> Result<Option<Node>> {
+        let Range { start, end } = range;
+        let args = (start.row, start.col, end.row, end.col);
+        self.0.call_method(method, args, 1)
+    }
+}
+
+/// An iterator over the children of a [`Node`], returned by
+/// [`Node::children()`] and [`Node::named_children()`].
+///
+/// If looking up a child fails the error is yielded and the iteration
+/// stops.
+pub struct Children {
+    node: Node,
+    idx: usize,
+    count: usize,
+    named: bool,
+}
+
+impl Children {
+    fn new(node: Node, count: usize, named: bool) -> Self {
+        Self { node, idx: 0, count, named }
+    }
+}
+
+impl Iterator for Children {
+    type Item = Result<Node>;
+
+    fn next(&mut self) -> Option<Result<Node>> {
+        while self.idx < self.count {
+            let idx = self.idx;
+            self.idx += 1;
+
+            let child = if self.named {
+                self.node.named_child(idx)
+            } else {
+                self.node.child(idx)
+            };
+
+            match child {
+                Ok(Some(child)) => return Some(Ok(child)),
+                Ok(None) => continue,
+                // The error is only yielded once, then the iteration stops.
+                Err(err) => {
+                    self.idx = self.count;
+                    return Some(Err(err));
+                },
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.count - self.idx))
+    }
+}
+
+/// A handle to a parsed treesitter [query][1].
+///
+/// [1]: https://neovim.io/doc/user/treesitter.html#treesitter-query
+#[derive(Clone, Debug)]
+pub struct Query {
+    query: LuaObject,
+    captures: Rc<[String]>,
+}
+
+impl Query {
+    /// Binding to [`vim.treesitter.query.parse()`][1].
+    ///
+    /// Parses a query written in the query language of `lang`.
+    ///
+    /// [1]: https://neovim.io/doc/user/treesitter.html#vim.treesitter.query.parse()
+    pub fn parse(lang: &str, source: &str) -> Result<Self> {
+        let query = LuaObject::from_vim_field("treesitter.query")?
+            .call_field("parse", (lang.to_owned(), source.to_owned()), 1)?;
+
+        Self::from_lua(query)
+    }
+
+    /// Binding to [`vim.treesitter.query.get()`][1].
+    ///
+    /// Returns the query named `name` for `lang` from the runtime files,
+    /// e.g. `"highlights"`, or `None` if there isn't one.
+    ///
+    /// [1]: https://neovim.io/doc/user/treesitter.html#vim.treesitter.query.get()
+    pub fn get(lang: &str, name: &str) -> Result<Option<Self>> {
+        LuaObject::from_vim_field("treesitter.query")?
+            .call_field::<_, Option<LuaObject>>(
+                "get",
+                (lang.to_owned(), name.to_owned()),
+                1,
+            )?
+            .map(Self::from_lua)
+            .transpose()
+    }
+
+    fn from_lua(query: LuaObject) -> Result<Self> {
+        let captures = query.get::<Vec<String>>("captures")?.into();
+        Ok(Self { query, captures })
+    }
+
+    /// Returns the names of the captures of the query, where the name of the
+    /// capture with id `n` is at index `n - 1`.
+    pub fn captures(&self) -> &[String] {
+        &self.captures
+    }
+
+    /// Returns an iterator over the captures of the query in `node`, in the
+    /// order in which they appear in the buffer.
+    ///
+    /// Only the captures on the given rows are returned. Captures whose
+    /// pattern has a predicate that doesn't match are skipped.
+    pub fn iter_captures<R>(
+        &self,
+        node: &Node,
+        buffer: &Buffer,
+        rows: R,
+    ) -> Result<QueryCaptures>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, stop) = rows_to_lua(rows);
+
+        let iter = self.query.call_method(
+            "iter_captures",
+            (node.0.clone(), buffer.clone(), start, stop),
+            1,
+        )?;
+
+        Ok(QueryCaptures { iter, captures: self.captures.clone() })
+    }
+
+    /// Returns an iterator over the matches of the query in `node`, in the
+    /// order in which they're found.
+    ///
+    /// Only the matches on the given rows are returned. Matches whose
+    /// pattern has a predicate that doesn't match are skipped.
+    pub fn iter_matches<R>(
+        &self,
+        node: &Node,
+        buffer: &Buffer,
+        rows: R,
+    ) -> Result<QueryMatches>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, stop) = rows_to_lua(rows);
+
+        let iter = self.query.call_method(
+            "iter_matches",
+            (node.0.clone(), buffer.clone(), start, stop),
+            1,
+        )?;
+
+        Ok(QueryMatches { iter, captures: self.captures.clone() })
+    }
+}
+
+/// A node captured by a [`Query`].
+#[derive(Clone, Debug)]
+pub struct QueryCapture {
+    /// The 1-indexed id of the capture.
+    pub id: u32,
+
+    /// The name of the capture, without the leading `@`.
+    pub name: String,
+
+    /// The captured node.
+    pub node: Node,
+
+    /// The metadata of the match the capture belongs to, as set by the
+    /// directives of its pattern.
+    pub metadata: Object,
+}
+
+/// A match of a pattern of a [`Query`].
+#[derive(Clone, Debug)]
+pub struct QueryMatch {
+    /// The 1-indexed id of the pattern that matched.
+    pub pattern: u32,
+
+    /// The captures of the match.
+    pub captures: Vec<QueryCapture>,
+
+    /// The metadata of the match, as set by the directives of its pattern.
+    pub metadata: Object,
+}
+
+/// An iterator over the captures of a [`Query`], returned by
+/// [`Query::iter_captures()`].
+pub struct QueryCaptures {
+    iter: LuaObject,
+    captures: Rc<[String]>,
+}
+
+impl Iterator for QueryCaptures {
+    type Item = Result<QueryCapture>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next =
+            self.iter.call::<_, (Option<u32>, Option<Node>, Object)>((), 3);
+
+        match next {
+            Ok((Some(id), Some(node), metadata)) => Some(Ok(QueryCapture {
+                id,
+                name: capture_name(&self.captures, id),
+                node,
+                metadata,
+            })),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// An iterator over the matches of a [`Query`], returned by
+/// [`Query::iter_matches()`].
+pub struct QueryMatches {
+    iter: LuaObject,
+    captures: Rc<[String]>,
+}
+
+impl Iterator for QueryMatches {
+    type Item = Result<QueryMatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self
+            .iter
+            .call::<_, (Option<u32>, Option<MatchCaptures>, Object)>((), 3);
+
+        let (pattern, nodes, metadata) = match next {
+            Ok((Some(pattern), Some(nodes), metadata)) => {
+                (pattern, nodes, metadata)
+            },
+            Ok(_) => return None,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let captures = nodes
+            .0
+            .into_iter()
+            .map(|(id, node)| QueryCapture {
+                id,
+                name: capture_name(&self.captures, id),
+                node,
+                metadata: metadata.clone(),
+            })
+            .collect();
+
+        Some(Ok(QueryMatch { pattern, captures, metadata }))
+    }
+}
+
+/// The `match` table yielded by `Query:iter_matches()`, mapping capture ids
+/// to either a single node or a list of nodes depending on the version of
+/// Neovim.
+struct MatchCaptures(Vec<(u32, Node)>);
+
+impl Poppable for MatchCaptures {
+    unsafe fn pop(lstate: *mut lua_State) -> StdResult<Self, luajit::Error> {
+        if lua_type(lstate, -1) != LUA_TTABLE {
+            let found = lua_type(lstate, -1);
+            return Err(luajit::Error::pop_wrong_type::<Self>(
+                LUA_TTABLE, found,
+            ));
+        }
+
+        let mut captures = Vec::new();
+
+        lua_pushnil(lstate);
+
+        while lua_next(lstate, -2) != 0 {
+            let id = lua_tointeger(lstate, -2) as u32;
+
+            if lua_type(lstate, -1) == LUA_TTABLE {
+                for node in <Vec<Node> as Poppable>::pop(lstate)? {
+                    captures.push((id, node));
+                }
+            } else {
+                captures.push((id, Node::pop(lstate)?));
+            }
+        }
+
+        // Pop the table.
+        lua_pop(lstate, 1);
+
+        // The table isn't an array, so its iteration order is unspecified.
+        captures.sort_by_key(|&(id, _)| id);
+
+        Ok(Self(captures))
+    }
+}
+
+macro_rules! impl_handle {
+    ($($handle:ident),*) => {
+        $(
+            impl Poppable for $handle {
+                unsafe fn pop(
+                    lstate: *mut lua_State,
+                ) -> StdResult<Self, luajit::Error> {
+                    LuaObject::pop(lstate).map(Self)
+                }
+            }
+        )*
+    };
+}
+
+impl_handle!(LanguageTree, Tree, Node);
+
+fn capture_name(captures: &[String], id: u32) -> String {
+    (id as usize)
+        .checked_sub(1)
+        .and_then(|idx| captures.get(idx))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Converts a range of rows into the `start` and `stop` arguments of the
+/// query iterators, with `stop` being exclusive.
+fn rows_to_lua<R: RangeBounds<usize>>(
+    rows: R,
+) -> (Option<usize>, Option<usize>) {
+    let start = match rows.start_bound() {
+        Bound::Unbounded => None,
+        Bound::Included(&n) => Some(n),
+        Bound::Excluded(&n) => Some(n + 1),
+    };
+
+    let stop = match rows.end_bound() {
+        Bound::Unbounded => None,
+        Bound::Included(&n) => Some(n + 1),
+        Bound::Excluded(&n) => Some(n),
+    };
+
+    (start, stop)
+}
+
+/// Converts a range into a Lua `Range4`, i.e. a
+/// `{ start_row, start_col, end_row, end_col }` list.
+fn range_to_lua(range: Range) -> Vec<usize> {
+    let Range { start, end } = range;
+    vec![start.row, start.col, end.row, end.col]
+}
+
+/// Converts a Lua `Range4` or `Range6` into a range. A `Range6` also
+/// contains the byte offsets of the start and end positions, after their
+/// columns.
+fn range_from_lua(range: &[usize]) -> Result<Range> {
+    let (start_row, start_col, end_row, end_col) = match *range {
+        [sr, sc, er, ec] => (sr, sc, er, ec),
+        [sr, sc, _, er, ec, _] => (sr, sc, er, ec),
+        _ => {
+            return Err(Error::custom(format!("invalid range: {range:?}")));
+        },
+    };
+
+    Ok(Range::new(
+        Position::new(start_row, start_col),
+        Position::new(end_row, end_col),
+    ))
+}
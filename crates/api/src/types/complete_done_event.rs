@@ -0,0 +1,32 @@
+use types::conversion::FromObject;
+use types::{Dictionary, Object};
+
+use super::CompletionItem;
+
+/// The informations available during a `CompleteDone` autocommand.
+///
+/// Can be read with [`CompleteDoneEvent::get()`] from within the
+/// autocommand's callback.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompleteDoneEvent {
+    /// The item that was inserted, or `None` if completion was stopped
+    /// without selecting one.
+    pub item: Option<CompletionItem>,
+}
+
+impl CompleteDoneEvent {
+    /// Reads the event from `v:completed_item`. Only valid within a
+    /// `CompleteDone` autocommand.
+    pub fn get() -> crate::Result<Self> {
+        let item = crate::get_vvar::<Dictionary>("completed_item")?;
+
+        let item = if item.is_empty() {
+            None
+        } else {
+            Some(CompletionItem::from_object(Object::from(item))?)
+        };
+
+        Ok(Self { item })
+    }
+}
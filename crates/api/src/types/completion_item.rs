@@ -0,0 +1,156 @@
+use serde::Deserialize;
+use types::{
+    conversion::{self, FromObject, ToObject},
+    serde::Deserializer,
+    Dictionary,
+    Object,
+};
+
+/// An entry of the popup menu displayed in Insert mode completion.
+///
+/// See `:h complete-items` for more informations.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompletionItem {
+    /// The text that will be inserted.
+    pub word: String,
+
+    /// An abbreviation of [`word`](CompletionItem::word) displayed in the
+    /// menu instead of it.
+    pub abbr: Option<String>,
+
+    /// Extra text displayed after the word in the menu.
+    pub menu: Option<String>,
+
+    /// More informations about the item, displayed in the preview window.
+    pub info: Option<String>,
+
+    /// The kind of the item, e.g. `"f"` for functions or `"v"` for
+    /// variables.
+    pub kind: Option<String>,
+
+    /// Whether case is ignored when comparing the item with the other ones
+    /// to remove duplicates.
+    pub icase: bool,
+
+    /// Whether the item is always kept when the typed text doesn't match
+    /// it.
+    pub equal: bool,
+
+    /// Whether the item is added even if an item with the same word is
+    /// already present.
+    pub dup: bool,
+
+    /// Whether the item is added even if its word is empty.
+    pub empty: bool,
+
+    /// Any value attached to the item, available in
+    /// [`CompleteDoneEvent::item`](crate::types::CompleteDoneEvent::item).
+    pub user_data: Object,
+}
+
+impl CompletionItem {
+    /// Creates a new item inserting `word`.
+    #[inline]
+    pub fn new(word: impl Into<String>) -> Self {
+        Self { word: word.into(), ..Default::default() }
+    }
+}
+
+impl From<&CompletionItem> for Dictionary {
+    fn from(item: &CompletionItem) -> Self {
+        let mut dict = Dictionary::new();
+
+        dict.insert("word", item.word.as_str());
+
+        for (key, value) in [
+            ("abbr", &item.abbr),
+            ("menu", &item.menu),
+            ("info", &item.info),
+            ("kind", &item.kind),
+        ] {
+            if let Some(value) = value {
+                dict.insert(key, value.as_str());
+            }
+        }
+
+        for (key, value) in [
+            ("icase", item.icase),
+            ("equal", item.equal),
+            ("dup", item.dup),
+            ("empty", item.empty),
+        ] {
+            if value {
+                dict.insert(key, 1);
+            }
+        }
+
+        if !item.user_data.is_nil() {
+            dict.insert("user_data", item.user_data.clone());
+        }
+
+        dict
+    }
+}
+
+impl ToObject for CompletionItem {
+    fn to_object(self) -> Result<Object, conversion::Error> {
+        Ok(Dictionary::from(&self).into())
+    }
+}
+
+/// An item as stored in `v:completed_item`, where the missing fields are
+/// empty strings.
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawCompletionItem {
+    word: String,
+    abbr: String,
+    menu: String,
+    info: String,
+    kind: String,
+    user_data: Object,
+}
+
+impl Default for RawCompletionItem {
+    fn default() -> Self {
+        Self {
+            word: String::new(),
+            abbr: String::new(),
+            menu: String::new(),
+            info: String::new(),
+            kind: String::new(),
+            user_data: Object::nil(),
+        }
+    }
+}
+
+impl From<RawCompletionItem> for CompletionItem {
+    fn from(raw: RawCompletionItem) -> Self {
+        let non_empty = |s: String| (!s.is_empty()).then_some(s);
+
+        // `user_data` is an empty string when it's not set.
+        let user_data = if raw.user_data == Object::from("") {
+            Object::nil()
+        } else {
+            raw.user_data
+        };
+
+        Self {
+            word: raw.word,
+            abbr: non_empty(raw.abbr),
+            menu: non_empty(raw.menu),
+            info: non_empty(raw.info),
+            kind: non_empty(raw.kind),
+            user_data,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromObject for CompletionItem {
+    fn from_object(obj: Object) -> Result<Self, conversion::Error> {
+        RawCompletionItem::deserialize(Deserializer::new(obj))
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+}
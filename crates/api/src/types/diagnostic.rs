@@ -0,0 +1,149 @@
+use serde::Deserialize;
+use types::{
+    conversion::{self, FromObject, ToObject},
+    serde::Deserializer,
+    Dictionary,
+    Integer,
+    Object,
+};
+
+use super::{DiagnosticSeverity, Position, Range};
+use crate::Buffer;
+
+/// A diagnostic as used by the [`diagnostic`](crate::diagnostic) module,
+/// i.e. the `Diagnostic` structure of `vim.diagnostic`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diagnostic {
+    /// The range the diagnostic refers to.
+    pub range: Range,
+
+    /// The severity of the diagnostic.
+    pub severity: DiagnosticSeverity,
+
+    /// The message of the diagnostic.
+    pub message: String,
+
+    /// The source of the diagnostic, e.g. the name of the linter.
+    pub source: Option<String>,
+
+    /// The code of the diagnostic. Numeric codes are converted to strings.
+    pub code: Option<String>,
+
+    /// Arbitrary data attached to the diagnostic.
+    pub user_data: Object,
+
+    /// The buffer the diagnostic belongs to. Only set on the diagnostics
+    /// returned by [`diagnostic::get()`](crate::diagnostic::get).
+    pub buffer: Option<Buffer>,
+
+    /// The namespace the diagnostic belongs to. Only set on the diagnostics
+    /// returned by [`diagnostic::get()`](crate::diagnostic::get).
+    pub namespace: Option<u32>,
+}
+
+impl Diagnostic {
+    /// Creates a new diagnostic.
+    #[inline]
+    pub fn new(
+        range: Range,
+        severity: DiagnosticSeverity,
+        message: impl Into<String>,
+    ) -> Self {
+        Self { range, severity, message: message.into(), ..Default::default() }
+    }
+}
+
+impl From<&Diagnostic> for Dictionary {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        let Range { start, end } = diagnostic.range;
+
+        let mut dict = Dictionary::from_iter([
+            ("lnum", Object::from(start.row as Integer)),
+            ("col", Object::from(start.col as Integer)),
+            ("end_lnum", Object::from(end.row as Integer)),
+            ("end_col", Object::from(end.col as Integer)),
+            ("severity", diagnostic.severity.into()),
+            ("message", diagnostic.message.as_str().into()),
+        ]);
+
+        if let Some(source) = &diagnostic.source {
+            dict.insert("source", source.as_str());
+        }
+        if let Some(code) = &diagnostic.code {
+            dict.insert("code", code.as_str());
+        }
+        if !diagnostic.user_data.is_nil() {
+            dict.insert("user_data", diagnostic.user_data.clone());
+        }
+
+        dict
+    }
+}
+
+impl ToObject for Diagnostic {
+    fn to_object(self) -> Result<Object, conversion::Error> {
+        Ok(Dictionary::from(&self).into())
+    }
+}
+
+#[derive(Deserialize)]
+struct RawDiagnostic {
+    lnum: usize,
+    #[serde(default)]
+    col: usize,
+    end_lnum: Option<usize>,
+    end_col: Option<usize>,
+    #[serde(default)]
+    severity: DiagnosticSeverity,
+    message: String,
+    source: Option<String>,
+    #[serde(default)]
+    code: Object,
+    #[serde(default)]
+    user_data: Object,
+    bufnr: Option<Buffer>,
+    namespace: Option<u32>,
+}
+
+impl From<RawDiagnostic> for Diagnostic {
+    fn from(raw: RawDiagnostic) -> Self {
+        let start = Position::new(raw.lnum, raw.col);
+        let end = Position::new(
+            raw.end_lnum.unwrap_or(raw.lnum),
+            raw.end_col.unwrap_or(raw.col),
+        );
+
+        let code = match raw.code.kind() {
+            types::ObjectKind::Integer => {
+                Integer::from_object(raw.code).ok().map(|n| n.to_string())
+            },
+            _ => String::from_object(raw.code).ok(),
+        };
+
+        Self {
+            range: Range::new(start, end),
+            severity: raw.severity,
+            message: raw.message,
+            source: raw.source,
+            code,
+            user_data: raw.user_data,
+            buffer: raw.bufnr,
+            namespace: raw.namespace,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Diagnostic {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        RawDiagnostic::deserialize(deserializer).map(Into::into)
+    }
+}
+
+impl FromObject for Diagnostic {
+    fn from_object(obj: Object) -> Result<Self, conversion::Error> {
+        Self::deserialize(Deserializer::new(obj)).map_err(Into::into)
+    }
+}
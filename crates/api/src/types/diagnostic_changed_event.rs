@@ -0,0 +1,39 @@
+use serde::Deserialize;
+use types::{conversion, serde::Deserializer};
+
+use super::{AutocmdCallbackArgs, Diagnostic};
+use crate::Buffer;
+
+/// The arguments of a `DiagnosticChanged` autocommand.
+///
+/// Can be created from the arguments passed to the autocommand's callback
+/// with [`TryFrom`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiagnosticChangedEvent {
+    /// The buffer whose diagnostics changed.
+    pub buffer: Buffer,
+
+    /// The new diagnostics of the buffer.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Deserialize)]
+struct Data {
+    #[serde(default)]
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl TryFrom<AutocmdCallbackArgs> for DiagnosticChangedEvent {
+    type Error = conversion::Error;
+
+    fn try_from(args: AutocmdCallbackArgs) -> Result<Self, Self::Error> {
+        let diagnostics = if args.data.is_nil() {
+            Vec::new()
+        } else {
+            Data::deserialize(Deserializer::new(args.data))?.diagnostics
+        };
+
+        Ok(Self { buffer: args.buffer, diagnostics })
+    }
+}
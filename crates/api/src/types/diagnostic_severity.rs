@@ -0,0 +1,53 @@
+use serde::Deserialize;
+use types::{Integer, Object};
+
+/// The severity of a [`Diagnostic`](super::Diagnostic), like the values of
+/// `vim.diagnostic.severity`.
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash,
+)]
+pub enum DiagnosticSeverity {
+    #[default]
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Hint = 4,
+}
+
+impl TryFrom<Integer> for DiagnosticSeverity {
+    type Error = Integer;
+
+    #[inline]
+    fn try_from(severity: Integer) -> Result<Self, Integer> {
+        match severity {
+            1 => Ok(Self::Error),
+            2 => Ok(Self::Warn),
+            3 => Ok(Self::Info),
+            4 => Ok(Self::Hint),
+            other => Err(other),
+        }
+    }
+}
+
+impl From<DiagnosticSeverity> for Object {
+    #[inline]
+    fn from(severity: DiagnosticSeverity) -> Self {
+        (severity as Integer).into()
+    }
+}
+
+impl<'de> Deserialize<'de> for DiagnosticSeverity {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let severity = Integer::deserialize(deserializer)?;
+
+        Self::try_from(severity).map_err(|other| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Signed(other),
+                &"a severity between 1 and 4",
+            )
+        })
+    }
+}
@@ -11,6 +11,9 @@ use super::{ExtmarkHlMode, ExtmarkVirtTextChunk, ExtmarkVirtTextPosition};
 #[non_exhaustive]
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize)]
 pub struct ExtmarkInfos {
+    #[serde(default)]
+    pub cursorline_hl_group: Option<String>,
+
     #[serde(default)]
     pub end_col: Option<usize>,
 
@@ -29,11 +32,23 @@ pub struct ExtmarkInfos {
     #[serde(default)]
     pub hl_mode: Option<ExtmarkHlMode>,
 
+    #[serde(default)]
+    pub line_hl_group: Option<String>,
+
+    #[serde(default)]
+    pub number_hl_group: Option<String>,
+
     #[serde(default)]
     pub priority: Option<u32>,
 
     pub right_gravity: bool,
 
+    #[serde(default)]
+    pub sign_hl_group: Option<String>,
+
+    #[serde(default)]
+    pub sign_text: Option<String>,
+
     #[serde(default)]
     pub ui_watched: Option<bool>,
 
@@ -1,6 +1,8 @@
 use serde::Deserialize;
 use types::{Array, Integer, Object};
 
+use super::Position;
+
 #[non_exhaustive]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize)]
 pub enum ExtmarkPosition {
@@ -24,3 +26,9 @@ impl From<ExtmarkPosition> for Object {
         }
     }
 }
+
+impl From<Position> for ExtmarkPosition {
+    fn from(pos: Position) -> Self {
+        Self::ByTuple((pos.row, pos.col))
+    }
+}
@@ -2,23 +2,27 @@ use serde::Deserialize;
 use types::{
     conversion::{self, FromObject},
     serde::Deserializer,
-    Function,
-    Object,
+    Function, Object,
 };
 
 use super::Mode;
 use crate::serde_utils as utils;
+use crate::Buffer;
 
 #[non_exhaustive]
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
 pub struct KeymapInfos {
-    /// Whether the mapping is local to a specific buffer.
-    #[serde(deserialize_with = "utils::bool_from_int")]
-    pub buffer: bool,
+    /// The buffer the mapping is local to, or `None` for global mappings.
+    #[serde(deserialize_with = "utils::buffer_from_int")]
+    pub buffer: Option<Buffer>,
 
     /// Optional callback triggered by the keymap.
     pub callback: Option<Function<(), ()>>,
 
+    /// The description of the mapping, if any.
+    #[serde(default)]
+    pub desc: Option<String>,
+
     /// Whether the keymap argument is an expression.
     #[serde(deserialize_with = "utils::bool_from_int")]
     pub expr: bool,
@@ -43,6 +47,11 @@ pub struct KeymapInfos {
     #[serde(deserialize_with = "utils::bool_from_int")]
     pub nowait: bool,
 
+    /// Whether the keycodes in the string returned by an expression mapping
+    /// are replaced.
+    #[serde(default, deserialize_with = "utils::bool_from_int")]
+    pub replace_keycodes: bool,
+
     /// The right-hand side of the mapping.
     #[serde(default, deserialize_with = "utils::empty_string_is_none")]
     pub rhs: Option<String>,
@@ -14,7 +14,12 @@ mod command_infos;
 mod command_modifiers;
 mod command_nargs;
 mod command_range;
+mod complete_done_event;
+mod completion_item;
 mod context_type;
+mod diagnostic;
+mod diagnostic_changed_event;
+mod diagnostic_severity;
 mod editor_context;
 mod extmark_hl_mode;
 mod extmark_infos;
@@ -32,13 +37,22 @@ mod one_or_more;
 mod option_infos;
 mod parsed_viml_expression;
 mod paste_phase;
+mod position;
 mod proc_infos;
+mod qf_action;
+mod qf_item;
+mod qf_list_id;
+mod qf_list_infos;
+mod qf_text_func_args;
+mod register_contents;
 mod register_type;
 #[cfg(feature = "neovim-nightly")]
 mod split_direction;
 mod split_modifier;
 mod statusline_highlight_infos;
 mod statusline_infos;
+mod text_change;
+mod text_yank_post_event;
 mod ui_infos;
 mod viml_ast_node;
 #[cfg(feature = "neovim-nightly")]
@@ -66,7 +80,12 @@ pub use command_infos::*;
 pub use command_modifiers::*;
 pub use command_nargs::*;
 pub use command_range::*;
+pub use complete_done_event::*;
+pub use completion_item::*;
 pub use context_type::*;
+pub use diagnostic::*;
+pub use diagnostic_changed_event::*;
+pub use diagnostic_severity::*;
 pub use editor_context::*;
 pub use extmark_hl_mode::*;
 pub use extmark_infos::*;
@@ -84,13 +103,22 @@ pub use one_or_more::*;
 pub use option_infos::*;
 pub use parsed_viml_expression::*;
 pub use paste_phase::*;
+pub use position::*;
 pub use proc_infos::*;
+pub use qf_action::*;
+pub use qf_item::*;
+pub use qf_list_id::*;
+pub use qf_list_infos::*;
+pub use qf_text_func_args::*;
+pub use register_contents::*;
 pub use register_type::*;
 #[cfg(feature = "neovim-nightly")]
 pub use split_direction::*;
 pub use split_modifier::*;
 pub use statusline_highlight_infos::*;
 pub use statusline_infos::*;
+pub use text_change::*;
+pub use text_yank_post_event::*;
 pub use ui_infos::*;
 pub use viml_ast_node::*;
 #[cfg(feature = "neovim-nightly")]
@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+
+use super::LspPosition;
+
+/// A position in a buffer, with a 0-indexed row and a 0-indexed byte column.
+///
+/// The Neovim API isn't consistent about the indexing of rows: the cursor and
+/// marks use 1-indexed rows, while extmarks and text ranges use 0-indexed
+/// ones. A `Position` is always 0-indexed, and converting from and to the
+/// other convention has to go through the `one_indexed` methods.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct Position {
+    /// The 0-indexed row.
+    pub row: usize,
+
+    /// The 0-indexed byte column.
+    pub col: usize,
+}
+
+impl Position {
+    /// Creates a new position from a 0-indexed row and byte column.
+    #[inline]
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Creates a new position from a 1-indexed row and a 0-indexed byte
+    /// column, like the ones used by the cursor and by marks.
+    ///
+    /// Returns `None` if `row` is zero, which Neovim uses to indicate that a
+    /// mark is not set.
+    #[inline]
+    pub const fn from_one_indexed_row(row: usize, col: usize) -> Option<Self> {
+        if row == 0 {
+            None
+        } else {
+            Some(Self { row: row - 1, col })
+        }
+    }
+
+    /// Returns the 1-indexed row of the position.
+    #[inline]
+    pub const fn one_indexed_row(&self) -> usize {
+        self.row + 1
+    }
+
+    /// Converts the position into an LSP position with a UTF-16 column.
+    /// `line` is the contents of the line at [`row`](Position::row).
+    ///
+    /// Returns `None` if the column is past the end of the line.
+    pub fn to_lsp(&self, line: &[u8]) -> Option<LspPosition> {
+        let character =
+            PositionEncoding::Utf16.index_of_byte(line, self.col)?;
+        Some(LspPosition {
+            line: self.row as u32,
+            character: character as u32,
+        })
+    }
+
+    /// Creates a position from an LSP position with a UTF-16 column. `line`
+    /// is the contents of the line at [`line`](LspPosition::line).
+    ///
+    /// Returns `None` if the column is past the end of the line.
+    pub fn from_lsp(pos: LspPosition, line: &[u8]) -> Option<Self> {
+        let col = PositionEncoding::Utf16
+            .byte_of_index(line, pos.character as usize)?;
+        Some(Self { row: pos.line as usize, col })
+    }
+}
+
+impl From<(usize, usize)> for Position {
+    /// Creates a position from a 0-indexed `(row, col)` tuple.
+    #[inline]
+    fn from((row, col): (usize, usize)) -> Self {
+        Self::new(row, col)
+    }
+}
+
+impl From<Position> for (usize, usize) {
+    /// Returns the 0-indexed `(row, col)` tuple of the position.
+    #[inline]
+    fn from(pos: Position) -> Self {
+        (pos.row, pos.col)
+    }
+}
+
+/// A range of text in a buffer, from [`start`](Range::start) inclusive to
+/// [`end`](Range::end) exclusive.
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
+pub struct Range {
+    /// The start of the range.
+    pub start: Position,
+
+    /// The end of the range, exclusive.
+    pub end: Position,
+}
+
+impl Range {
+    /// Creates a new range.
+    #[inline]
+    pub const fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    /// Creates an empty range at the given position.
+    #[inline]
+    pub const fn empty(pos: Position) -> Self {
+        Self { start: pos, end: pos }
+    }
+
+    /// Returns whether the range is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// Returns whether the range contains the given position.
+    #[inline]
+    pub fn contains(&self, pos: Position) -> bool {
+        self.start <= pos && pos < self.end
+    }
+}
+
+/// The unit used to measure the columns of a line, like the `encoding`
+/// argument of `vim.str_utfindex()` and `vim.str_byteindex()`.
+///
+/// Bytes that aren't valid UTF-8 count as one unit in every encoding, like
+/// they do in Neovim.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum PositionEncoding {
+    /// Columns are byte offsets, which is what the Neovim API uses.
+    #[default]
+    Utf8,
+
+    /// Columns count UTF-16 code units, which is the default in LSP.
+    Utf16,
+
+    /// Columns count Unicode codepoints.
+    Utf32,
+}
+
+impl PositionEncoding {
+    /// Converts a byte column of `line` into a column in this encoding.
+    ///
+    /// A byte column in the middle of a character is rounded up to the end
+    /// of the character. Returns `None` if `byte` is past the end of the
+    /// line.
+    pub fn index_of_byte(self, line: &[u8], byte: usize) -> Option<usize> {
+        if byte > line.len() {
+            return None;
+        }
+
+        let mut index = 0;
+        let mut offset = 0;
+
+        for (byte_len, utf16_len) in chars(line) {
+            if offset >= byte {
+                break;
+            }
+            offset += byte_len;
+            index += self.char_len(byte_len, utf16_len);
+        }
+
+        Some(index)
+    }
+
+    /// Converts a column of `line` in this encoding into a byte column.
+    ///
+    /// A column in the middle of a character is rounded up to the end of
+    /// the character. Returns `None` if `index` is past the end of the line.
+    pub fn byte_of_index(self, line: &[u8], index: usize) -> Option<usize> {
+        let mut current = 0;
+        let mut offset = 0;
+
+        for (byte_len, utf16_len) in chars(line) {
+            if current >= index {
+                return Some(offset);
+            }
+            offset += byte_len;
+            current += self.char_len(byte_len, utf16_len);
+        }
+
+        (current >= index).then_some(offset)
+    }
+
+    /// Returns the length of `text` in this encoding.
+    pub fn len(self, text: &[u8]) -> usize {
+        chars(text).map(|(bytes, utf16)| self.char_len(bytes, utf16)).sum()
+    }
+
+    #[inline]
+    fn char_len(self, byte_len: usize, utf16_len: usize) -> usize {
+        match self {
+            Self::Utf8 => byte_len,
+            Self::Utf16 => utf16_len,
+            Self::Utf32 => 1,
+        }
+    }
+}
+
+/// Iterates over the characters of `bytes`, yielding their length in bytes
+/// and in UTF-16 code units. Bytes that aren't valid UTF-8 are yielded as
+/// single characters.
+fn chars(mut bytes: &[u8]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    core::iter::from_fn(move || {
+        let first = *bytes.first()?;
+
+        let len = match first {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+
+        let (len, utf16_len) = match bytes
+            .get(..len)
+            .and_then(|char| core::str::from_utf8(char).ok())
+        {
+            Some(char) => (len, char.encode_utf16().count()),
+            None => (1, 1),
+        };
+
+        bytes = &bytes[len..];
+
+        Some((len, utf16_len))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_indexed_rows() {
+        assert_eq!(Position::from_one_indexed_row(0, 3), None);
+        let pos = Position::from_one_indexed_row(1, 3).unwrap();
+        assert_eq!(pos, Position::new(0, 3));
+        assert_eq!(pos.one_indexed_row(), 1);
+    }
+
+    #[test]
+    fn index_of_byte() {
+        let line = "aé𝄞b".as_bytes();
+        assert_eq!(PositionEncoding::Utf16.index_of_byte(line, 3), Some(2));
+        assert_eq!(PositionEncoding::Utf16.index_of_byte(line, 7), Some(4));
+        assert_eq!(PositionEncoding::Utf32.index_of_byte(line, 7), Some(3));
+        assert_eq!(PositionEncoding::Utf8.index_of_byte(line, 7), Some(7));
+        assert_eq!(PositionEncoding::Utf16.index_of_byte(line, 9), None);
+
+        // In the middle of "é".
+        assert_eq!(PositionEncoding::Utf32.index_of_byte(line, 2), Some(2));
+    }
+
+    #[test]
+    fn byte_of_index() {
+        let line = "aé𝄞b".as_bytes();
+        assert_eq!(PositionEncoding::Utf16.byte_of_index(line, 2), Some(3));
+        assert_eq!(PositionEncoding::Utf16.byte_of_index(line, 5), Some(8));
+        assert_eq!(PositionEncoding::Utf32.byte_of_index(line, 3), Some(7));
+        assert_eq!(PositionEncoding::Utf16.byte_of_index(line, 6), None);
+
+        // In the middle of the surrogate pair.
+        assert_eq!(PositionEncoding::Utf16.byte_of_index(line, 3), Some(7));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let line = b"a\xffb\xe2\x82";
+        assert_eq!(PositionEncoding::Utf16.len(line), 5);
+        assert_eq!(PositionEncoding::Utf32.byte_of_index(line, 2), Some(2));
+    }
+
+    #[test]
+    fn lsp_roundtrip() {
+        let line = "𝄞x".as_bytes();
+        let pos = Position::new(4, 4);
+        let lsp = pos.to_lsp(line).unwrap();
+        assert_eq!(lsp, LspPosition { line: 4, character: 2 });
+        assert_eq!(Position::from_lsp(lsp, line), Some(pos));
+    }
+}
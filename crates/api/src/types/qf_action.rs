@@ -0,0 +1,40 @@
+use types::Object;
+
+/// How [`set_qflist`](crate::set_qflist) and
+/// [`Window::set_loclist`](crate::Window::set_loclist) modify the list, i.e.
+/// the `action` argument of `setqflist()`.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum QfAction {
+    /// Replaces the items of the list.
+    #[default]
+    Replace,
+
+    /// Appends the items to the list.
+    Append,
+
+    /// Creates a new list after the last one in the stack.
+    New,
+
+    /// Frees all the lists in the stack.
+    Free,
+}
+
+impl QfAction {
+    #[inline]
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Replace => "r",
+            Self::Append => "a",
+            Self::New => " ",
+            Self::Free => "f",
+        }
+    }
+}
+
+impl From<QfAction> for Object {
+    #[inline]
+    fn from(action: QfAction) -> Self {
+        action.as_str().into()
+    }
+}
@@ -0,0 +1,245 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use types::{
+    conversion::{self, FromObject, ToObject},
+    serde::Deserializer,
+    Dictionary,
+    Integer,
+    Object,
+};
+
+use crate::serde_utils as utils;
+use crate::Buffer;
+
+/// An entry of a quickfix or location list.
+///
+/// Lines and columns are 1-indexed like in Vimscript, with 0 meaning that
+/// they're not set.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QfItem {
+    /// The buffer the entry refers to.
+    pub buffer: Option<Buffer>,
+
+    /// The name of the file the entry refers to. Only used when setting a
+    /// list and [`buffer`](QfItem::buffer) is not set, lists returned by
+    /// Neovim only contain the buffer.
+    pub filename: Option<PathBuf>,
+
+    /// The module name displayed instead of the file name.
+    pub module: Option<String>,
+
+    /// The 1-indexed line number.
+    pub lnum: usize,
+
+    /// The 1-indexed line number of the end of the entry.
+    pub end_lnum: usize,
+
+    /// The 1-indexed column number.
+    pub col: usize,
+
+    /// The 1-indexed column number of the end of the entry.
+    pub end_col: usize,
+
+    /// Whether [`col`](QfItem::col) is a screen column instead of a byte
+    /// index.
+    pub vcol: bool,
+
+    /// The error number.
+    pub nr: Option<u32>,
+
+    /// A search pattern used to locate the entry.
+    pub pattern: Option<String>,
+
+    /// The description of the entry.
+    pub text: String,
+
+    /// The type of the entry.
+    pub kind: QfItemKind,
+
+    /// Whether the entry is a recognized error message. Invalid entries are
+    /// displayed as plain text and are skipped when navigating the list.
+    pub valid: bool,
+}
+
+impl Default for QfItem {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            buffer: None,
+            filename: None,
+            module: None,
+            lnum: 0,
+            end_lnum: 0,
+            col: 0,
+            end_col: 0,
+            vcol: false,
+            nr: None,
+            pattern: None,
+            text: String::new(),
+            kind: QfItemKind::default(),
+            valid: true,
+        }
+    }
+}
+
+/// The type of a [`QfItem`].
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum QfItemKind {
+    #[default]
+    None,
+    Error,
+    Warning,
+    Info,
+    Note,
+    Hint,
+    Other(char),
+}
+
+impl From<char> for QfItemKind {
+    #[inline]
+    fn from(ch: char) -> Self {
+        match ch {
+            'E' | 'e' => Self::Error,
+            'W' | 'w' => Self::Warning,
+            'I' | 'i' => Self::Info,
+            'N' | 'n' => Self::Note,
+            'H' | 'h' => Self::Hint,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl QfItemKind {
+    #[inline]
+    fn to_char(self) -> Option<char> {
+        match self {
+            Self::None => None,
+            Self::Error => Some('E'),
+            Self::Warning => Some('W'),
+            Self::Info => Some('I'),
+            Self::Note => Some('N'),
+            Self::Hint => Some('H'),
+            Self::Other(ch) => Some(ch),
+        }
+    }
+}
+
+impl From<&QfItem> for Dictionary {
+    fn from(item: &QfItem) -> Self {
+        let mut dict = Dictionary::new();
+
+        if let Some(buffer) = &item.buffer {
+            dict.insert("bufnr", buffer.0);
+        }
+        if let Some(filename) = &item.filename {
+            dict.insert("filename", types::String::from(filename.as_path()));
+        }
+        if let Some(module) = &item.module {
+            dict.insert("module", module.as_str());
+        }
+        if let Some(nr) = item.nr {
+            dict.insert("nr", nr as Integer);
+        }
+        if let Some(pattern) = &item.pattern {
+            dict.insert("pattern", pattern.as_str());
+        }
+        if let Some(kind) = item.kind.to_char() {
+            dict.insert("type", kind);
+        }
+
+        dict.insert("lnum", item.lnum as Integer);
+        dict.insert("end_lnum", item.end_lnum as Integer);
+        dict.insert("col", item.col as Integer);
+        dict.insert("end_col", item.end_col as Integer);
+        dict.insert("vcol", item.vcol as Integer);
+        dict.insert("text", item.text.as_str());
+        dict.insert("valid", item.valid as Integer);
+
+        dict
+    }
+}
+
+impl ToObject for QfItem {
+    fn to_object(self) -> Result<Object, conversion::Error> {
+        Ok(Dictionary::from(&self).into())
+    }
+}
+
+/// A quickfix entry as returned by `getqflist()`.
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawQfItem {
+    bufnr: i32,
+    module: String,
+    lnum: usize,
+    end_lnum: usize,
+    col: usize,
+    end_col: usize,
+    #[serde(deserialize_with = "utils::bool_from_int")]
+    vcol: bool,
+    nr: i64,
+    pattern: String,
+    text: String,
+    #[serde(deserialize_with = "utils::char_from_string")]
+    r#type: Option<char>,
+    #[serde(deserialize_with = "utils::bool_from_int")]
+    valid: bool,
+}
+
+impl Default for RawQfItem {
+    fn default() -> Self {
+        Self {
+            bufnr: 0,
+            module: String::new(),
+            lnum: 0,
+            end_lnum: 0,
+            col: 0,
+            end_col: 0,
+            vcol: false,
+            nr: 0,
+            pattern: String::new(),
+            text: String::new(),
+            r#type: None,
+            valid: true,
+        }
+    }
+}
+
+impl From<RawQfItem> for QfItem {
+    fn from(raw: RawQfItem) -> Self {
+        let non_empty = |s: String| (!s.is_empty()).then_some(s);
+
+        Self {
+            buffer: (raw.bufnr > 0).then(|| Buffer::from(raw.bufnr)),
+            filename: None,
+            module: non_empty(raw.module),
+            lnum: raw.lnum,
+            end_lnum: raw.end_lnum,
+            col: raw.col,
+            end_col: raw.end_col,
+            vcol: raw.vcol,
+            nr: u32::try_from(raw.nr).ok().filter(|&nr| nr > 0),
+            pattern: non_empty(raw.pattern),
+            text: raw.text,
+            kind: raw.r#type.map(QfItemKind::from).unwrap_or_default(),
+            valid: raw.valid,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for QfItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        RawQfItem::deserialize(deserializer).map(Into::into)
+    }
+}
+
+impl FromObject for QfItem {
+    fn from_object(obj: Object) -> Result<Self, conversion::Error> {
+        Self::deserialize(Deserializer::new(obj)).map_err(Into::into)
+    }
+}
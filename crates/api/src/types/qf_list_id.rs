@@ -0,0 +1,34 @@
+use types::{Dictionary, Integer};
+
+/// Identifies a list in the quickfix or location list stack.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum QfListId {
+    /// The current list.
+    #[default]
+    Current,
+
+    /// The list at the given 1-indexed position in the stack.
+    Nr(u32),
+
+    /// The list with the given unique id.
+    Id(u32),
+
+    /// The last list in the stack.
+    Last,
+}
+
+impl From<QfListId> for Dictionary {
+    /// Returns the `what` argument of `getqflist()` selecting the list.
+    fn from(id: QfListId) -> Self {
+        let mut dict = Dictionary::new();
+
+        match id {
+            QfListId::Current => dict.insert("nr", 0 as Integer),
+            QfListId::Nr(nr) => dict.insert("nr", nr as Integer),
+            QfListId::Id(id) => dict.insert("id", id as Integer),
+            QfListId::Last => dict.insert("nr", "$"),
+        }
+
+        dict
+    }
+}
@@ -0,0 +1,122 @@
+use serde::Deserialize;
+use types::{
+    conversion::{self, FromObject},
+    serde::Deserializer,
+    Object,
+};
+
+use super::QfItem;
+use crate::{Buffer, Window};
+
+/// Informations about a quickfix or location list, as returned by
+/// `getqflist({'all': 1})`.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QfListInfos {
+    /// The number of changes made to the list.
+    pub changedtick: u32,
+
+    /// The context attached to the list, or nil if it doesn't have one.
+    pub context: Object,
+
+    /// The window used to display the files of a location list, if any.
+    pub filewinid: Option<Window>,
+
+    /// The unique id of the list. This is zero if the list doesn't exist.
+    pub id: u32,
+
+    /// The 1-indexed index of the current item in the list.
+    pub idx: usize,
+
+    /// The items of the list.
+    pub items: Vec<QfItem>,
+
+    /// The 1-indexed position of the list in the stack.
+    pub nr: u32,
+
+    /// The buffer displaying the list in the quickfix window, if any.
+    pub qfbufnr: Option<Buffer>,
+
+    /// The function used to display the items of the list, or nil if it
+    /// doesn't have one.
+    pub quickfixtextfunc: Object,
+
+    /// The number of items in the list.
+    pub size: usize,
+
+    /// The title of the list.
+    pub title: String,
+
+    /// The quickfix window displaying the list, if it's open.
+    pub winid: Option<Window>,
+}
+
+#[derive(Deserialize)]
+struct RawQfListInfos {
+    #[serde(default)]
+    changedtick: u32,
+    #[serde(default)]
+    context: Object,
+    #[serde(default)]
+    filewinid: i32,
+    #[serde(default)]
+    id: u32,
+    #[serde(default)]
+    idx: usize,
+    #[serde(default)]
+    items: Vec<QfItem>,
+    #[serde(default)]
+    nr: u32,
+    #[serde(default)]
+    qfbufnr: i32,
+    #[serde(default)]
+    quickfixtextfunc: Object,
+    #[serde(default)]
+    size: usize,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    winid: i32,
+}
+
+/// Neovim uses an empty string for the context and the `quickfixtextfunc`
+/// of lists that don't have one.
+fn empty_string_is_nil(obj: Object) -> Object {
+    let is_empty_string = matches!(obj.kind(), types::ObjectKind::String)
+        && types::String::from_object(obj.clone())
+            .is_ok_and(|s| s.as_bytes().is_empty());
+
+    if is_empty_string {
+        Object::nil()
+    } else {
+        obj
+    }
+}
+
+impl From<RawQfListInfos> for QfListInfos {
+    fn from(raw: RawQfListInfos) -> Self {
+        Self {
+            changedtick: raw.changedtick,
+            context: empty_string_is_nil(raw.context),
+            filewinid: (raw.filewinid > 0)
+                .then(|| Window::from(raw.filewinid)),
+            id: raw.id,
+            idx: raw.idx,
+            items: raw.items,
+            nr: raw.nr,
+            qfbufnr: (raw.qfbufnr > 0).then(|| Buffer::from(raw.qfbufnr)),
+            quickfixtextfunc: empty_string_is_nil(raw.quickfixtextfunc),
+            size: raw.size,
+            title: raw.title,
+            winid: (raw.winid > 0).then(|| Window::from(raw.winid)),
+        }
+    }
+}
+
+impl FromObject for QfListInfos {
+    fn from_object(obj: Object) -> Result<Self, conversion::Error> {
+        RawQfListInfos::deserialize(Deserializer::new(obj))
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,52 @@
+use serde::Deserialize;
+use types::{
+    conversion::{self, FromObject},
+    serde::Deserializer,
+    Object,
+};
+
+use crate::serde_utils as utils;
+use crate::Window;
+
+/// Arguments passed to the function set as the
+/// [`quickfixtextfunc`](crate::opts::SetQfListOptsBuilder::quickfixtextfunc)
+/// of a list. The function should return the text to display for each item
+/// from [`start_idx`](QfTextFuncArgs::start_idx) to
+/// [`end_idx`](QfTextFuncArgs::end_idx).
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+pub struct QfTextFuncArgs {
+    /// Whether the function is called for a quickfix list. If `false` it's
+    /// called for the location list of [`winid`](QfTextFuncArgs::winid).
+    #[serde(deserialize_with = "utils::bool_from_int")]
+    pub quickfix: bool,
+
+    /// The window whose location list is displayed.
+    pub winid: Window,
+
+    /// The id of the list.
+    pub id: u32,
+
+    /// The 1-indexed index of the first item to display.
+    pub start_idx: usize,
+
+    /// The 1-indexed index of the last item to display.
+    pub end_idx: usize,
+}
+
+impl FromObject for QfTextFuncArgs {
+    fn from_object(obj: Object) -> Result<Self, conversion::Error> {
+        Self::deserialize(Deserializer::new(obj)).map_err(Into::into)
+    }
+}
+
+impl luajit::Poppable for QfTextFuncArgs {
+    unsafe fn pop(
+        lstate: *mut luajit::ffi::lua_State,
+    ) -> Result<Self, luajit::Error> {
+        let obj = Object::pop(lstate)?;
+
+        Self::from_object(obj)
+            .map_err(luajit::Error::pop_error_from_err::<Self, _>)
+    }
+}
@@ -0,0 +1,36 @@
+use super::RegisterType;
+
+/// The contents of a register, as returned by
+/// [`Register::get()`](crate::Register::get).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RegisterContents {
+    /// The lines stored in the register. NUL bytes are represented by
+    /// newlines, like in `getreg(regname, 1, 1)`.
+    pub lines: Vec<String>,
+
+    /// The type of the register.
+    pub kind: RegisterType,
+}
+
+impl RegisterContents {
+    /// Creates new register contents.
+    #[inline]
+    pub fn new<L, S>(lines: L, kind: RegisterType) -> Self
+    where
+        L: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self { lines: lines.into_iter().map(Into::into).collect(), kind }
+    }
+
+    /// Returns the width of a blockwise register in display cells, i.e. the
+    /// width of its longest line, or `None` if the register isn't
+    /// blockwise.
+    #[inline]
+    pub fn width(&self) -> Option<usize> {
+        match self.kind {
+            RegisterType::BlockwiseVisual(width) => width,
+            _ => None,
+        }
+    }
+}
@@ -1,18 +1,32 @@
-use serde::{ser, Serialize};
-use types::{conversion::FromObject, serde::Serializer};
+use serde::{de, ser, Deserialize, Serialize};
+use types::{
+    conversion::{self, FromObject},
+    serde::{Deserializer, Serializer},
+    Object,
+};
 
+/// The type of a register, i.e. the `regtype` argument of `setreg()` and
+/// [`put()`](crate::put).
+///
+/// Can be deserialized both from the values returned by `getregtype()`
+/// (`"v"`, `"V"` and `"<C-V>{width}"`) and from the ones accepted by
+/// `setreg()` (`"c"`, `"l"` and `"b{width}"`).
 #[non_exhaustive]
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize)]
 pub enum RegisterType {
+    /// Blockwise, with the width of the block in display cells if known.
     #[serde(serialize_with = "serialize_blockwise")]
     BlockwiseVisual(Option<usize>),
 
+    /// Characterwise.
     #[serde(rename = "c")]
     Charwise,
 
+    /// Linewise.
     #[serde(rename = "l")]
     Linewise,
 
+    /// Unknown, or guessed from the contents when setting a register.
     #[serde(rename = "")]
     Guess,
 }
@@ -42,3 +56,73 @@ impl From<RegisterType> for types::String {
             .expect("`RegisterType` is serialized into a string")
     }
 }
+
+impl RegisterType {
+    /// Parses a register type as returned by `getregtype()` or accepted by
+    /// `setreg()`.
+    fn parse(regtype: &str) -> Option<Self> {
+        let mut chars = regtype.chars();
+
+        let Some(first) = chars.next() else {
+            return Some(Self::Guess);
+        };
+
+        let rest = chars.as_str();
+
+        match first {
+            'c' | 'v' if rest.is_empty() => Some(Self::Charwise),
+            'l' | 'V' if rest.is_empty() => Some(Self::Linewise),
+            'b' | '\x16' if rest.is_empty() => {
+                Some(Self::BlockwiseVisual(None))
+            },
+            'b' | '\x16' => {
+                rest.parse().ok().map(Some).map(Self::BlockwiseVisual)
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RegisterType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let regtype = String::deserialize(deserializer)?;
+
+        Self::parse(&regtype).ok_or_else(|| {
+            de::Error::invalid_value(
+                de::Unexpected::Str(&regtype),
+                &"a register type",
+            )
+        })
+    }
+}
+
+impl FromObject for RegisterType {
+    fn from_object(obj: Object) -> Result<Self, conversion::Error> {
+        Self::deserialize(Deserializer::new(obj)).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_regtype() {
+        assert_eq!(RegisterType::parse("v"), Some(RegisterType::Charwise));
+        assert_eq!(RegisterType::parse("V"), Some(RegisterType::Linewise));
+        assert_eq!(RegisterType::parse(""), Some(RegisterType::Guess));
+        assert_eq!(
+            RegisterType::parse("\x1612"),
+            Some(RegisterType::BlockwiseVisual(Some(12)))
+        );
+        assert_eq!(
+            RegisterType::parse("b"),
+            Some(RegisterType::BlockwiseVisual(None))
+        );
+        assert_eq!(RegisterType::parse("x"), None);
+        assert_eq!(RegisterType::parse("\x16foo"), None);
+    }
+}
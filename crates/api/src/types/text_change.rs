@@ -0,0 +1,352 @@
+use serde::Serialize;
+use types::{
+    conversion::{self, ToObject},
+    serde::Serializer,
+    Object,
+};
+
+use super::{Position, PositionEncoding};
+use crate::opts::{OnBytesArgs, OnLinesArgs};
+use crate::Buffer;
+
+/// A change to the lines of a buffer, as received by the
+/// [`on_lines`](crate::opts::BufAttachOptsBuilder::on_lines) callback.
+///
+/// Can be created from the callback's arguments with [`From`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinesChange {
+    /// The buffer that changed.
+    pub buffer: Buffer,
+
+    /// The value of `b:changedtick` after the change.
+    pub changedtick: u32,
+
+    /// The first row that changed (0-indexed).
+    pub first_row: usize,
+
+    /// The row after the last one that changed, before the change.
+    pub last_row: usize,
+
+    /// The row after the last one that changed, after the change.
+    pub new_last_row: usize,
+
+    /// The size in bytes of the replaced lines, including their newlines.
+    pub old_byte_count: usize,
+
+    /// The number of UTF-32 codepoints in the replaced lines, if
+    /// [`utf_sizes`](crate::opts::BufAttachOptsBuilder::utf_sizes) was
+    /// set.
+    pub old_utf32_count: Option<usize>,
+
+    /// The number of UTF-16 code units in the replaced lines, if
+    /// [`utf_sizes`](crate::opts::BufAttachOptsBuilder::utf_sizes) was
+    /// set.
+    pub old_utf16_count: Option<usize>,
+}
+
+impl LinesChange {
+    /// Returns the number of lines that were replaced.
+    pub fn old_line_count(&self) -> usize {
+        self.last_row - self.first_row
+    }
+
+    /// Returns the number of lines that replaced them.
+    pub fn new_line_count(&self) -> usize {
+        self.new_last_row - self.first_row
+    }
+}
+
+impl From<OnLinesArgs> for LinesChange {
+    fn from(args: OnLinesArgs) -> Self {
+        let (
+            _,
+            buffer,
+            changedtick,
+            first_row,
+            last_row,
+            new_last_row,
+            old_byte_count,
+            old_utf32_count,
+            old_utf16_count,
+        ) = args;
+
+        Self {
+            buffer,
+            changedtick,
+            first_row,
+            last_row,
+            new_last_row,
+            old_byte_count,
+            old_utf32_count,
+            old_utf16_count,
+        }
+    }
+}
+
+/// A position in a buffer, as reported by the
+/// [`on_bytes`](crate::opts::BufAttachOptsBuilder::on_bytes) callback.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct BytePosition {
+    /// The 0-indexed row.
+    pub row: usize,
+
+    /// The 0-indexed byte column within the row.
+    pub col: usize,
+
+    /// The byte offset from the start of the buffer, counting one byte for
+    /// every newline.
+    pub offset: usize,
+}
+
+impl From<BytePosition> for Position {
+    fn from(pos: BytePosition) -> Self {
+        Self::new(pos.row, pos.col)
+    }
+}
+
+/// A change to the text of a buffer, as received by the
+/// [`on_bytes`](crate::opts::BufAttachOptsBuilder::on_bytes) callback.
+///
+/// The text between [`start`](BytesChange::start) and
+/// [`old_end`](BytesChange::old_end) was replaced by the text between
+/// [`start`](BytesChange::start) and [`new_end`](BytesChange::new_end). Can
+/// be created from the callback's arguments with [`From`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BytesChange {
+    /// The buffer that changed.
+    pub buffer: Buffer,
+
+    /// The value of `b:changedtick` after the change.
+    pub changedtick: u32,
+
+    /// The start of the changed text.
+    pub start: BytePosition,
+
+    /// The end of the replaced text, before the change.
+    pub old_end: BytePosition,
+
+    /// The end of the new text, after the change.
+    pub new_end: BytePosition,
+}
+
+impl BytesChange {
+    /// Returns the size in bytes of the replaced text.
+    pub fn old_byte_len(&self) -> usize {
+        self.old_end.offset - self.start.offset
+    }
+
+    /// Returns the size in bytes of the new text.
+    pub fn new_byte_len(&self) -> usize {
+        self.new_end.offset - self.start.offset
+    }
+
+    /// Returns whether the change only inserted text.
+    pub fn is_insertion(&self) -> bool {
+        self.old_end == self.start
+    }
+
+    /// Returns whether the change only deleted text.
+    pub fn is_deletion(&self) -> bool {
+        self.new_end == self.start
+    }
+
+    /// Converts the change into an LSP `TextDocumentContentChangeEvent`
+    /// with UTF-16 columns, reading the new text from the buffer.
+    ///
+    /// Since the callback is executed after the buffer has changed, the
+    /// replaced text can't be read from it anymore and has to be passed in
+    /// `old_text`, e.g. from a [`BufferMirror`](crate::BufferMirror).
+    ///
+    /// This has to be called before the buffer changes again, or the text
+    /// read from it could be out of date.
+    pub fn to_content_change(
+        &self,
+        old_text: &[u8],
+    ) -> crate::Result<TextDocumentContentChangeEvent> {
+        let start_line = self
+            .buffer
+            .get_lines(self.start.row..self.start.row + 1, false)?
+            .next()
+            .map(|line| line.as_bytes().to_owned())
+            .unwrap_or_default();
+
+        let start_prefix = start_line.get(..self.start.col).unwrap_or(&[]);
+
+        let start = LspPosition {
+            line: self.start.row as u32,
+            character: PositionEncoding::Utf16.len(start_prefix) as u32,
+        };
+
+        let end = LspPosition {
+            line: self.old_end.row as u32,
+            character: match old_text.iter().rposition(|&b| b == b'\n') {
+                Some(idx) => {
+                    PositionEncoding::Utf16.len(&old_text[idx + 1..]) as u32
+                },
+                None => {
+                    start.character
+                        + PositionEncoding::Utf16.len(old_text) as u32
+                },
+            },
+        };
+
+        let text = self.read_new_text()?;
+
+        Ok(TextDocumentContentChangeEvent {
+            range: LspRange { start, end },
+            range_length: PositionEncoding::Utf16.len(old_text) as u32,
+            text: String::from_utf8_lossy(&text).into_owned(),
+        })
+    }
+
+    /// Reads the text between `start` and `new_end` from the buffer.
+    pub(crate) fn read_new_text(&self) -> crate::Result<Vec<u8>> {
+        if self.is_deletion() {
+            return Ok(Vec::new());
+        }
+
+        let BytePosition { row: start_row, col: start_col, .. } = self.start;
+        let BytePosition { row: end_row, col: end_col, .. } = self.new_end;
+
+        // The last line doesn't need to be read if the text ends with a
+        // newline.
+        let last_row = if end_col == 0 { end_row } else { end_row + 1 };
+
+        let lines = self.buffer.get_lines(start_row..last_row, false)?;
+
+        let mut text = Vec::with_capacity(self.new_byte_len());
+
+        for (row, line) in (start_row..).zip(lines) {
+            let line = line.as_bytes();
+            let start = if row == start_row { start_col } else { 0 };
+
+            if row == end_row {
+                text.extend_from_slice(
+                    line.get(start..end_col).unwrap_or(&[]),
+                );
+            } else {
+                text.extend_from_slice(line.get(start..).unwrap_or(&[]));
+                text.push(b'\n');
+            }
+        }
+
+        Ok(text)
+    }
+}
+
+impl From<OnBytesArgs> for BytesChange {
+    fn from(args: OnBytesArgs) -> Self {
+        let (
+            _,
+            buffer,
+            changedtick,
+            start_row,
+            start_col,
+            start_offset,
+            old_end_row,
+            old_end_col,
+            old_byte_len,
+            new_end_row,
+            new_end_col,
+            new_byte_len,
+        ) = args;
+
+        let start = BytePosition {
+            row: start_row,
+            col: start_col,
+            offset: start_offset,
+        };
+
+        let end = |rows: usize, col: usize, len: usize| BytePosition {
+            row: start_row + rows,
+            // The end column is relative to the start column if the text
+            // doesn't span multiple rows.
+            col: if rows == 0 { start_col + col } else { col },
+            offset: start_offset + len,
+        };
+
+        Self {
+            buffer,
+            changedtick,
+            start,
+            old_end: end(old_end_row, old_end_col, old_byte_len),
+            new_end: end(new_end_row, new_end_col, new_byte_len),
+        }
+    }
+}
+
+/// A position in a text document as defined by the [Language Server
+/// Protocol][1], with a UTF-16 column.
+///
+/// [1]: https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#position
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Serialize)]
+pub struct LspPosition {
+    /// The 0-indexed line.
+    pub line: u32,
+
+    /// The 0-indexed UTF-16 column.
+    pub character: u32,
+}
+
+/// A range in a text document as defined by the [Language Server
+/// Protocol][1].
+///
+/// [1]: https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#range
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Serialize)]
+pub struct LspRange {
+    /// The start of the range.
+    pub start: LspPosition,
+
+    /// The end of the range, exclusive.
+    pub end: LspPosition,
+}
+
+/// An incremental change to a text document as defined by the [Language
+/// Server Protocol][1].
+///
+/// [1]: https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocumentContentChangeEvent
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentContentChangeEvent {
+    /// The range of the document that changed.
+    pub range: LspRange,
+
+    /// The length of the replaced text in UTF-16 code units.
+    pub range_length: u32,
+
+    /// The new text for the range.
+    pub text: String,
+}
+
+impl ToObject for TextDocumentContentChangeEvent {
+    fn to_object(self) -> Result<Object, conversion::Error> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_change_from_args() {
+        let buffer = Buffer::from(1);
+
+        // "foo\nbar" replaced by "X" starting at (1, 2).
+        let args = ("bytes".into(), buffer, 3, 1, 2, 6, 1, 3, 7, 0, 1, 1);
+        let change = BytesChange::from(args);
+
+        assert_eq!(change.start, BytePosition { row: 1, col: 2, offset: 6 });
+        assert_eq!(
+            change.old_end,
+            BytePosition { row: 2, col: 3, offset: 13 }
+        );
+        assert_eq!(change.new_end, BytePosition { row: 1, col: 3, offset: 7 });
+        assert_eq!(change.old_byte_len(), 7);
+        assert_eq!(change.new_byte_len(), 1);
+        assert!(!change.is_insertion());
+        assert!(!change.is_deletion());
+    }
+}
@@ -0,0 +1,81 @@
+use serde::Deserialize;
+use types::{
+    conversion::{self, FromObject},
+    serde::Deserializer,
+    Object,
+};
+
+use super::{RegisterContents, RegisterType};
+use crate::Register;
+
+/// The contents of `v:event` during a `TextYankPost` autocommand.
+///
+/// Can be read with [`TextYankPostEvent::get()`] from within the
+/// autocommand's callback.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize)]
+pub struct TextYankPostEvent {
+    /// Whether the motion is inclusive.
+    pub inclusive: bool,
+
+    /// The operator that caused the yank, e.g. `"y"`, `"d"` or `"c"`.
+    pub operator: String,
+
+    /// The text that was yanked or deleted.
+    pub regcontents: Vec<String>,
+
+    /// The register the text was stored in.
+    #[serde(deserialize_with = "deserialize_regname")]
+    pub regname: Register,
+
+    /// The type of the register.
+    pub regtype: RegisterType,
+
+    /// Whether the operation was done in Visual mode.
+    pub visual: bool,
+}
+
+impl TextYankPostEvent {
+    /// Reads the event from `v:event`. Only valid within a `TextYankPost`
+    /// autocommand.
+    pub fn get() -> crate::Result<Self> {
+        crate::get_vvar("event")
+    }
+
+    /// Returns the yanked text together with its register type.
+    pub fn contents(&self) -> RegisterContents {
+        RegisterContents {
+            lines: self.regcontents.clone(),
+            kind: self.regtype.clone(),
+        }
+    }
+}
+
+fn deserialize_regname<'de, D>(deserializer: D) -> Result<Register, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    let mut chars = name.chars();
+
+    match (chars.next(), chars.next()) {
+        // The unnamed register is reported as an empty string.
+        (None, _) => Ok(Register::Unnamed),
+        (Some(ch), None) => Register::from_char(ch).ok_or_else(|| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Char(ch),
+                &"a register name",
+            )
+        }),
+        _ => Err(serde::de::Error::invalid_length(
+            name.chars().count(),
+            &"empty string or string with a single character",
+        )),
+    }
+}
+
+impl FromObject for TextYankPostEvent {
+    fn from_object(obj: Object) -> Result<Self, conversion::Error> {
+        Self::deserialize(Deserializer::new(obj)).map_err(Into::into)
+    }
+}
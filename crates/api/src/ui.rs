@@ -0,0 +1,408 @@
+//! Bindings to the [`vim.ui`][1] Lua module.
+//!
+//! [`select()`] and [`input()`] prompt the user through `vim.ui.select()`
+//! and `vim.ui.input()`, which other plugins can replace to change how the
+//! prompts are displayed. Their results are passed to a callback, or can
+//! be awaited with [`select_async()`] and [`input_async()`].
+//!
+//! Those functions can themselves be replaced by a Rust implementation with
+//! [`set_select()`] and [`set_input()`].
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::api::{opts::SelectOpts, ui};
+//!
+//! let opts = SelectOpts::builder()
+//!     .prompt("Pick a fruit")
+//!     .format_item(|fruit: &&str| fruit.to_uppercase())
+//!     .build();
+//!
+//! ui::select(vec!["apple", "banana"], &opts, |choice| {
+//!     if let Some((idx, fruit)) = choice {
+//!         nvim_oxi::print!("picked {fruit} ({idx})");
+//!     }
+//!     Ok::<_, std::convert::Infallible>(())
+//! })?;
+//! ```
+//!
+//! [1]: https://neovim.io/doc/user/lua.html#vim.ui
+
+use core::cell::RefCell;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::convert::Infallible;
+use std::error::Error as StdError;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use types::{Dictionary, Function, Integer, Object};
+
+use crate::lua_object::LuaObject;
+use crate::opts::{InputOpts, SelectOpts};
+use crate::utils::{call_vim_function, set_vim_field};
+use crate::{Error, Result};
+
+/// Binding to [`vim.ui.select()`][1].
+///
+/// Prompts the user to pick one of `items`, calling `on_choice` with the
+/// index of the chosen item and the item itself, or with `None` if the
+/// prompt was cancelled.
+///
+/// The items are displayed with the
+/// [`format_item`](crate::opts::SelectOptsBuilder::format_item) function
+/// of `opts`, which defaults to their [`Display`](core::fmt::Display)
+/// implementation. Items which don't implement it need their options to be
+/// created with [`SelectOpts::builder_with()`].
+///
+/// [1]: https://neovim.io/doc/user/lua.html#vim.ui.select()
+pub fn select<T, F, E>(
+    items: Vec<T>,
+    opts: &SelectOpts<T>,
+    on_choice: F,
+) -> Result<()>
+where
+    T: 'static,
+    F: FnOnce(Option<(usize, T)>) -> std::result::Result<(), E> + 'static,
+    E: StdError + 'static,
+{
+    let indices = (1..=items.len() as Integer).collect::<Vec<_>>();
+
+    // Lua is only given the indices of the items, which are taken out of
+    // the vector once one is chosen.
+    let items = Rc::new(RefCell::new(Some(items)));
+
+    let format_item = {
+        let items = Rc::clone(&items);
+        let format = opts.format_item.clone();
+        Function::<Integer, String>::from_fn(move |idx| {
+            let items = items.borrow();
+            let item = items
+                .as_ref()
+                .and_then(|items| items.get(index(idx)?))
+                .ok_or_else(|| Error::custom(format!("no item at {idx}")))?;
+            Ok::<_, Error>(format(item))
+        })
+    };
+
+    let on_choice =
+        Function::<(Option<Object>, Option<Integer>), ()>::from_fn_once(
+            move |(_, idx)| {
+                let items = items.borrow_mut().take().unwrap_or_default();
+                let choice = idx.and_then(index).and_then(|idx| {
+                    items.into_iter().nth(idx).map(|item| (idx, item))
+                });
+                on_choice(choice)
+            },
+        );
+
+    let mut lua_opts = Dictionary::from(opts);
+    lua_opts.insert("format_item", format_item.clone());
+
+    let ret = call_vim_function::<_, ()>(
+        "ui.select",
+        (indices, lua_opts, on_choice.clone()),
+    );
+
+    // `vim.ui.select()` holds on to the functions until it's done with
+    // them.
+    format_item.remove_from_lua_registry();
+    on_choice.remove_from_lua_registry();
+
+    ret
+}
+
+/// Binding to [`vim.ui.input()`][1].
+///
+/// Prompts the user for some text, calling `on_confirm` with it, or with
+/// `None` if the prompt was cancelled.
+///
+/// [1]: https://neovim.io/doc/user/lua.html#vim.ui.input()
+pub fn input<F, E>(opts: &InputOpts, on_confirm: F) -> Result<()>
+where
+    F: FnOnce(Option<String>) -> std::result::Result<(), E> + 'static,
+    E: StdError + 'static,
+{
+    let on_confirm = Function::<Option<String>, ()>::from_fn_once(on_confirm);
+
+    let ret = call_vim_function::<_, ()>(
+        "ui.input",
+        (Dictionary::from(opts), on_confirm.clone()),
+    );
+
+    on_confirm.remove_from_lua_registry();
+
+    ret
+}
+
+/// Like [`select()`], but returns a future resolving to the choice of the
+/// user instead of taking a callback.
+///
+/// The prompt is displayed right away, not when the future is first
+/// polled. The future never resolves if the implementation of
+/// `vim.ui.select()` never calls its callback.
+pub fn select_async<T>(
+    items: Vec<T>,
+    opts: &SelectOpts<T>,
+) -> Result<Response<Option<(usize, T)>>>
+where
+    T: 'static,
+{
+    let (respond, response) = Response::new();
+    select(items, opts, respond)?;
+    Ok(response)
+}
+
+/// Like [`input()`], but returns a future resolving to the text entered by
+/// the user instead of taking a callback.
+///
+/// The prompt is displayed right away, not when the future is first
+/// polled. The future never resolves if the implementation of
+/// `vim.ui.input()` never calls its callback.
+pub fn input_async(opts: &InputOpts) -> Result<Response<Option<String>>> {
+    let (respond, response) = Response::new();
+    input(opts, respond)?;
+    Ok(response)
+}
+
+/// A future resolving to the answer given to a prompt, returned by
+/// [`select_async()`] and [`input_async()`].
+///
+/// It can be sent to other threads if `T` can, but the answer is always
+/// given on the Neovim thread.
+pub struct Response<T> {
+    slot: Arc<Mutex<Slot<T>>>,
+}
+
+struct Slot<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+}
+
+impl<T> Response<T> {
+    /// Returns the callback answering the prompt together with the future
+    /// resolving to its argument.
+    fn new() -> (impl FnOnce(T) -> std::result::Result<(), Infallible>, Self) {
+        let slot = Arc::new(Mutex::new(Slot { value: None, waker: None }));
+
+        let respond = {
+            let slot = Arc::clone(&slot);
+            move |value| {
+                let mut slot = slot.lock().unwrap();
+                slot.value = Some(value);
+                if let Some(waker) = slot.waker.take() {
+                    waker.wake();
+                }
+                Ok(())
+            }
+        };
+
+        (respond, Self { slot })
+    }
+}
+
+impl<T> Future for Response<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut slot = self.slot.lock().unwrap();
+
+        match slot.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+impl<T> fmt::Debug for Response<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let is_ready = self.slot.lock().unwrap().value.is_some();
+        f.debug_struct("Response").field("is_ready", &is_ready).finish()
+    }
+}
+
+/// A call to `vim.ui.select()` handled by the function registered with
+/// [`set_select()`].
+pub struct SelectRequest {
+    /// The items to choose from, formatted with the `format_item` function
+    /// given by the caller, or with `tostring()`.
+    pub items: Vec<String>,
+
+    /// The text to display above the items.
+    pub prompt: Option<String>,
+
+    /// A hint about the kind of the items, e.g. `"codeaction"`.
+    pub kind: Option<String>,
+
+    raw_items: Vec<LuaObject>,
+    on_choice: LuaObject,
+}
+
+impl SelectRequest {
+    /// Answers the request with the 0-indexed position of the chosen item
+    /// in [`items`](SelectRequest::items), or with `None` if the prompt was
+    /// cancelled.
+    pub fn choose(self, choice: Option<usize>) -> Result<()> {
+        let choice = choice.and_then(|idx| {
+            let item = self.raw_items.get(idx)?.clone();
+            Some((item, (idx + 1) as Integer))
+        });
+
+        let (item, idx) = choice.unzip();
+
+        self.on_choice.call((item, idx), 0)
+    }
+}
+
+impl fmt::Debug for SelectRequest {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelectRequest")
+            .field("items", &self.items)
+            .field("prompt", &self.prompt)
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A call to `vim.ui.input()` handled by the function registered with
+/// [`set_input()`].
+pub struct InputRequest {
+    /// The text to display before the input.
+    pub prompt: Option<String>,
+
+    /// The text the input starts with.
+    pub default: Option<String>,
+
+    /// The kind of completion to use for the input, e.g. `"file"`.
+    pub completion: Option<String>,
+
+    on_confirm: LuaObject,
+}
+
+impl InputRequest {
+    /// Answers the request with the entered text, or with `None` if the
+    /// prompt was cancelled.
+    pub fn confirm(self, input: Option<String>) -> Result<()> {
+        self.on_confirm.call(input, 0)
+    }
+}
+
+impl fmt::Debug for InputRequest {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InputRequest")
+            .field("prompt", &self.prompt)
+            .field("default", &self.default)
+            .field("completion", &self.completion)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Replaces `vim.ui.select()` with `fun`, which is called for every prompt
+/// opened by Neovim or by other plugins.
+///
+/// The request doesn't have to be answered before `fun` returns, but it
+/// should eventually be answered exactly once with
+/// [`SelectRequest::choose()`].
+pub fn set_select<F, E>(fun: F) -> Result<()>
+where
+    F: FnMut(SelectRequest) -> std::result::Result<(), E> + 'static,
+    E: StdError + 'static,
+{
+    let mut fun = fun;
+
+    let select = Function::<
+        (Vec<LuaObject>, Option<LuaObject>, LuaObject),
+        (),
+    >::from_fn_mut(move |(raw_items, opts, on_choice)| {
+        let request = select_request(raw_items, opts, on_choice)?;
+        fun(request).map_err(Error::custom)
+    });
+
+    set_vim_field("ui.select", select.clone())?;
+    select.remove_from_lua_registry();
+    Ok(())
+}
+
+/// Replaces `vim.ui.input()` with `fun`, which is called for every prompt
+/// opened by Neovim or by other plugins.
+///
+/// The request doesn't have to be answered before `fun` returns, but it
+/// should eventually be answered exactly once with
+/// [`InputRequest::confirm()`].
+pub fn set_input<F, E>(fun: F) -> Result<()>
+where
+    F: FnMut(InputRequest) -> std::result::Result<(), E> + 'static,
+    E: StdError + 'static,
+{
+    let mut fun = fun;
+
+    let input = Function::<(Option<LuaObject>, LuaObject), ()>::from_fn_mut(
+        move |(opts, on_confirm)| {
+            let field = |name| get_string(opts.as_ref(), name);
+            let request = InputRequest {
+                prompt: field("prompt")?,
+                default: field("default")?,
+                completion: field("completion")?,
+                on_confirm,
+            };
+            fun(request).map_err(Error::custom)
+        },
+    );
+
+    set_vim_field("ui.input", input.clone())?;
+    input.remove_from_lua_registry();
+    Ok(())
+}
+
+/// Builds the request passed to the function registered with
+/// [`set_select()`] from the arguments of `vim.ui.select()`.
+fn select_request(
+    raw_items: Vec<LuaObject>,
+    opts: Option<LuaObject>,
+    on_choice: LuaObject,
+) -> Result<SelectRequest> {
+    let format_item = match &opts {
+        Some(opts) => opts.get::<Option<LuaObject>>("format_item")?,
+        None => None,
+    };
+
+    let format_item = match format_item {
+        Some(format_item) => format_item,
+        None => LuaObject::from_global("tostring")?,
+    };
+
+    let items = raw_items
+        .iter()
+        .map(|item| format_item.call::<_, String>(item.clone(), 1))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(SelectRequest {
+        items,
+        prompt: get_string(opts.as_ref(), "prompt")?,
+        kind: get_string(opts.as_ref(), "kind")?,
+        raw_items,
+        on_choice,
+    })
+}
+
+/// Converts the 1-based index of an item into a 0-based one.
+fn index(idx: Integer) -> Option<usize> {
+    usize::try_from(idx).ok()?.checked_sub(1)
+}
+
+/// Returns the string field `name` of the options table of a `vim.ui`
+/// function.
+fn get_string(opts: Option<&LuaObject>, name: &str) -> Result<Option<String>> {
+    match opts {
+        Some(opts) => opts.get(name),
+        None => Ok(None),
+    }
+}
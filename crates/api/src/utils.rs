@@ -1,6 +1,10 @@
 use core::ops::{Bound, RangeBounds};
+use std::ffi::{c_int, CStr, CString};
 
-use types::Integer;
+use luajit::ffi::*;
+use luajit::{cstr, Poppable, Pushable};
+use types::conversion::FromObject;
+use types::{Integer, Object};
 
 #[inline]
 pub(crate) fn range_to_limits<R>(range: R) -> (Integer, Integer)
@@ -22,3 +26,162 @@ where
 
     (start, end)
 }
+
+/// Calls the Lua function at the given path in the `vim` global table, e.g.
+/// `"diagnostic.set"` for `vim.diagnostic.set()`.
+///
+/// This is used to bind the parts of the Lua standard library that aren't
+/// exposed through the C API.
+pub(crate) fn call_vim_function<A, R>(path: &str, args: A) -> crate::Result<R>
+where
+    A: Pushable,
+    R: FromObject,
+{
+    let obj = unsafe {
+        luajit::with_state(move |lstate| {
+            let top = lua_gettop(lstate);
+
+            let ret = push_vim_field(lstate, path).and_then(|()| {
+                if lua_type(lstate, -1) != LUA_TFUNCTION {
+                    return Err(crate::Error::custom(format!(
+                        "vim.{path} is not a function"
+                    )));
+                }
+                let nargs = args.push(lstate).map_err(crate::Error::custom)?;
+                pcall(lstate, nargs, 1)?;
+                Object::pop(lstate).map_err(crate::Error::custom)
+            });
+
+            lua_settop(lstate, top);
+            ret
+        })
+    }?;
+
+    Ok(R::from_object(obj)?)
+}
+
+/// Pushes the value at the given path in the `vim` global table onto the
+/// stack, together with the tables containing it. The caller is responsible
+/// for restoring the stack.
+pub(crate) unsafe fn push_vim_field(
+    lstate: *mut lua_State,
+    path: &str,
+) -> crate::Result<()> {
+    lua_getglobal(lstate, cstr!("vim"));
+
+    for field in path.split('.') {
+        if lua_type(lstate, -1) != LUA_TTABLE {
+            return Err(crate::Error::custom(format!("vim.{path} not found")));
+        }
+        let field = CString::new(field).map_err(crate::Error::custom)?;
+        // This goes through the `__index` metamethod of `vim`, which loads
+        // modules like `vim.diagnostic` on first access.
+        lua_getfield(lstate, -1, field.as_ptr());
+    }
+
+    Ok(())
+}
+
+/// Sets the value at the given path in the `vim` global table, e.g.
+/// `"ui.select"` for `vim.ui.select`.
+pub(crate) fn set_vim_field<V>(path: &str, value: V) -> crate::Result<()>
+where
+    V: Pushable,
+{
+    let (parent, field) = path
+        .rsplit_once('.')
+        .ok_or_else(|| crate::Error::custom("can't replace a vim module"))?;
+
+    let field = CString::new(field).map_err(crate::Error::custom)?;
+
+    unsafe {
+        luajit::with_state(move |lstate| {
+            let top = lua_gettop(lstate);
+
+            let ret = push_vim_field(lstate, parent).and_then(|()| {
+                if lua_type(lstate, -1) != LUA_TTABLE {
+                    return Err(crate::Error::custom(format!(
+                        "vim.{parent} is not a table"
+                    )));
+                }
+                value.push(lstate).map_err(crate::Error::custom)?;
+                lua_setfield(lstate, -2, field.as_ptr());
+                Ok(())
+            });
+
+            lua_settop(lstate, top);
+            ret
+        })
+    }
+}
+
+/// Sets the Lua global `name` to `value`.
+///
+/// This is used for the options which can only be set to the name of a
+/// function, which is then called through `v:lua`.
+pub(crate) fn set_lua_global<V>(name: &str, value: V) -> crate::Result<()>
+where
+    V: Pushable,
+{
+    let name = CString::new(name).map_err(crate::Error::custom)?;
+
+    unsafe {
+        luajit::with_state(move |lstate| {
+            value.push(lstate).map_err(crate::Error::custom)?;
+            lua_setglobal(lstate, name.as_ptr());
+            Ok(())
+        })
+    }
+}
+
+/// Calls the function on the stack below its `nargs` arguments in protected
+/// mode, converting a Lua error into an [`Error`](crate::Error).
+pub(crate) unsafe fn pcall(
+    lstate: *mut lua_State,
+    nargs: c_int,
+    nresults: c_int,
+) -> crate::Result<()> {
+    if lua_pcall(lstate, nargs, nresults, 0) == LUA_OK {
+        return Ok(());
+    }
+
+    // `lua_tostring()` returns NULL if the error value is neither a string
+    // nor a number, e.g. with `error({})` or `error()`.
+    let msg = lua_tostring(lstate, -1);
+
+    let msg = if msg.is_null() {
+        let type_name = CStr::from_ptr(luaL_typename(lstate, -1));
+        format!("(error object is a {} value)", type_name.to_string_lossy())
+    } else {
+        CStr::from_ptr(msg).to_string_lossy().into_owned()
+    };
+
+    lua_pop(lstate, 1);
+
+    Err(crate::Error::custom(msg))
+}
+
+/// Schedules `fun` to be called by the main event loop, like
+/// `vim.schedule()`.
+pub(crate) fn schedule<F>(fun: F) -> crate::Result<()>
+where
+    F: FnOnce(()) -> crate::Result<()> + 'static,
+{
+    let fun = types::Function::<(), ()>::from_fn_once(fun);
+
+    unsafe {
+        luajit::with_state(move |lstate| {
+            let top = lua_gettop(lstate);
+
+            let ret = push_vim_field(lstate, "schedule").and_then(|()| {
+                lua_rawgeti(lstate, LUA_REGISTRYINDEX, fun.lua_ref());
+                pcall(lstate, 1, 0)
+            });
+
+            // `vim.schedule()` holds on to the function until it's called.
+            luaL_unref(lstate, LUA_REGISTRYINDEX, fun.lua_ref());
+            lua_settop(lstate, top);
+            ret
+        })
+    }
+}
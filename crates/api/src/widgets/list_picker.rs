@@ -0,0 +1,142 @@
+use core::cell::Cell;
+use core::fmt;
+use std::error::Error as StdError;
+use std::rc::Rc;
+
+use types::conversion::FromObject;
+
+use super::{map_keys, Popup, PopupOpts, PopupPosition, PopupSize};
+use crate::opts::{OptionOpts, OptionScope, SelectOpts};
+use crate::types::Mode;
+#[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+use crate::types::WindowTitle;
+use crate::Result;
+
+/// A [`Popup`] listing some items, one per line, for the user to pick
+/// one.
+///
+/// The item under the cursor is picked with `<CR>`, while `<Esc>` and `q`
+/// close the picker without picking anything, which also happens when the
+/// cursor leaves its buffer.
+#[derive(Clone)]
+pub struct ListPicker {
+    popup: Popup,
+}
+
+impl fmt::Debug for ListPicker {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListPicker").field("popup", &self.popup).finish()
+    }
+}
+
+impl ListPicker {
+    /// Opens a picker centered in the editor and sized to fit `items`,
+    /// calling `on_choice` with the picked item and its index, or with
+    /// `None` if the picker was closed without picking one.
+    ///
+    /// The items are displayed like [`ui::select()`](crate::ui::select)
+    /// does, and the prompt is used as the picker's title.
+    pub fn open<T, F, E>(
+        items: Vec<T>,
+        opts: &SelectOpts<T>,
+        on_choice: F,
+    ) -> Result<Self>
+    where
+        T: 'static,
+        F: FnOnce(Option<(usize, T)>) -> std::result::Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let lines = items
+            .iter()
+            .map(|item| (opts.format_item)(item))
+            .collect::<Vec<_>>();
+
+        let prompt = Option::<String>::from_object(opts.prompt.clone())?;
+
+        let width = lines
+            .iter()
+            .chain(&prompt)
+            .map(|line| line.chars().count() as u32)
+            .max()
+            .unwrap_or_default()
+            .max(MIN_WIDTH);
+
+        let mut builder = PopupOpts::builder();
+
+        builder
+            .width(width)
+            .height(PopupSize::Cells(lines.len().max(1) as u32))
+            .position(PopupPosition::Center)
+            .close_on(["BufLeave"]);
+
+        #[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+        if let Some(prompt) = prompt {
+            builder.title(WindowTitle::SimpleString(prompt.into()));
+        }
+
+        let popup = Popup::open(&builder.build())?;
+        popup.set_lines(lines)?;
+
+        let mut buffer = popup.buffer();
+        let buf_opts = OptionOpts::builder().buffer(buffer.clone()).build();
+        crate::set_option_value("modifiable", false, &buf_opts)?;
+
+        #[cfg(not(feature = "neovim-nightly"))]
+        let win_opts = OptionOpts::builder()
+            .window(popup.window())
+            .scope(OptionScope::Local)
+            .build();
+        #[cfg(feature = "neovim-nightly")]
+        let win_opts = OptionOpts::builder()
+            .win(popup.window())
+            .scope(OptionScope::Local)
+            .build();
+        crate::set_option_value("cursorline", true, &win_opts)?;
+
+        let picked = Rc::new(Cell::new(None::<usize>));
+
+        popup.on_close({
+            let picked = Rc::clone(&picked);
+            move || {
+                let choice = picked.get().and_then(|idx| {
+                    items.into_iter().nth(idx).map(|item| (idx, item))
+                });
+                on_choice(choice)
+            }
+        });
+
+        map_keys(&mut buffer, &[Mode::Normal], "<CR>", {
+            let popup = popup.clone();
+            move || {
+                let (row, _) = popup.window().get_cursor()?;
+                picked.set(Some(row - 1));
+                popup.close()
+            }
+        })?;
+
+        for lhs in ["<Esc>", "q"] {
+            map_keys(&mut buffer, &[Mode::Normal], lhs, {
+                let popup = popup.clone();
+                move || popup.close()
+            })?;
+        }
+
+        Ok(Self { popup })
+    }
+
+    /// Returns the popup displaying the items.
+    #[inline]
+    pub fn popup(&self) -> &Popup {
+        &self.popup
+    }
+
+    /// Closes the picker without picking an item.
+    #[inline]
+    pub fn close(&self) -> Result<()> {
+        self.popup.close()
+    }
+}
+
+/// The minimum width of a picker, without its border.
+const MIN_WIDTH: u32 = 20;
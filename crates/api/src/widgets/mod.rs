@@ -0,0 +1,61 @@
+//! Floating-window widgets built on [`open_win()`](crate::open_win).
+//!
+//! A [`Popup`] owns a scratch buffer displayed in a floating window, and
+//! takes care of placing it on the screen and of cleaning everything up
+//! once it's closed. [`ListPicker`] and [`TextPrompt`] use it to let the
+//! user pick an item or enter some text.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::api::widgets::{Popup, PopupOpts, PopupPosition};
+//!
+//! let opts = PopupOpts::builder()
+//!     .width(40)
+//!     .height(0.3)
+//!     .position(PopupPosition::Cursor { row: 1, col: 0 })
+//!     .close_on(["CursorMoved", "BufLeave"])
+//!     .build();
+//!
+//! let popup = Popup::open(&opts)?;
+//! popup.set_lines(["Hello from a popup!"])?;
+//! ```
+
+mod list_picker;
+mod popup;
+mod text_prompt;
+
+pub use list_picker::*;
+pub use popup::*;
+pub use text_prompt::*;
+
+use std::rc::Rc;
+
+use crate::opts::SetKeymapOpts;
+use crate::types::Mode;
+use crate::{Buffer, Result};
+
+/// Maps `lhs` to `fun` in the given modes of a widget's buffer.
+fn map_keys<F>(
+    buffer: &mut Buffer,
+    modes: &[Mode],
+    lhs: &str,
+    fun: F,
+) -> Result<()>
+where
+    F: Fn() -> Result<()> + 'static,
+{
+    let fun = Rc::new(fun);
+
+    for &mode in modes {
+        let fun = Rc::clone(&fun);
+        let opts = SetKeymapOpts::builder()
+            .callback(move |()| fun())
+            .nowait(true)
+            .silent(true)
+            .build();
+        buffer.set_keymap(mode, lhs, "", &opts)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,696 @@
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use std::error::Error as StdError;
+use std::rc::{Rc, Weak};
+
+use types::Integer;
+
+use crate::opts::{
+    BufDeleteOpts, CreateAugroupOpts, CreateAutocmdOpts, OptionOpts,
+};
+use crate::types::{
+    WindowBorder, WindowConfig, WindowRelativeTo, WindowStyle,
+};
+#[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+use crate::types::{WindowTitle, WindowTitlePosition};
+use crate::utils::schedule;
+use crate::{Buffer, Error, Result, Window};
+
+/// Where a [`Popup`] is placed on the screen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum PopupPosition {
+    /// Centered in the editor.
+    #[default]
+    Center,
+
+    /// Centered in the given window.
+    CenterIn(Window),
+
+    /// At the given 0-indexed screen row and column.
+    Editor { row: u32, col: u32 },
+
+    /// Offset from the screen cell of the cursor. A popup that doesn't fit
+    /// below the cursor is placed above it instead.
+    Cursor { row: i32, col: i32 },
+}
+
+/// The width or height of a [`Popup`], without its border.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PopupSize {
+    /// A fixed number of screen cells.
+    Cells(u32),
+
+    /// A fraction of the window the popup is centered in, or of the editor.
+    Ratio(f64),
+}
+
+impl PopupSize {
+    #[inline]
+    fn resolve(self, available: u32) -> u32 {
+        match self {
+            Self::Cells(cells) => cells,
+            Self::Ratio(ratio) => (available as f64 * ratio).round() as u32,
+        }
+    }
+}
+
+impl From<u32> for PopupSize {
+    #[inline]
+    fn from(cells: u32) -> Self {
+        Self::Cells(cells)
+    }
+}
+
+impl From<f64> for PopupSize {
+    #[inline]
+    fn from(ratio: f64) -> Self {
+        Self::Ratio(ratio)
+    }
+}
+
+/// Options passed to [`Popup::open()`].
+#[derive(Clone, Debug)]
+pub struct PopupOpts {
+    width: PopupSize,
+    height: PopupSize,
+    position: PopupPosition,
+    border: WindowBorder,
+    #[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+    title: Option<WindowTitle>,
+    #[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+    title_pos: Option<WindowTitlePosition>,
+    #[cfg(feature = "neovim-nightly")]
+    footer: Option<WindowTitle>,
+    #[cfg(feature = "neovim-nightly")]
+    footer_pos: Option<WindowTitlePosition>,
+    close_on: Vec<String>,
+    enter: bool,
+    focusable: bool,
+    zindex: Option<u32>,
+}
+
+impl Default for PopupOpts {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            width: PopupSize::Ratio(0.5),
+            height: PopupSize::Ratio(0.5),
+            position: PopupPosition::Center,
+            border: WindowBorder::Rounded,
+            #[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+            title: None,
+            #[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+            title_pos: None,
+            #[cfg(feature = "neovim-nightly")]
+            footer: None,
+            #[cfg(feature = "neovim-nightly")]
+            footer_pos: None,
+            close_on: Vec::new(),
+            enter: true,
+            focusable: true,
+            zindex: None,
+        }
+    }
+}
+
+impl PopupOpts {
+    #[inline(always)]
+    /// Creates a new [`PopupOptsBuilder`].
+    pub fn builder() -> PopupOptsBuilder {
+        PopupOptsBuilder::default()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct PopupOptsBuilder(PopupOpts);
+
+impl PopupOptsBuilder {
+    /// The width of the popup, without its border. Defaults to half of the
+    /// editor.
+    #[inline]
+    pub fn width(&mut self, width: impl Into<PopupSize>) -> &mut Self {
+        self.0.width = width.into();
+        self
+    }
+
+    /// The height of the popup, without its border. Defaults to half of the
+    /// editor.
+    #[inline]
+    pub fn height(&mut self, height: impl Into<PopupSize>) -> &mut Self {
+        self.0.height = height.into();
+        self
+    }
+
+    /// Where the popup is placed. Defaults to
+    /// [`PopupPosition::Center`].
+    #[inline]
+    pub fn position(&mut self, position: PopupPosition) -> &mut Self {
+        self.0.position = position;
+        self
+    }
+
+    /// The border of the popup. Defaults to [`WindowBorder::Rounded`].
+    #[inline]
+    pub fn border(&mut self, border: WindowBorder) -> &mut Self {
+        self.0.border = border;
+        self
+    }
+
+    /// The title displayed in the top border.
+    #[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "neovim-0-9", feature = "neovim-nightly")))
+    )]
+    #[inline]
+    pub fn title(&mut self, title: WindowTitle) -> &mut Self {
+        self.0.title = Some(title);
+        self
+    }
+
+    #[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "neovim-0-9", feature = "neovim-nightly")))
+    )]
+    #[inline]
+    pub fn title_pos(&mut self, title_pos: WindowTitlePosition) -> &mut Self {
+        self.0.title_pos = Some(title_pos);
+        self
+    }
+
+    /// The footer displayed in the bottom border.
+    #[cfg(feature = "neovim-nightly")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "neovim-nightly")))]
+    #[inline]
+    pub fn footer(&mut self, footer: WindowTitle) -> &mut Self {
+        self.0.footer = Some(footer);
+        self
+    }
+
+    #[cfg(feature = "neovim-nightly")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "neovim-nightly")))]
+    #[inline]
+    pub fn footer_pos(
+        &mut self,
+        footer_pos: WindowTitlePosition,
+    ) -> &mut Self {
+        self.0.footer_pos = Some(footer_pos);
+        self
+    }
+
+    /// Closes the popup when one of the given events is triggered, e.g.
+    /// `"BufLeave"` or `"CursorMoved"`.
+    #[inline]
+    pub fn close_on<'a, I>(&mut self, events: I) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.0.close_on.extend(events.into_iter().map(Into::into));
+        self
+    }
+
+    /// Whether to make the popup the current window. Defaults to `true`.
+    #[inline]
+    pub fn enter(&mut self, enter: bool) -> &mut Self {
+        self.0.enter = enter;
+        self
+    }
+
+    /// Whether the popup can be focused by user actions like mouse clicks.
+    /// Defaults to `true`.
+    #[inline]
+    pub fn focusable(&mut self, focusable: bool) -> &mut Self {
+        self.0.focusable = focusable;
+        self
+    }
+
+    /// Stacking order of the popup.
+    #[inline]
+    pub fn zindex(&mut self, zindex: u32) -> &mut Self {
+        self.0.zindex = Some(zindex);
+        self
+    }
+
+    #[inline]
+    pub fn build(&mut self) -> PopupOpts {
+        std::mem::take(&mut self.0)
+    }
+}
+
+type OnClose = Box<dyn FnOnce() -> Result<()>>;
+
+/// A floating window displaying a scratch buffer.
+///
+/// The popup is closed together with its buffer and its autocommands by
+/// [`close()`](Popup::close), by one of the events passed to
+/// [`close_on`](PopupOptsBuilder::close_on), or when its window is closed
+/// by other means. It's resized and moved every time the editor is
+/// resized.
+///
+/// Cloning a popup returns another handle to the same window.
+#[derive(Clone)]
+pub struct Popup(Rc<Inner>);
+
+struct Inner {
+    buffer: Buffer,
+    window: Window,
+    augroup: u32,
+    opts: RefCell<PopupOpts>,
+    is_closed: Cell<bool>,
+    on_close: RefCell<Vec<OnClose>>,
+}
+
+impl fmt::Debug for Popup {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Popup")
+            .field("buffer", &self.0.buffer)
+            .field("window", &self.0.window)
+            .field("is_closed", &self.0.is_closed.get())
+            .finish()
+    }
+}
+
+impl Popup {
+    /// Opens a new popup displaying an empty scratch buffer.
+    pub fn open(opts: &PopupOpts) -> Result<Self> {
+        let buffer = crate::create_buf(false, true)?;
+
+        let buf_opts = OptionOpts::builder().buffer(buffer.clone()).build();
+        crate::set_option_value("bufhidden", "wipe", &buf_opts)?;
+
+        let config = window_config(opts, geometry(opts)?);
+        let window = crate::open_win(&buffer, opts.enter, &config)?;
+
+        let augroup = crate::create_augroup(
+            &format!("nvim_oxi_popup_{}", window.0),
+            &CreateAugroupOpts::builder().clear(true).build(),
+        )?;
+
+        let popup = Self(Rc::new(Inner {
+            buffer,
+            window,
+            augroup,
+            opts: RefCell::new(opts.clone()),
+            is_closed: Cell::new(false),
+            on_close: RefCell::new(Vec::new()),
+        }));
+
+        popup.create_autocmds()?;
+
+        Ok(popup)
+    }
+
+    /// Returns the buffer displayed in the popup.
+    #[inline]
+    pub fn buffer(&self) -> Buffer {
+        self.0.buffer.clone()
+    }
+
+    /// Returns the floating window of the popup.
+    #[inline]
+    pub fn window(&self) -> Window {
+        self.0.window.clone()
+    }
+
+    /// Returns whether the popup has been closed.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed.get()
+    }
+
+    /// Replaces the contents of the popup's buffer with `lines`.
+    pub fn set_lines<Line, Lines>(&self, lines: Lines) -> Result<()>
+    where
+        Lines: IntoIterator<Item = Line>,
+        Line: Into<types::String>,
+    {
+        self.buffer().set_lines(.., false, lines)
+    }
+
+    /// Changes the size of the popup, moving it to keep it on the screen.
+    pub fn resize(
+        &self,
+        width: impl Into<PopupSize>,
+        height: impl Into<PopupSize>,
+    ) -> Result<()> {
+        {
+            let opts = &mut *self.0.opts.borrow_mut();
+            opts.width = width.into();
+            opts.height = height.into();
+        }
+        self.update()
+    }
+
+    /// Moves the popup to `position`.
+    pub fn set_position(&self, position: PopupPosition) -> Result<()> {
+        self.0.opts.borrow_mut().position = position;
+        self.update()
+    }
+
+    /// Recomputes the size and position of the popup, e.g. after the
+    /// editor has been resized.
+    pub fn update(&self) -> Result<()> {
+        if self.is_closed() {
+            return Ok(());
+        }
+        let opts = &*self.0.opts.borrow();
+        let config = window_config(opts, geometry(opts)?);
+        self.window().set_config(&config)
+    }
+
+    /// Registers a function called once the popup has been closed. Its
+    /// error is returned by the call to [`close()`](Popup::close) which
+    /// closed the popup.
+    pub fn on_close<F, E>(&self, fun: F)
+    where
+        F: FnOnce() -> std::result::Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let fun = move || fun().map_err(Error::custom);
+        self.0.on_close.borrow_mut().push(Box::new(fun));
+    }
+
+    /// Closes the popup's window, wipes its buffer and deletes its
+    /// autocommands. Does nothing if the popup is already closed.
+    pub fn close(&self) -> Result<()> {
+        if self.0.is_closed.replace(true) {
+            return Ok(());
+        }
+
+        let mut res = crate::del_augroup_by_id(self.0.augroup);
+
+        let window = self.window();
+        if window.is_valid() {
+            res = res.and(window.close(true));
+        }
+
+        let buffer = self.buffer();
+        if buffer.is_valid() {
+            let opts = BufDeleteOpts::builder().force(true).build();
+            res = res.and(buffer.delete(&opts));
+        }
+
+        for fun in self.0.on_close.take() {
+            res = res.and(fun());
+        }
+
+        res
+    }
+
+    fn create_autocmds(&self) -> Result<()> {
+        let group = self.0.augroup;
+        let window = self.0.window.0.to_string();
+        let close_on = self.0.opts.borrow().close_on.clone();
+
+        let weak = Rc::downgrade(&self.0);
+        let resize = CreateAutocmdOpts::builder()
+            .group(group)
+            .callback(move |_| {
+                if let Some(inner) = weak.upgrade() {
+                    Popup(inner).update()?;
+                }
+                Ok::<_, Error>(false)
+            })
+            .build();
+
+        crate::create_autocmd(["VimResized"], &resize)?;
+
+        let closed = CreateAutocmdOpts::builder()
+            .group(group)
+            .patterns([window.as_str()])
+            .callback(self.close_callback())
+            .build();
+
+        crate::create_autocmd(["WinClosed"], &closed)?;
+
+        if !close_on.is_empty() {
+            let opts = CreateAutocmdOpts::builder()
+                .group(group)
+                .callback(self.close_callback())
+                .build();
+
+            crate::create_autocmd(close_on.iter().map(String::as_str), &opts)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns an autocommand callback closing the popup.
+    ///
+    /// Windows can't be closed by some autocommands, so the popup is closed
+    /// on the next iteration of the event loop.
+    fn close_callback(
+        &self,
+    ) -> impl FnMut(crate::types::AutocmdCallbackArgs) -> Result<bool> {
+        let weak = Rc::downgrade(&self.0);
+        move |_| {
+            let weak = Weak::clone(&weak);
+            schedule(move |()| match weak.upgrade() {
+                Some(inner) => Popup(inner).close(),
+                None => Ok(()),
+            })?;
+            Ok(false)
+        }
+    }
+}
+
+/// A rectangle of screen cells.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+struct Area {
+    row: u32,
+    col: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Where a popup is placed, with every position resolved to screen cells.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Target {
+    Center(Area),
+    At { row: i64, col: i64 },
+    Cursor { row: i64, col: i64, offset_row: i64, offset_col: i64 },
+}
+
+/// Returns the area of the popup's contents given the screen it's
+/// displayed on and the size of its border, keeping it on the screen.
+fn compute_geometry(
+    screen: Area,
+    target: Target,
+    width: PopupSize,
+    height: PopupSize,
+    (border_width, border_height): (u32, u32),
+) -> Area {
+    let relative_to = match target {
+        Target::Center(area) => area,
+        _ => screen,
+    };
+
+    let max_width = screen.width.saturating_sub(border_width).max(1);
+    let max_height = screen.height.saturating_sub(border_height).max(1);
+
+    let width = width.resolve(relative_to.width).clamp(1, max_width);
+    let height = height.resolve(relative_to.height).clamp(1, max_height);
+
+    let outer_width = (width + border_width) as i64;
+    let outer_height = (height + border_height) as i64;
+
+    let (row, col) = match target {
+        Target::Center(area) => (
+            area.row as i64 + (area.height as i64 - outer_height) / 2,
+            area.col as i64 + (area.width as i64 - outer_width) / 2,
+        ),
+
+        Target::At { row, col } => (row, col),
+
+        Target::Cursor { row, col, offset_row, offset_col } => {
+            let below = row + offset_row;
+            let above = row - outer_height - (offset_row - 1);
+            let screen_end = (screen.row + screen.height) as i64;
+            let row = if below + outer_height > screen_end && above >= 0 {
+                above
+            } else {
+                below
+            };
+            (row, col + offset_col)
+        },
+    };
+
+    let clamp = |pos: i64, start: u32, len: u32, outer: i64| {
+        let max = (start as i64 + len as i64 - outer).max(start as i64);
+        pos.clamp(start as i64, max) as u32
+    };
+
+    Area {
+        row: clamp(row, screen.row, screen.height, outer_height),
+        col: clamp(col, screen.col, screen.width, outer_width),
+        width,
+        height,
+    }
+}
+
+/// Returns the number of columns and rows taken by a border.
+fn border_size(border: &WindowBorder) -> (u32, u32) {
+    match border {
+        WindowBorder::None => (0, 0),
+        // The shadow is only drawn on the right and at the bottom.
+        WindowBorder::Shadow => (1, 1),
+        _ => (2, 2),
+    }
+}
+
+/// Returns the area of the popup's contents.
+fn geometry(opts: &PopupOpts) -> Result<Area> {
+    let global = OptionOpts::default();
+    let columns = crate::get_option_value::<Integer>("columns", &global)?;
+    let lines = crate::get_option_value::<Integer>("lines", &global)?;
+    let cmdheight = crate::get_option_value::<Integer>("cmdheight", &global)?;
+
+    let screen = Area {
+        row: 0,
+        col: 0,
+        width: columns.max(1) as u32,
+        height: (lines - cmdheight).max(1) as u32,
+    };
+
+    let target = match &opts.position {
+        PopupPosition::Center => Target::Center(screen),
+
+        PopupPosition::CenterIn(window) => {
+            let (row, col) = window.get_position()?;
+            Target::Center(Area {
+                row: row as u32,
+                col: col as u32,
+                width: window.get_width()?,
+                height: window.get_height()?,
+            })
+        },
+
+        &PopupPosition::Editor { row, col } => {
+            Target::At { row: row as i64, col: col as i64 }
+        },
+
+        &PopupPosition::Cursor { row: offset_row, col: offset_col } => {
+            // The 1-indexed screen cell of the cursor.
+            let pos = crate::eval::<Vec<Integer>>(
+                "[win_screenpos(0)[0] + winline() - 1, \
+                 win_screenpos(0)[1] + wincol() - 1]",
+            )?;
+            Target::Cursor {
+                row: pos[0] - 1,
+                col: pos[1] - 1,
+                offset_row: offset_row as i64,
+                offset_col: offset_col as i64,
+            }
+        },
+    };
+
+    Ok(compute_geometry(
+        screen,
+        target,
+        opts.width,
+        opts.height,
+        border_size(&opts.border),
+    ))
+}
+
+/// Returns the configuration of the popup's window.
+fn window_config(opts: &PopupOpts, area: Area) -> WindowConfig {
+    let mut builder = WindowConfig::builder();
+
+    builder
+        .relative(WindowRelativeTo::Editor)
+        .row(area.row)
+        .col(area.col)
+        .width(area.width)
+        .height(area.height)
+        .border(opts.border.clone())
+        .focusable(opts.focusable)
+        .style(WindowStyle::Minimal);
+
+    if let Some(zindex) = opts.zindex {
+        builder.zindex(zindex);
+    }
+
+    #[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+    {
+        if let Some(title) = &opts.title {
+            builder.title(title.clone());
+        }
+        if let Some(title_pos) = opts.title_pos {
+            builder.title_pos(title_pos);
+        }
+    }
+
+    #[cfg(feature = "neovim-nightly")]
+    {
+        if let Some(footer) = &opts.footer {
+            builder.footer(footer.clone());
+        }
+        if let Some(footer_pos) = opts.footer_pos {
+            builder.footer_pos(footer_pos);
+        }
+    }
+
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Area = Area { row: 0, col: 0, width: 80, height: 24 };
+
+    #[test]
+    fn centered() {
+        let area = compute_geometry(
+            SCREEN,
+            Target::Center(SCREEN),
+            PopupSize::Cells(40),
+            PopupSize::Ratio(0.5),
+            (2, 2),
+        );
+        assert_eq!(area, Area { row: 5, col: 19, width: 40, height: 12 });
+    }
+
+    #[test]
+    fn clamped_to_screen() {
+        let area = compute_geometry(
+            SCREEN,
+            Target::At { row: 20, col: -5 },
+            PopupSize::Cells(200),
+            PopupSize::Cells(10),
+            (2, 2),
+        );
+        assert_eq!(area, Area { row: 12, col: 0, width: 78, height: 10 });
+    }
+
+    #[test]
+    fn flipped_above_cursor() {
+        let cursor = |row| Target::Cursor {
+            row,
+            col: 10,
+            offset_row: 1,
+            offset_col: 0,
+        };
+
+        let below = compute_geometry(
+            SCREEN,
+            cursor(5),
+            PopupSize::Cells(20),
+            PopupSize::Cells(3),
+            (2, 2),
+        );
+        assert_eq!(below, Area { row: 6, col: 10, width: 20, height: 3 });
+
+        let above = compute_geometry(
+            SCREEN,
+            cursor(22),
+            PopupSize::Cells(20),
+            PopupSize::Cells(3),
+            (2, 2),
+        );
+        assert_eq!(above, Area { row: 17, col: 10, width: 20, height: 3 });
+    }
+}
@@ -0,0 +1,126 @@
+use core::fmt;
+use std::cell::RefCell;
+use std::error::Error as StdError;
+use std::rc::Rc;
+
+use types::conversion::FromObject;
+
+use super::{map_keys, Popup, PopupOpts, PopupPosition};
+use crate::opts::InputOpts;
+use crate::types::Mode;
+#[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+use crate::types::WindowTitle;
+use crate::Result;
+
+/// A one-line [`Popup`] below the cursor where the user can enter some
+/// text.
+///
+/// The text is confirmed with `<CR>` in Insert or Normal mode, while
+/// `<C-c>` in Insert mode and `<Esc>` or `q` in Normal mode close the
+/// prompt without confirming anything, which also happens when the cursor
+/// leaves its buffer.
+#[derive(Clone)]
+pub struct TextPrompt {
+    popup: Popup,
+}
+
+impl fmt::Debug for TextPrompt {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextPrompt").field("popup", &self.popup).finish()
+    }
+}
+
+impl TextPrompt {
+    /// Opens a prompt in Insert mode, calling `on_confirm` with the entered
+    /// text, or with `None` if the prompt was closed without confirming
+    /// it.
+    ///
+    /// The prompt is used as the popup's title and the default text as its
+    /// initial contents. The completion option is ignored.
+    pub fn open<F, E>(opts: &InputOpts, on_confirm: F) -> Result<Self>
+    where
+        F: FnOnce(Option<String>) -> std::result::Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let prompt = Option::<String>::from_object(opts.prompt.clone())?;
+        let default = Option::<String>::from_object(opts.default.clone())?
+            .unwrap_or_default();
+
+        let width = prompt
+            .iter()
+            .chain([&default])
+            .map(|text| text.chars().count() as u32 + 2)
+            .max()
+            .unwrap_or_default()
+            .max(MIN_WIDTH);
+
+        let mut builder = PopupOpts::builder();
+
+        builder
+            .width(width)
+            .height(1)
+            .position(PopupPosition::Cursor { row: 1, col: 0 })
+            .close_on(["BufLeave"]);
+
+        #[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+        if let Some(prompt) = prompt {
+            builder.title(WindowTitle::SimpleString(prompt.into()));
+        }
+
+        let popup = Popup::open(&builder.build())?;
+        popup.set_lines([default.as_str()])?;
+
+        let confirmed = Rc::new(RefCell::new(None::<String>));
+
+        popup.on_close({
+            let confirmed = Rc::clone(&confirmed);
+            move || on_confirm(confirmed.take())
+        });
+
+        let mut buffer = popup.buffer();
+
+        map_keys(&mut buffer, &[Mode::Insert, Mode::Normal], "<CR>", {
+            let popup = popup.clone();
+            move || {
+                *confirmed.borrow_mut() = Some(crate::get_current_line()?);
+                crate::command("stopinsert")?;
+                popup.close()
+            }
+        })?;
+
+        map_keys(&mut buffer, &[Mode::Insert], "<C-c>", {
+            let popup = popup.clone();
+            move || {
+                crate::command("stopinsert")?;
+                popup.close()
+            }
+        })?;
+
+        for lhs in ["<Esc>", "q"] {
+            map_keys(&mut buffer, &[Mode::Normal], lhs, {
+                let popup = popup.clone();
+                move || popup.close()
+            })?;
+        }
+
+        crate::command("startinsert!")?;
+
+        Ok(Self { popup })
+    }
+
+    /// Returns the popup containing the text.
+    #[inline]
+    pub fn popup(&self) -> &Popup {
+        &self.popup
+    }
+
+    /// Closes the prompt without confirming the text.
+    #[inline]
+    pub fn close(&self) -> Result<()> {
+        self.popup.close()
+    }
+}
+
+/// The minimum width of a prompt, without its border.
+const MIN_WIDTH: u32 = 30;
@@ -17,6 +17,7 @@ use crate::choose;
 use crate::ffi::window::*;
 #[cfg(feature = "neovim-nightly")]
 use crate::opts::WinTextHeightOpts;
+use crate::types::Position;
 #[cfg(feature = "neovim-nightly")]
 use crate::types::WinTextHeightInfos;
 use crate::Result;
@@ -164,6 +165,14 @@ impl Window {
         })
     }
 
+    /// Same as [`get_cursor`](Window::get_cursor), but returns the cursor
+    /// position as a 0-indexed [`Position`].
+    pub fn get_cursor_position(&self) -> Result<Position> {
+        let (row, col) = self.get_cursor()?;
+        Ok(Position::from_one_indexed_row(row, col)
+            .expect("cursor row is 1-indexed"))
+    }
+
     /// Binding to [`nvim_win_get_height()`][1].
     ///
     /// Gets the window height as a count of rows.
@@ -298,6 +307,12 @@ impl Window {
         choose!(err, ())
     }
 
+    /// Same as [`set_cursor`](Window::set_cursor), but takes the cursor
+    /// position as a 0-indexed [`Position`].
+    pub fn set_cursor_position(&mut self, pos: Position) -> Result<()> {
+        self.set_cursor(pos.one_indexed_row(), pos.col)
+    }
+
     /// Binding to [`nvim_win_set_height()`][1].
     ///
     /// Sets the window height.
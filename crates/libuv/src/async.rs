@@ -1,6 +1,14 @@
 use std::error::Error as StdError;
 
-use crate::{ffi, Error, Handle};
+use crate::{
+    ffi,
+    handle_error,
+    Error,
+    ErrorAction,
+    Handle,
+    HandleKind,
+    UvError,
+};
 
 type Callback = Box<dyn FnMut() -> Result<(), Box<dyn StdError>> + 'static>;
 
@@ -60,7 +68,7 @@ impl AsyncHandle {
             unsafe { ffi::uv_async_send(self.handle.as_ptr() as *mut _) };
 
         if retv < 0 {
-            return Err(Error::AsyncTrigger);
+            return Err(Error::AsyncTrigger(UvError::new(retv)));
         }
 
         Ok(())
@@ -68,15 +76,19 @@ impl AsyncHandle {
 }
 
 extern "C" fn async_cb(ptr: *mut ffi::uv_async_t) {
-    let handle: Handle<_, Callback> = unsafe { Handle::from_raw(ptr) };
+    let mut handle: Handle<_, Callback> = unsafe { Handle::from_raw(ptr) };
 
     let callback = unsafe { handle.get_data() };
 
     if !callback.is_null() {
         let callback = unsafe { &mut *callback };
 
-        if let Err(_err) = callback() {
-            // TODO: what now?
+        if let Err(err) = callback() {
+            if let ErrorAction::Stop = handle_error(HandleKind::Async, &*err) {
+                // Dropping the callback turns every future call to
+                // `AsyncHandle::send` into a no-op.
+                unsafe { handle.drop_data() };
+            }
         }
     }
 }
@@ -0,0 +1,149 @@
+use core::cell::RefCell;
+use core::fmt;
+use std::error::Error as StdError;
+use std::ffi::c_char;
+
+use luajit::ffi::*;
+use luajit::macros::cstr;
+
+type ErrorHandler =
+    Box<dyn FnMut(HandleKind, &dyn StdError) -> ErrorAction + 'static>;
+
+thread_local! {
+    static HANDLER: RefCell<Option<ErrorHandler>> = const { RefCell::new(None) };
+}
+
+/// The kind of handle whose callback returned an error.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum HandleKind {
+    /// An [`AsyncHandle`](crate::AsyncHandle).
+    Async,
+
+    /// A [`TimerHandle`](crate::TimerHandle).
+    Timer,
+
+    /// The receiving half of a [`channel`](crate::channel()).
+    Channel,
+}
+
+impl fmt::Display for HandleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Async => "async",
+            Self::Timer => "timer",
+            Self::Channel => "channel",
+        })
+    }
+}
+
+/// What to do with a handle after its callback returned an error.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ErrorAction {
+    /// Keep the handle running as if nothing happened.
+    #[default]
+    Continue,
+
+    /// Stop the handle. Stopped timers won't fire again until they're
+    /// restarted, stopped async handles will ignore all future calls to
+    /// [`AsyncHandle::send`](crate::AsyncHandle::send) and stopped channels
+    /// are closed, dropping all the queued messages.
+    Stop,
+}
+
+/// Installs a handler which will be called every time the callback of a
+/// handle returns an error.
+///
+/// The handler replaces the default one, which reports the error via
+/// [`notify_error`] and keeps the handle running.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::libuv::{self, ErrorAction};
+///
+/// // Keep notifying the user, but stop the handle on the first error.
+/// libuv::set_error_handler(|kind, err| {
+///     libuv::notify_error(kind, err);
+///     ErrorAction::Stop
+/// });
+/// ```
+pub fn set_error_handler<F>(handler: F)
+where
+    F: FnMut(HandleKind, &dyn StdError) -> ErrorAction + 'static,
+{
+    HANDLER.with(|h| *h.borrow_mut() = Some(Box::new(handler)));
+}
+
+/// Restores the default error handler.
+pub fn reset_error_handler() {
+    HANDLER.with(|h| *h.borrow_mut() = None);
+}
+
+/// Reports a callback error to the user via [`vim.notify()`][1] at the
+/// `ERROR` level.
+///
+/// The notification is scheduled, so it's safe to call this function from
+/// inside a libuv callback.
+///
+/// [1]: https://neovim.io/doc/user/lua.html#vim.notify()
+pub fn notify_error(kind: HandleKind, err: &dyn StdError) {
+    let msg = format!("[nvim-oxi] error in libuv {kind} callback: {err}");
+
+    unsafe {
+        luajit::with_state(move |lstate| {
+            lua_getglobal(lstate, cstr!("vim"));
+
+            // https://neovim.io/doc/user/lua.html#vim.log.levels
+            lua_getfield(lstate, -1, cstr!("log"));
+            lua_getfield(lstate, -1, cstr!("levels"));
+            lua_getfield(lstate, -1, cstr!("ERROR"));
+            let level = lua_tointeger(lstate, -1);
+            lua_pop(lstate, 3);
+
+            // Libuv callbacks are executed in a fast event context where
+            // `vim.notify` can't be called directly, so we wrap it with
+            // `vim.schedule_wrap`.
+            lua_getfield(lstate, -1, cstr!("schedule_wrap"));
+            lua_getfield(lstate, -2, cstr!("notify"));
+            lua_call(lstate, 1, 1);
+
+            lua_pushlstring(lstate, msg.as_ptr() as *const c_char, msg.len());
+            lua_pushinteger(lstate, level);
+
+            // There's no Lua frame to unwind to from a libuv callback, so we
+            // can't let errors propagate.
+            if lua_pcall(lstate, 2, 0, 0) != LUA_OK {
+                lua_pop(lstate, 1);
+            }
+
+            // Pop `vim` off the stack.
+            lua_pop(lstate, 1);
+        })
+    };
+}
+
+/// Passes the error returned by the callback of a handle of the given kind to
+/// the current error handler, returning what to do with the handle.
+pub(crate) fn handle_error(
+    kind: HandleKind,
+    err: &dyn StdError,
+) -> ErrorAction {
+    // Take the handler out of the cell so that it can call
+    // `set_error_handler` itself without panicking.
+    let Some(mut handler) = HANDLER.with(|h| h.borrow_mut().take()) else {
+        notify_error(kind, err);
+        return ErrorAction::Continue;
+    };
+
+    let action = handler(kind, err);
+
+    HANDLER.with(|h| {
+        let mut h = h.borrow_mut();
+        if h.is_none() {
+            *h = Some(handler);
+        }
+    });
+
+    action
+}
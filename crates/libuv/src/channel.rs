@@ -0,0 +1,343 @@
+use std::collections::VecDeque;
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use crate::{ffi, handle_error, Error, ErrorAction, Handle, HandleKind};
+
+type Callback<T> =
+    Box<dyn FnMut(T) -> Result<(), Box<dyn StdError>> + 'static>;
+
+/// Creates a bounded channel whose messages are delivered to `callback` on
+/// the Neovim thread, returning the sending half.
+///
+/// Unlike [`AsyncHandle::send`](crate::AsyncHandle::send), no message is
+/// lost when multiple wakeups get coalesced: every time the callback's
+/// handle is woken up all the messages in the queue are drained.
+///
+/// At most `capacity` messages can be queued at once. When the queue is full
+/// [`Sender::send`] blocks until the Neovim thread has made room, while
+/// [`Sender::try_send`] returns immediately.
+///
+/// The underlying libuv handle is closed once all the [`Sender`]s have been
+/// dropped and the remaining messages have been delivered.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::convert::Infallible;
+/// use std::thread;
+///
+/// use nvim_oxi::libuv;
+///
+/// let sender = libuv::channel(16, |n: i32| {
+///     nvim_oxi::print!("Received {n} from a background thread");
+///     Ok::<_, Infallible>(())
+/// })?;
+///
+/// thread::spawn(move || {
+///     for n in 0..100 {
+///         sender.send(n).unwrap();
+///     }
+/// });
+/// ```
+pub fn channel<T, Cb, E>(
+    capacity: usize,
+    mut callback: Cb,
+) -> Result<Sender<T>, Error>
+where
+    T: Send + 'static,
+    Cb: FnMut(T) -> Result<(), E> + 'static,
+    E: StdError + 'static,
+{
+    assert!(capacity > 0, "the capacity of a channel must be positive");
+
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue {
+            messages: VecDeque::with_capacity(capacity),
+            is_closed: false,
+        }),
+        not_full: Condvar::new(),
+        capacity,
+        num_senders: AtomicUsize::new(1),
+    });
+
+    let mut handle = Handle::new(|uv_loop, handle| unsafe {
+        ffi::uv_async_init(
+            uv_loop,
+            handle.as_mut_ptr(),
+            Some(channel_cb::<T> as _),
+        )
+    })?;
+
+    let callback: Callback<T> = Box::new(move |message| {
+        // Type erase the callback by boxing its error.
+        callback(message).map_err(|err| Box::new(err) as Box<dyn StdError>)
+    });
+
+    unsafe { handle.set_data(Receiver { shared: shared.clone(), callback }) };
+
+    Ok(Sender { shared, handle: RawAsync(handle.as_mut_ptr()) })
+}
+
+/// The sending half of a channel created by [`channel`].
+///
+/// Senders can be cloned and sent to other threads. The channel is closed
+/// once every sender has been dropped.
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+    handle: RawAsync,
+}
+
+/// The receiving half of a channel, stored as the data of its async handle.
+struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    callback: Callback<T>,
+}
+
+struct Shared<T> {
+    queue: Mutex<Queue<T>>,
+    not_full: Condvar,
+    capacity: usize,
+    num_senders: AtomicUsize,
+}
+
+struct Queue<T> {
+    messages: VecDeque<T>,
+
+    /// Whether the receiving half of the channel has been closed, either
+    /// because all the senders were dropped or because the callback returned
+    /// an error and the error handler asked to stop it.
+    is_closed: bool,
+}
+
+/// A pointer to the `uv_async_t` which wakes up the Neovim thread.
+#[derive(Copy, Clone)]
+struct RawAsync(*mut ffi::uv_async_t);
+
+// SAFETY: `uv_async_send` is the only libuv function which is safe to call
+// from any thread, and it's the only one senders call.
+unsafe impl Send for RawAsync {}
+unsafe impl Sync for RawAsync {}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, Queue<T>> {
+        // The lock is never held while running user code, so it can't be
+        // poisoned.
+        self.queue.lock().unwrap()
+    }
+
+    /// Takes all the queued messages, also returning whether all the senders
+    /// have been dropped.
+    fn drain(&self) -> (VecDeque<T>, bool) {
+        let mut queue = self.lock();
+        let messages = core::mem::take(&mut queue.messages);
+        let is_disconnected = self.num_senders.load(Ordering::Acquire) == 0;
+        queue.is_closed |= is_disconnected;
+        drop(queue);
+        self.not_full.notify_all();
+        (messages, is_disconnected)
+    }
+
+    /// Closes the receiving half of the channel, dropping all the queued
+    /// messages and unblocking the senders.
+    fn close(&self) {
+        let mut queue = self.lock();
+        queue.is_closed = true;
+        let messages = core::mem::take(&mut queue.messages);
+        drop(queue);
+        self.not_full.notify_all();
+        drop(messages);
+    }
+}
+
+impl<T> Sender<T> {
+    /// Sends a message to the Neovim thread, blocking the current thread
+    /// while the channel is full.
+    ///
+    /// Returns the message back if the receiving half of the channel has
+    /// been closed.
+    ///
+    /// NOTE: calling this from the Neovim thread when the channel is full
+    /// will deadlock, as the queue can only be drained by that same thread.
+    /// Use [`try_send`](Sender::try_send) there instead.
+    pub fn send(&self, message: T) -> Result<(), SendError<T>> {
+        let mut queue = self.shared.lock();
+
+        while queue.messages.len() >= self.shared.capacity && !queue.is_closed
+        {
+            queue = self.shared.not_full.wait(queue).unwrap();
+        }
+
+        if queue.is_closed {
+            return Err(SendError(message));
+        }
+
+        queue.messages.push_back(message);
+        drop(queue);
+        self.wake_up();
+        Ok(())
+    }
+
+    /// Tries to send a message to the Neovim thread without blocking.
+    pub fn try_send(&self, message: T) -> Result<(), TrySendError<T>> {
+        let mut queue = self.shared.lock();
+
+        if queue.is_closed {
+            return Err(TrySendError::Closed(message));
+        }
+
+        if queue.messages.len() >= self.shared.capacity {
+            return Err(TrySendError::Full(message));
+        }
+
+        queue.messages.push_back(message);
+        drop(queue);
+        self.wake_up();
+        Ok(())
+    }
+
+    /// Returns whether the receiving half of the channel has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().is_closed
+    }
+
+    /// Returns the maximum number of messages that can be queued at once.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    fn wake_up(&self) {
+        // This can only fail if the handle is not an async handle.
+        let _ = unsafe { ffi::uv_async_send(self.handle.0) };
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.num_senders.fetch_add(1, Ordering::AcqRel);
+        Self { shared: self.shared.clone(), handle: self.handle }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        // The decrement and the wake up have to happen atomically with
+        // respect to `Shared::drain`, or the Neovim thread could close the
+        // handle while we're still waking it up.
+        let _queue = self.shared.lock();
+
+        if self.shared.num_senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // This was the last sender. Wake up the Neovim thread one last
+            // time so that it can drain the queue and close the handle.
+            self.wake_up();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("capacity", &self.shared.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The error returned by [`Sender::send`] when the receiving half of the
+/// channel has been closed. Contains the message that couldn't be sent.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sending on a closed channel")
+    }
+}
+
+impl<T> StdError for SendError<T> {}
+
+/// The error returned by [`Sender::try_send`].
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum TrySendError<T> {
+    /// The channel is full.
+    Full(T),
+
+    /// The receiving half of the channel has been closed.
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Returns the message that couldn't be sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(message) | Self::Closed(message) => message,
+        }
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("Full(..)"),
+            Self::Closed(_) => f.write_str("Closed(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("sending on a full channel"),
+            Self::Closed(_) => f.write_str("sending on a closed channel"),
+        }
+    }
+}
+
+impl<T> StdError for TrySendError<T> {}
+
+impl<T> From<SendError<T>> for TrySendError<T> {
+    fn from(SendError(message): SendError<T>) -> Self {
+        Self::Closed(message)
+    }
+}
+
+extern "C" fn channel_cb<T: 'static>(ptr: *mut ffi::uv_async_t) {
+    let handle: Handle<_, Receiver<T>> = unsafe { Handle::from_raw(ptr) };
+
+    let receiver = unsafe { handle.get_data() };
+
+    if receiver.is_null() {
+        return;
+    }
+
+    let receiver = unsafe { &mut *receiver };
+
+    let (messages, is_disconnected) = receiver.shared.drain();
+
+    for message in messages {
+        if let Err(err) = (receiver.callback)(message) {
+            if let ErrorAction::Stop = handle_error(HandleKind::Channel, &*err)
+            {
+                receiver.shared.close();
+                break;
+            }
+        }
+    }
+
+    // The last sender wakes up the handle after it's been dropped, so this
+    // is the last time the callback is executed and we can close the handle.
+    if is_disconnected {
+        unsafe { handle.close() };
+    }
+}
@@ -0,0 +1,106 @@
+use std::cell::Cell;
+use std::error::Error as StdError;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::{Error, TimerHandle};
+
+/// Delays the execution of a callback until a given amount of time has passed
+/// without it being [`call`](Debouncer::call)ed again.
+///
+/// This is useful to react to bursts of events (e.g. `TextChangedI`) only once
+/// they have settled down.
+///
+/// The callback is executed on the main thread by a [`TimerHandle`] which is
+/// reused across calls and closed when the `Debouncer` is dropped.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::convert::Infallible;
+/// use std::time::Duration;
+///
+/// use nvim_oxi::libuv::Debouncer;
+///
+/// let mut debouncer = Debouncer::new(Duration::from_millis(200), || {
+///     nvim_oxi::print!("No keys pressed in the last 200ms");
+///     Ok::<_, Infallible>(())
+/// })?;
+///
+/// // The callback will only be called once, 200ms after the last call.
+/// for _ in 0..10 {
+///     debouncer.call()?;
+/// }
+/// ```
+pub struct Debouncer {
+    delay: Duration,
+    is_pending: Rc<Cell<bool>>,
+    timer: Option<TimerHandle>,
+}
+
+impl Debouncer {
+    /// Creates a new `Debouncer` which executes `callback` after `delay` has
+    /// passed since the last call to [`call`](Debouncer::call).
+    pub fn new<Cb, E>(delay: Duration, mut callback: Cb) -> Result<Self, Error>
+    where
+        Cb: FnMut() -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let is_pending = Rc::new(Cell::new(false));
+
+        let timer = {
+            let is_pending = is_pending.clone();
+            TimerHandle::with_callback(move |_| {
+                is_pending.set(false);
+                callback()
+            })?
+        };
+
+        Ok(Self { delay, is_pending, timer: Some(timer) })
+    }
+
+    /// Schedules the callback to be executed after the delay, cancelling the
+    /// previously scheduled execution if it hasn't happened yet.
+    pub fn call(&mut self) -> Result<(), Error> {
+        let delay = self.delay;
+        self.timer_mut().restart(delay)?;
+        self.is_pending.set(true);
+        Ok(())
+    }
+
+    /// Cancels the pending execution of the callback, if any.
+    pub fn cancel(&mut self) -> Result<(), Error> {
+        self.timer_mut().stop()?;
+        self.is_pending.set(false);
+        Ok(())
+    }
+
+    /// Returns whether there's a pending execution of the callback.
+    pub fn is_pending(&self) -> bool {
+        self.is_pending.get()
+    }
+
+    /// Returns the delay after which the callback is executed.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Changes the delay after which the callback is executed. This doesn't
+    /// affect an already pending execution.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    fn timer_mut(&mut self) -> &mut TimerHandle {
+        // The timer is only taken out when the debouncer is dropped.
+        self.timer.as_mut().unwrap()
+    }
+}
+
+impl Drop for Debouncer {
+    fn drop(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            timer.close();
+        }
+    }
+}
@@ -1,21 +1,91 @@
+use core::ffi::c_int;
+use std::ffi::CStr;
+
 use thiserror::Error as ThisError;
 
+use crate::ffi;
+
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Clone, Debug, Eq, PartialEq, ThisError)]
 pub enum Error {
-    #[error("Couldn't trigger async handle")]
-    AsyncTrigger,
+    #[error("Couldn't trigger async handle: {0}")]
+    AsyncTrigger(UvError),
 
-    #[error("Couldn't initialize handle")]
-    HandleInit,
+    #[error("Couldn't initialize handle: {0}")]
+    HandleInit(UvError),
 
     #[error("Couldn't allocate memory for a new handle")]
     HandleMemAlloc,
 
-    #[error("Couldn't start timer handle")]
-    TimerStart,
+    #[error("Couldn't restart timer handle: {0}")]
+    TimerAgain(UvError),
+
+    #[error("Couldn't start timer handle: {0}")]
+    TimerStart(UvError),
+
+    #[error("Couldn't stop timer handle: {0}")]
+    TimerStop(UvError),
+}
+
+impl Error {
+    /// Returns the underlying libuv error, if any.
+    pub fn uv_error(&self) -> Option<&UvError> {
+        match self {
+            Self::AsyncTrigger(err)
+            | Self::HandleInit(err)
+            | Self::TimerAgain(err)
+            | Self::TimerStart(err)
+            | Self::TimerStop(err) => Some(err),
+
+            Self::HandleMemAlloc => None,
+        }
+    }
+}
+
+/// An error code returned by a libuv function, together with its name and
+/// description as given by [`uv_err_name`][1] and [`uv_strerror`][2].
+///
+/// [1]: http://docs.libuv.org/en/v1.x/errors.html#c.uv_err_name
+/// [2]: http://docs.libuv.org/en/v1.x/errors.html#c.uv_strerror
+#[derive(Clone, Debug, Eq, PartialEq, ThisError)]
+#[error("{message} ({name})")]
+pub struct UvError {
+    code: i32,
+    name: String,
+    message: String,
+}
+
+impl UvError {
+    /// Creates a new error from a (negative) return value of a libuv
+    /// function.
+    pub(crate) fn new(code: c_int) -> Self {
+        let (name, message) = unsafe {
+            (
+                CStr::from_ptr(ffi::uv_err_name(code)),
+                CStr::from_ptr(ffi::uv_strerror(code)),
+            )
+        };
+
+        Self {
+            code,
+            name: name.to_string_lossy().into_owned(),
+            message: message.to_string_lossy().into_owned(),
+        }
+    }
+
+    /// The raw error code, e.g. `-22` for `EINVAL`.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The name of the error, e.g. `"EINVAL"`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 
-    #[error("Couldn't stop timer handle")]
-    TimerStop,
+    /// A description of the error, e.g. `"invalid argument"`.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
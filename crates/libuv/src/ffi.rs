@@ -8,7 +8,7 @@
 #![allow(non_snake_case)]
 #![allow(non_camel_case_types)]
 
-use core::ffi::{c_int, c_uint, c_void};
+use core::ffi::{c_char, c_int, c_uint, c_void};
 use core::marker::{PhantomData, PhantomPinned};
 
 pub(crate) type uv_timer_cb =
@@ -53,6 +53,10 @@ extern "C" {
 
     pub(crate) fn uv_async_send(async_: *mut uv_async_t) -> c_int;
 
+    pub(crate) fn uv_close(handle: *mut uv_handle_t, close_cb: uv_close_cb);
+
+    pub(crate) fn uv_err_name(err: c_int) -> *const c_char;
+
     pub(crate) fn uv_handle_get_data(
         handle: *const uv_handle_t,
     ) -> *mut c_void;
@@ -62,6 +66,8 @@ extern "C" {
         data: *mut c_void,
     );
 
+    pub(crate) fn uv_strerror(err: c_int) -> *const c_char;
+
     pub(crate) fn uv_timer_init(
         loop_: *mut uv_loop_t,
         handle: *mut uv_timer_t,
@@ -75,6 +81,14 @@ extern "C" {
     ) -> c_int;
 
     pub(crate) fn uv_timer_stop(handle: *mut uv_timer_t) -> c_int;
+
+    pub(crate) fn uv_timer_again(handle: *mut uv_timer_t) -> c_int;
+
+    pub(crate) fn uv_timer_set_repeat(handle: *mut uv_timer_t, repeat: u64);
+
+    pub(crate) fn uv_timer_get_repeat(handle: *const uv_timer_t) -> u64;
+
+    pub(crate) fn uv_timer_get_due_in(handle: *const uv_timer_t) -> u64;
 }
 
 #[repr(C)]
@@ -111,7 +125,8 @@ struct uv_timer_s {
 
 type uv_handle_type = c_uint;
 
-type uv_close_cb = Option<unsafe extern "C" fn(handle: *mut uv_handle_t)>;
+pub(crate) type uv_close_cb =
+    Option<unsafe extern "C" fn(handle: *mut uv_handle_t)>;
 
 #[repr(C)]
 #[derive(Copy, Clone)]
@@ -2,7 +2,7 @@ use std::alloc::{self, Layout};
 use std::ffi::c_void;
 use std::marker::PhantomData;
 
-use crate::{ffi, Error, Result};
+use crate::{ffi, Error, Result, UvError};
 
 /// TODO: docs
 pub(crate) trait ProperLayout: Sized {}
@@ -40,7 +40,7 @@ impl<T: ProperLayout, D> Handle<T, D> {
 
         if retv < 0 {
             unsafe { alloc::dealloc(ptr as *mut u8, layout) };
-            return Err(Error::HandleInit);
+            return Err(Error::HandleInit(UvError::new(retv)));
         }
 
         Ok(handle)
@@ -71,4 +71,34 @@ impl<T: ProperLayout, D> Handle<T, D> {
             data as *mut c_void,
         )
     }
+
+    /// Drops the data associated to the handle, if any.
+    pub(crate) unsafe fn drop_data(&mut self) {
+        let data = self.get_data();
+
+        if !data.is_null() {
+            ffi::uv_handle_set_data(
+                self.as_mut_ptr() as *mut ffi::uv_handle_t,
+                core::ptr::null_mut(),
+            );
+            drop(Box::from_raw(data));
+        }
+    }
+
+    /// Closes the handle, dropping its data and freeing its memory once
+    /// libuv is done with it.
+    pub(crate) unsafe fn close(self) {
+        ffi::uv_close(
+            self.ptr as *mut ffi::uv_handle_t,
+            Some(close_cb::<T, D>),
+        );
+    }
+}
+
+unsafe extern "C" fn close_cb<T: ProperLayout, D: 'static>(
+    ptr: *mut ffi::uv_handle_t,
+) {
+    let mut handle = Handle::<T, D>::from_raw(ptr as *mut T);
+    handle.drop_data();
+    alloc::dealloc(ptr as *mut u8, Layout::new::<T>());
 }
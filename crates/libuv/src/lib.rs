@@ -1,14 +1,29 @@
 mod r#async;
+mod callback_error;
+mod channel;
+mod debouncer;
 mod error;
 mod ffi;
 mod handle;
 mod r#loop;
+mod throttler;
 mod timer;
 
-pub use error::Error;
+use callback_error::handle_error;
+pub use callback_error::{
+    notify_error,
+    reset_error_handler,
+    set_error_handler,
+    ErrorAction,
+    HandleKind,
+};
+pub use channel::{channel, SendError, Sender, TrySendError};
+pub use debouncer::Debouncer;
 use error::Result;
+pub use error::{Error, UvError};
 use handle::{Handle, ProperLayout};
 pub use r#async::AsyncHandle;
 pub use r#loop::init;
 use r#loop::with_loop;
+pub use throttler::Throttler;
 pub use timer::TimerHandle;
@@ -0,0 +1,150 @@
+use std::cell::Cell;
+use std::error::Error as StdError;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::{Error, TimerHandle};
+
+/// Limits the execution of a callback to at most once every given interval.
+///
+/// The first [`call`](Throttler::call) executes the callback on the next
+/// iteration of the event loop. Calls made while the throttler is cooling
+/// down are coalesced into a single execution at the end of the interval.
+///
+/// The callback is executed on the main thread by a [`TimerHandle`] which is
+/// reused across calls and closed when the `Throttler` is dropped.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::convert::Infallible;
+/// use std::time::Duration;
+///
+/// use nvim_oxi::libuv::Throttler;
+///
+/// let mut throttler = Throttler::new(Duration::from_millis(100), || {
+///     nvim_oxi::print!("Refreshing");
+///     Ok::<_, Infallible>(())
+/// })?;
+///
+/// // The callback will be called twice: once right away and once after
+/// // 100ms.
+/// for _ in 0..10 {
+///     throttler.call()?;
+/// }
+/// ```
+pub struct Throttler {
+    interval: Duration,
+    state: Rc<State>,
+    timer: Option<TimerHandle>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Whether the callback should be executed the next time the timer fires.
+    is_pending: Cell<bool>,
+
+    /// Whether the timer is running, i.e. whether the callback has been
+    /// executed less than one interval ago.
+    is_cooling_down: Cell<bool>,
+}
+
+impl Throttler {
+    /// Creates a new `Throttler` which executes `callback` at most once every
+    /// `interval`.
+    pub fn new<Cb, E>(
+        interval: Duration,
+        mut callback: Cb,
+    ) -> Result<Self, Error>
+    where
+        Cb: FnMut() -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let state = Rc::new(State::default());
+
+        let mut timer = {
+            let state = state.clone();
+
+            TimerHandle::with_callback(move |timer| {
+                if state.is_pending.replace(false) {
+                    let res = callback();
+
+                    // Non-repeating timers are stopped by libuv after firing,
+                    // and the timer is stopped here if the callback failed so
+                    // that the next call restarts it whatever the error
+                    // handler decides.
+                    if res.is_err() {
+                        let _ = timer.stop();
+                        state.is_cooling_down.set(false);
+                    } else if timer.get_repeat().is_zero() {
+                        state.is_cooling_down.set(false);
+                    }
+
+                    return res;
+                }
+
+                // Nothing happened during the last interval, so we can stop
+                // the timer until the next call.
+                state.is_cooling_down.set(false);
+                let _ = timer.stop();
+                Ok(())
+            })?
+        };
+
+        timer.set_repeat(interval);
+
+        Ok(Self { interval, state, timer: Some(timer) })
+    }
+
+    /// Schedules the execution of the callback, which happens on the next
+    /// event loop iteration if the throttler isn't cooling down or at the end
+    /// of the current interval if it is.
+    pub fn call(&mut self) -> Result<(), Error> {
+        self.state.is_pending.set(true);
+
+        if !self.state.is_cooling_down.get() {
+            self.timer_mut().restart(Duration::ZERO)?;
+            self.state.is_cooling_down.set(true);
+        }
+
+        Ok(())
+    }
+
+    /// Cancels the pending execution of the callback, if any, and resets the
+    /// throttler so that the next call executes the callback right away.
+    pub fn cancel(&mut self) -> Result<(), Error> {
+        self.timer_mut().stop()?;
+        self.state.is_pending.set(false);
+        self.state.is_cooling_down.set(false);
+        Ok(())
+    }
+
+    /// Returns whether there's a pending execution of the callback.
+    pub fn is_pending(&self) -> bool {
+        self.state.is_pending.get()
+    }
+
+    /// Returns the minimum interval between two executions of the callback.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Changes the minimum interval between two executions of the callback.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+        self.timer_mut().set_repeat(interval);
+    }
+
+    fn timer_mut(&mut self) -> &mut TimerHandle {
+        // The timer is only taken out when the throttler is dropped.
+        self.timer.as_mut().unwrap()
+    }
+}
+
+impl Drop for Throttler {
+    fn drop(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            timer.close();
+        }
+    }
+}
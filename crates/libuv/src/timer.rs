@@ -1,7 +1,15 @@
 use std::error::Error as StdError;
 use std::time::Duration;
 
-use crate::{ffi, Error, Handle};
+use crate::{
+    ffi,
+    handle_error,
+    Error,
+    ErrorAction,
+    Handle,
+    HandleKind,
+    UvError,
+};
 
 pub(crate) type Callback = Box<
     dyn FnMut(&mut TimerHandle) -> Result<(), Box<dyn StdError>> + 'static,
@@ -31,8 +39,20 @@ impl TimerHandle {
     pub fn start<Cb, E>(
         timeout: Duration,
         repeat: Duration,
-        mut callback: Cb,
+        callback: Cb,
     ) -> Result<Self, Error>
+    where
+        Cb: FnMut(&mut Self) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let mut timer = Self::with_callback(callback)?;
+        timer.start_inner(timeout, repeat)?;
+        Ok(timer)
+    }
+
+    /// Creates a new timer which will execute the given callback once
+    /// started, without starting it.
+    pub(crate) fn with_callback<Cb, E>(mut callback: Cb) -> Result<Self, Error>
     where
         Cb: FnMut(&mut Self) -> Result<(), E> + 'static,
         E: StdError + 'static,
@@ -46,9 +66,17 @@ impl TimerHandle {
 
         unsafe { timer.handle.set_data(callback) };
 
+        Ok(timer)
+    }
+
+    fn start_inner(
+        &mut self,
+        timeout: Duration,
+        repeat: Duration,
+    ) -> Result<(), Error> {
         let retv = unsafe {
             ffi::uv_timer_start(
-                timer.handle.as_mut_ptr(),
+                self.handle.as_mut_ptr(),
                 Some(timer_cb as _),
                 timeout.as_millis() as u64,
                 repeat.as_millis() as u64,
@@ -56,10 +84,10 @@ impl TimerHandle {
         };
 
         if retv < 0 {
-            return Err(Error::TimerStart);
+            return Err(Error::TimerStart(UvError::new(retv)));
         }
 
-        Ok(timer)
+        Ok(())
     }
 
     /// Same as [`start()`](TimerHandle::start) but accepts a closure that
@@ -83,11 +111,67 @@ impl TimerHandle {
         let retv = unsafe { ffi::uv_timer_stop(self.handle.as_mut_ptr()) };
 
         if retv < 0 {
-            return Err(Error::TimerStop);
+            return Err(Error::TimerStop(UvError::new(retv)));
         }
 
         Ok(())
     }
+
+    /// Stops the timer and starts it again, firing the callback after
+    /// `timeout` and then every [`repeat`](TimerHandle::get_repeat) interval.
+    ///
+    /// Unlike [`again()`](TimerHandle::again), this also works if the timer
+    /// has never been started or has been stopped.
+    pub fn restart(&mut self, timeout: Duration) -> Result<(), Error> {
+        let repeat = self.get_repeat();
+        self.start_inner(timeout, repeat)
+    }
+
+    /// Stops the timer and, if it's repeating, restarts it using the repeat
+    /// value as the timeout.
+    ///
+    /// Returns an error if the timer has never been started.
+    pub fn again(&mut self) -> Result<(), Error> {
+        let retv = unsafe { ffi::uv_timer_again(self.handle.as_mut_ptr()) };
+
+        if retv < 0 {
+            return Err(Error::TimerAgain(UvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the interval at which the callback is repeatedly executed.
+    ///
+    /// The new value only takes effect from the next time the timer fires or
+    /// is restarted. If it's zero the timer won't repeat.
+    pub fn set_repeat(&mut self, repeat: Duration) {
+        unsafe {
+            ffi::uv_timer_set_repeat(
+                self.handle.as_mut_ptr(),
+                repeat.as_millis() as u64,
+            )
+        };
+    }
+
+    /// Returns the interval at which the callback is repeatedly executed.
+    pub fn get_repeat(&self) -> Duration {
+        let millis = unsafe { ffi::uv_timer_get_repeat(self.handle.as_ptr()) };
+        Duration::from_millis(millis)
+    }
+
+    /// Returns how long until the timer fires, or zero if it has expired or
+    /// isn't running.
+    pub fn get_due_in(&self) -> Duration {
+        let millis = unsafe { ffi::uv_timer_get_due_in(self.handle.as_ptr()) };
+        Duration::from_millis(millis)
+    }
+
+    /// Stops the timer and releases all its resources, including the
+    /// callback.
+    pub fn close(self) {
+        unsafe { self.handle.close() };
+    }
 }
 
 extern "C" fn timer_cb(ptr: *mut ffi::uv_timer_t) {
@@ -99,8 +183,10 @@ extern "C" fn timer_cb(ptr: *mut ffi::uv_timer_t) {
         let mut handle = TimerHandle { handle };
         let callback = unsafe { &mut *callback };
 
-        if let Err(_err) = callback(&mut handle) {
-            // TODO: what now?
+        if let Err(err) = callback(&mut handle) {
+            if let ErrorAction::Stop = handle_error(HandleKind::Timer, &*err) {
+                let _ = handle.stop();
+            }
         }
     }
 }
@@ -137,6 +137,9 @@ extern "C" {
     // https://www.lua.org/manual/5.1/manual.html#lua_rawseti
     pub fn lua_rawseti(L: *mut lua_State, index: c_int, n: c_int);
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_setfield
+    pub fn lua_setfield(L: *mut lua_State, index: c_int, k: *const c_char);
+
     // https://www.lua.org/manual/5.1/manual.html#lua_settop
     pub fn lua_settop(L: *mut lua_State, index: c_int);
 
@@ -192,6 +195,11 @@ pub unsafe fn lua_pushcfunction(L: *mut lua_State, r#fn: lua_CFunction) {
     lua_pushcclosure(L, r#fn, 0)
 }
 
+// https://www.lua.org/manual/5.1/manual.html#lua_setglobal
+pub unsafe fn lua_setglobal(L: *mut lua_State, name: *const c_char) {
+    lua_setfield(L, LUA_GLOBALSINDEX, name)
+}
+
 // https://www.lua.org/manual/5.1/manual.html#lua_tostring
 pub unsafe fn lua_tostring(L: *mut lua_State, index: c_int) -> *const c_char {
     lua_tolstring(L, index, std::ptr::null_mut())
@@ -81,6 +81,20 @@ impl String {
         Self { data: data as *mut _, size: bytes.len() }
     }
 
+    /// Creates a `String` pointing to `len` bytes starting at `data`, without
+    /// copying them.
+    ///
+    /// # Safety
+    ///
+    /// The bytes must stay valid for as long as the `String` is used. They
+    /// are not null-terminated, so the `String` can only be passed to
+    /// functions which don't rely on that.
+    #[inline]
+    #[doc(hidden)]
+    pub unsafe fn from_raw_parts(data: *const u8, len: usize) -> Self {
+        Self { data: data as *mut _, size: len }
+    }
+
     /// Returns `true` if the `String` has a length of zero.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -1,9 +1,8 @@
 use std::thread;
 use std::time::Duration;
 
-use nvim_oxi::libuv::{AsyncHandle, TimerHandle};
+use nvim_oxi::libuv::{self, Sender, TimerHandle};
 use nvim_oxi::{print, schedule, Error, Result};
-use tokio::sync::mpsc::{self, UnboundedSender};
 use tokio::time;
 
 #[nvim_oxi::plugin]
@@ -38,29 +37,22 @@ fn libuv() -> Result<()> {
     });
 
     // --
-    let (sender, mut receiver) = mpsc::unbounded_channel::<i32>();
-
-    let handle = AsyncHandle::new(move || {
-        let i = receiver.blocking_recv().unwrap();
-        schedule(move |_| {
-            print!("Received number {i} from backround thread");
-            Ok(())
-        });
+    let sender = libuv::channel(16, |i: i32| {
+        print!("Received number {i} from backround thread");
         Ok::<_, Error>(())
     })?;
 
-    let _ = thread::spawn(move || send_numbers(handle, sender));
+    let _ = thread::spawn(move || send_numbers(sender));
 
     Ok(())
 }
 
 #[tokio::main]
-async fn send_numbers(handle: AsyncHandle, sender: UnboundedSender<i32>) {
+async fn send_numbers(sender: Sender<i32>) {
     let mut i = 0;
 
     loop {
         sender.send(i).unwrap();
-        handle.send().unwrap();
         i += 1;
 
         time::sleep(Duration::from_secs(1)).await;
@@ -21,6 +21,11 @@ where
     #[cfg(feature = "libuv")]
     libuv::init(lua_state);
 
+    #[cfg(feature = "tokio")]
+    if let Err(err) = crate::tokio::init() {
+        luajit::utils::push_error(&err, lua_state);
+    }
+
     match body().push(lua_state) {
         Ok(num_pushed) => num_pushed,
         Err(lua_err) => luajit::utils::push_error(&lua_err, lua_state),
@@ -82,6 +82,14 @@ pub mod mlua {
     }
 }
 
+#[cfg(any(feature = "log", feature = "tracing"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "log", feature = "tracing"))))]
+pub mod logging;
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
 pub use error::{Error, Result};
 pub use luajit::{dbg, print};
 pub use macros::plugin;
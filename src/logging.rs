@@ -0,0 +1,627 @@
+//! Backends for the [log] and [tracing] crates which route records to
+//! Neovim.
+//!
+//! A [`Logger`] sends every record to one [`Target`]: either
+//! [`vim.notify()`][notify], the `:messages` history or a rotating file in
+//! Neovim's log directory. It implements [`log::Log`] when the `log` feature
+//! is enabled and [`tracing_subscriber::Layer`] when the `tracing` feature is
+//! enabled.
+//!
+//! The maximum level of the records that are emitted can be changed at
+//! runtime, including from Lua via the function returned by
+//! [`LevelHandle::lua_setter`].
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::{self as nvim, api::types::LogLevel, logging, Dictionary};
+//!
+//! #[nvim::plugin]
+//! fn my_plugin() -> nvim::Result<Dictionary> {
+//!     let logger = logging::Logger::builder()
+//!         .target(logging::Target::Notify)
+//!         .level(LogLevel::Info)
+//!         .build()?;
+//!
+//!     let level = logger.level();
+//!     logger.install_log().unwrap();
+//!
+//!     log::info!("Hello from Rust!");
+//!
+//!     // `require("my_plugin").set_log_level("debug")`.
+//!     Ok(Dictionary::from_iter([("set_log_level", level.lua_setter())]))
+//! }
+//! ```
+//!
+//! [log]: https://docs.rs/log
+//! [tracing]: https://docs.rs/tracing
+//! [notify]: https://neovim.io/doc/user/lua.html#vim.notify()
+
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::api::{self, opts::*, types::LogLevel};
+use crate::conversion::FromObject;
+use crate::libuv::{self, Sender};
+use crate::{Function, Object, ObjectKind};
+
+/// How many records can be waiting to be emitted on the Neovim thread before
+/// new ones start being dropped.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Where a [`Logger`] sends its records.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Target {
+    /// Display records via [`api::notify`], which calls `vim.notify()`.
+    #[default]
+    Notify,
+
+    /// Display records in the message area and save them in the `:messages`
+    /// history via [`api::echo`], highlighted based on their level.
+    Messages,
+
+    /// Append records to a file in the directory returned by
+    /// `stdpath("log")`, rotating it when it gets too big.
+    File(FileTarget),
+}
+
+/// Options for the [`File`](Target::File) target.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileTarget {
+    /// The name of the log file, relative to `stdpath("log")`.
+    pub file_name: PathBuf,
+
+    /// The size in bytes after which the log file is rotated.
+    pub max_size: u64,
+
+    /// How many rotated files to keep around, named `<file_name>.1`,
+    /// `<file_name>.2`, etc., from the most to the least recent.
+    pub max_files: usize,
+}
+
+impl FileTarget {
+    /// Creates a new file target with a maximum size of 1MB and 3 rotated
+    /// files.
+    pub fn new(file_name: impl Into<PathBuf>) -> Self {
+        Self { file_name: file_name.into(), max_size: 1 << 20, max_files: 3 }
+    }
+}
+
+/// A shared handle to the maximum level of the records emitted by a
+/// [`Logger`].
+#[derive(Clone, Debug)]
+pub struct LevelHandle(Arc<AtomicU8>);
+
+impl LevelHandle {
+    fn new(level: LogLevel) -> Self {
+        Self(Arc::new(AtomicU8::new(level as u8)))
+    }
+
+    /// Returns the current level.
+    pub fn get(&self) -> LogLevel {
+        level_from_u8(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Sets the level. Records less severe than this won't be emitted, and
+    /// [`LogLevel::Off`] disables the logger entirely.
+    pub fn set(&self, level: LogLevel) {
+        self.0.store(level as u8, Ordering::Relaxed);
+    }
+
+    /// Returns whether a record of the given level should be emitted.
+    pub fn allows(&self, level: LogLevel) -> bool {
+        level != LogLevel::Off && level as u8 >= self.0.load(Ordering::Relaxed)
+    }
+
+    /// Returns a function which can be exposed to Lua to change the level at
+    /// runtime.
+    ///
+    /// The function accepts either one of the values in `vim.log.levels` or
+    /// their names as case-insensitive strings, e.g. `"debug"`.
+    pub fn lua_setter(&self) -> Function<Object, ()> {
+        let this = self.clone();
+
+        Function::from_fn(move |level: Object| {
+            let level = match level.kind() {
+                ObjectKind::Integer => {
+                    let level = i64::from_object(level)?;
+                    u8::try_from(level)
+                        .ok()
+                        .filter(|&l| l <= LogLevel::Off as u8)
+                        .map(level_from_u8)
+                },
+
+                ObjectKind::String => {
+                    let level = String::from_object(level)?;
+                    level_from_str(&level)
+                },
+
+                _ => None,
+            };
+
+            match level {
+                Some(level) => {
+                    this.set(level);
+                    Ok(())
+                },
+                None => Err(api::Error::Other("invalid log level".into())),
+            }
+        })
+    }
+}
+
+/// A builder for a [`Logger`].
+#[derive(Clone, Debug)]
+pub struct LoggerBuilder {
+    target: Target,
+    level: LogLevel,
+    name: Option<String>,
+}
+
+impl Default for LoggerBuilder {
+    fn default() -> Self {
+        Self { target: Target::default(), level: LogLevel::Info, name: None }
+    }
+}
+
+impl LoggerBuilder {
+    /// Where to send the records. Defaults to [`Target::Notify`].
+    pub fn target(&mut self, target: Target) -> &mut Self {
+        self.target = target;
+        self
+    }
+
+    /// The initial maximum level of the records that are emitted. Defaults to
+    /// [`LogLevel::Info`].
+    pub fn level(&mut self, level: LogLevel) -> &mut Self {
+        self.level = level;
+        self
+    }
+
+    /// A name prepended to every message, usually the name of the plugin.
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Builds the logger.
+    ///
+    /// This has to be called on the Neovim thread, e.g. in the plugin's
+    /// entrypoint. The resulting logger can then be used from any thread.
+    pub fn build(&mut self) -> crate::Result<Logger> {
+        let sink = match &self.target {
+            Target::Notify => Sink::main(MainTarget::Notify)?,
+
+            Target::Messages => Sink::main(MainTarget::Messages)?,
+
+            Target::File(opts) => {
+                let dir =
+                    api::call_function::<_, String>("stdpath", ("log",))?;
+                let file = RotatingFile::open(Path::new(&dir), opts)
+                    .map_err(|err| api::Error::Other(err.to_string()))?;
+                Sink::File(Mutex::new(file))
+            },
+        };
+
+        Ok(Logger {
+            sink: Arc::new(sink),
+            level: LevelHandle::new(self.level),
+            name: self.name.clone(),
+        })
+    }
+}
+
+/// A logger which routes records to Neovim. See the [module-level
+/// documentation](self) for more infos.
+#[derive(Clone)]
+pub struct Logger {
+    sink: Arc<Sink>,
+    level: LevelHandle,
+    name: Option<String>,
+}
+
+enum Sink {
+    /// Records are sent to the Neovim thread.
+    Main(Sender<Record>),
+
+    /// Records are written to a file from the calling thread.
+    File(Mutex<RotatingFile>),
+}
+
+/// The targets whose records are emitted on the Neovim thread.
+#[derive(Copy, Clone)]
+enum MainTarget {
+    Notify,
+    Messages,
+}
+
+impl Sink {
+    /// Creates a sink sending the records to the Neovim thread, where
+    /// they're emitted to `target`.
+    fn main(target: MainTarget) -> crate::Result<Self> {
+        let sender = libuv::channel(CHANNEL_CAPACITY, move |record| {
+            // The API can't be called from a libuv callback.
+            crate::schedule(move |()| {
+                emit_on_main(target, record);
+                Ok(())
+            });
+            Ok::<_, std::convert::Infallible>(())
+        })?;
+
+        Ok(Self::Main(sender))
+    }
+}
+
+struct Record {
+    level: LogLevel,
+    message: String,
+}
+
+impl Logger {
+    #[inline(always)]
+    pub fn builder() -> LoggerBuilder {
+        LoggerBuilder::default()
+    }
+
+    /// Returns a handle which can be used to change the maximum level of the
+    /// records emitted by this logger.
+    pub fn level(&self) -> LevelHandle {
+        self.level.clone()
+    }
+
+    /// Installs this logger as the global logger of the `log` crate.
+    #[cfg(feature = "log")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+    pub fn install_log(self) -> Result<(), ::log::SetLoggerError> {
+        // The filtering is done by the logger itself so that it can be
+        // changed at runtime.
+        ::log::set_boxed_logger(Box::new(self))?;
+        ::log::set_max_level(::log::LevelFilter::Trace);
+        Ok(())
+    }
+
+    /// Emits a record, regardless of the current level.
+    fn emit(&self, level: LogLevel, target: &str, message: &str) {
+        let mut line = String::new();
+
+        if let Some(name) = &self.name {
+            let _ = write!(line, "[{name}] ");
+        }
+
+        if !target.is_empty() {
+            let _ = write!(line, "{target}: ");
+        }
+
+        line.push_str(message);
+
+        match &*self.sink {
+            Sink::Main(sender) => {
+                // Records are dropped if the Neovim thread can't keep up,
+                // since blocking could deadlock if we're on that thread.
+                let _ = sender.try_send(Record { level, message: line });
+            },
+
+            Sink::File(file) => {
+                if let Ok(mut file) = file.lock() {
+                    let _ = file.write_record(level, &line);
+                }
+            },
+        }
+    }
+
+    /// Flushes the log file when using the [`File`](Target::File) target.
+    pub fn flush(&self) {
+        if let Sink::File(file) = &*self.sink {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.file.flush();
+            }
+        }
+    }
+}
+
+fn emit_on_main(target: MainTarget, record: Record) {
+    let Record { level, message } = record;
+
+    let _ = match target {
+        MainTarget::Notify => {
+            api::notify(&message, level, &NotifyOpts::default())
+        },
+
+        MainTarget::Messages => {
+            let hl_group = match level {
+                LogLevel::Error => Some("ErrorMsg"),
+                LogLevel::Warn => Some("WarningMsg"),
+                LogLevel::Debug | LogLevel::Trace => Some("Comment"),
+                _ => None,
+            };
+            api::echo([(message, hl_group)], true, &EchoOpts::default())
+        },
+    };
+}
+
+/// A log file which is rotated when it exceeds a maximum size.
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    fn open(dir: &Path, opts: &FileTarget) -> io::Result<Self> {
+        let path = dir.join(&opts.file_name);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_size: opts.max_size,
+            max_files: opts.max_files,
+        })
+    }
+
+    fn write_record(
+        &mut self,
+        level: LogLevel,
+        message: &str,
+    ) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        let line =
+            format!("{timestamp:.3} {:<5} {message}\n", level_name(level));
+
+        if self.size > 0 && self.size + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shifts `<path>.n` to `<path>.<n+1>` for every rotated file, moves the
+    /// current file to `<path>.1` and starts a new one.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let rotated = |n: usize| {
+            let mut path = self.path.clone().into_os_string();
+            path.push(format!(".{n}"));
+            PathBuf::from(path)
+        };
+
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(rotated(self.max_files));
+            for n in (1..self.max_files).rev() {
+                let _ = fs::rename(rotated(n), rotated(n + 1));
+            }
+            fs::rename(&self.path, rotated(1))?;
+        }
+
+        self.file =
+            OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn level_from_u8(level: u8) -> LogLevel {
+    match level {
+        0 => LogLevel::Trace,
+        1 => LogLevel::Debug,
+        2 => LogLevel::Info,
+        3 => LogLevel::Warn,
+        4 => LogLevel::Error,
+        _ => LogLevel::Off,
+    }
+}
+
+fn level_from_str(level: &str) -> Option<LogLevel> {
+    Some(match level.to_ascii_lowercase().as_str() {
+        "trace" => LogLevel::Trace,
+        "debug" => LogLevel::Debug,
+        "info" => LogLevel::Info,
+        "warn" => LogLevel::Warn,
+        "error" => LogLevel::Error,
+        "off" => LogLevel::Off,
+        _ => return None,
+    })
+}
+
+fn level_name(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Trace => "TRACE",
+        LogLevel::Debug => "DEBUG",
+        LogLevel::Info => "INFO",
+        LogLevel::Warn => "WARN",
+        LogLevel::Error => "ERROR",
+        _ => "OFF",
+    }
+}
+
+#[cfg(feature = "log")]
+mod log_impl {
+    use ::log::{Level, Log, Metadata, Record};
+
+    use super::*;
+
+    fn to_log_level(level: Level) -> LogLevel {
+        match level {
+            Level::Error => LogLevel::Error,
+            Level::Warn => LogLevel::Warn,
+            Level::Info => LogLevel::Info,
+            Level::Debug => LogLevel::Debug,
+            Level::Trace => LogLevel::Trace,
+        }
+    }
+
+    impl Log for Logger {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            self.level.allows(to_log_level(metadata.level()))
+        }
+
+        fn log(&self, record: &Record) {
+            let level = to_log_level(record.level());
+
+            if self.level.allows(level) {
+                let message = record.args().to_string();
+                self.emit(level, record.target(), &message);
+            }
+        }
+
+        fn flush(&self) {
+            Logger::flush(self);
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+mod tracing_impl {
+    use core::fmt;
+
+    use ::tracing::field::{Field, Visit};
+    use ::tracing::subscriber::Interest;
+    use ::tracing::{Event, Level, Metadata, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer};
+
+    use super::*;
+
+    fn to_log_level(level: &Level) -> LogLevel {
+        match *level {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            Level::INFO => LogLevel::Info,
+            Level::DEBUG => LogLevel::Debug,
+            Level::TRACE => LogLevel::Trace,
+        }
+    }
+
+    /// Formats the `message` field of an event followed by all its other
+    /// fields as `key=value` pairs.
+    #[derive(Default)]
+    struct MessageVisitor {
+        message: String,
+        fields: String,
+    }
+
+    impl Visit for MessageVisitor {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                let _ = write!(self.message, "{value:?}");
+            } else {
+                let _ = write!(self.fields, " {}={value:?}", field.name());
+            }
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "message" {
+                self.message.push_str(value);
+            } else {
+                let _ = write!(self.fields, " {}={value:?}", field.name());
+            }
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for Logger {
+        fn register_callsite(
+            &self,
+            _: &'static Metadata<'static>,
+        ) -> Interest {
+            // The default caches `Interest::never()` for the callsites that
+            // are disabled when they're first hit, so they'd stay disabled
+            // after the level is raised at runtime.
+            Interest::sometimes()
+        }
+
+        fn enabled(&self, metadata: &Metadata<'_>, _: Context<'_, S>) -> bool {
+            self.level.allows(to_log_level(metadata.level()))
+        }
+
+        fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+            let metadata = event.metadata();
+            let level = to_log_level(metadata.level());
+
+            if !self.level.allows(level) {
+                return;
+            }
+
+            let mut visitor = MessageVisitor::default();
+            event.record(&mut visitor);
+            visitor.message.push_str(&visitor.fields);
+
+            self.emit(level, metadata.target(), &visitor.message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_handle_allows() {
+        let level = LevelHandle::new(LogLevel::Warn);
+        assert!(level.allows(LogLevel::Error));
+        assert!(level.allows(LogLevel::Warn));
+        assert!(!level.allows(LogLevel::Info));
+
+        level.set(LogLevel::Off);
+        assert!(!level.allows(LogLevel::Error));
+        assert!(!level.allows(LogLevel::Off));
+    }
+
+    #[test]
+    fn level_from_str_is_case_insensitive() {
+        assert_eq!(level_from_str("DEBUG"), Some(LogLevel::Debug));
+        assert_eq!(level_from_str("warn"), Some(LogLevel::Warn));
+        assert_eq!(level_from_str("warning"), None);
+    }
+
+    #[test]
+    fn rotating_file() {
+        let dir = std::env::temp_dir()
+            .join(format!("nvim-oxi-logging-{}", std::process::id()));
+
+        let opts = FileTarget {
+            file_name: "test.log".into(),
+            max_size: 64,
+            max_files: 2,
+        };
+
+        let mut file = RotatingFile::open(&dir, &opts).unwrap();
+
+        for i in 0..10 {
+            file.write_record(LogLevel::Info, &format!("record {i}")).unwrap();
+        }
+
+        file.file.flush().unwrap();
+
+        let current = fs::read_to_string(dir.join("test.log")).unwrap();
+        assert!(current.ends_with("record 9\n"), "{current}");
+        assert!(current.len() as u64 <= opts.max_size);
+
+        assert!(dir.join("test.log.1").exists());
+        assert!(dir.join("test.log.2").exists());
+        assert!(!dir.join("test.log.3").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
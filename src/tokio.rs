@@ -0,0 +1,297 @@
+//! Integration with the [tokio] async runtime.
+//!
+//! When the `tokio` feature is enabled a multi-threaded runtime is started as
+//! soon as the plugin is loaded, and shut down when Neovim fires the
+//! `VimLeavePre` event. Futures can be spawned on it with [`spawn`], and
+//! tasks running on the runtime can hop back onto the Neovim thread, where
+//! the API can be called, with [`run_on_main`] or [`spawn_on_main`].
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use nvim_oxi::{self as nvim, api};
+//!
+//! #[nvim::plugin]
+//! fn my_plugin() -> nvim::Result<()> {
+//!     nvim::tokio::spawn(async {
+//!         let contents = tokio::fs::read_to_string("Cargo.toml").await?;
+//!
+//!         nvim::tokio::run_on_main(async move {
+//!             nvim::print!("{} bytes", contents.len());
+//!         })
+//!         .await;
+//!
+//!         Ok::<_, std::io::Error>(())
+//!     });
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [tokio]: https://tokio.rs
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::Wake;
+use std::time::Duration;
+
+use ::tokio::runtime::{self, Runtime};
+use ::tokio::sync::oneshot;
+use ::tokio::task::JoinHandle;
+use thiserror::Error as ThisError;
+
+use crate::api::{self, opts::CreateAutocmdOpts};
+use crate::libuv::AsyncHandle;
+
+/// How long to wait for the tasks running on the runtime to complete when
+/// Neovim is shutting down.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+
+static RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+
+static HANDLE: OnceLock<runtime::Handle> = OnceLock::new();
+
+static MAIN_QUEUE: OnceLock<MainQueue> = OnceLock::new();
+
+type MainTask = Pin<Box<dyn Future<Output = ()> + 'static>>;
+
+type SendMainTask = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+thread_local! {
+    /// The tasks spawned on the Neovim thread which haven't completed yet.
+    static MAIN_TASKS: RefCell<HashMap<u64, MainTask>> =
+        RefCell::new(HashMap::new());
+
+    static NEXT_TASK_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Starts the runtime and registers the autocommand that shuts it down.
+///
+/// Called by the plugin's entrypoint, so it's executed on the Neovim thread.
+pub(crate) fn init() -> crate::Result<()> {
+    if HANDLE.get().is_some() {
+        return Ok(());
+    }
+
+    let runtime = runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("nvim-oxi-tokio")
+        .build()
+        .map_err(|err| api::Error::Other(err.to_string()))?;
+
+    let handle = AsyncHandle::new(|| {
+        // The API can't be called from a libuv callback, so we poll the
+        // tasks on the next iteration of the main loop.
+        crate::schedule(|()| {
+            poll_main_queue();
+            Ok(())
+        });
+        Ok::<_, Infallible>(())
+    })?;
+
+    let _ = MAIN_QUEUE.set(MainQueue { messages: Mutex::default(), handle });
+    let _ = HANDLE.set(runtime.handle().clone());
+    *RUNTIME.lock().unwrap() = Some(runtime);
+
+    let opts = CreateAutocmdOpts::builder()
+        .desc("Shuts down nvim-oxi's tokio runtime")
+        .once(true)
+        .callback(|_| {
+            shutdown();
+            Ok::<_, Infallible>(false)
+        })
+        .build();
+
+    api::create_autocmd(["VimLeavePre"], &opts)?;
+
+    Ok(())
+}
+
+/// Shuts down the runtime, waiting for a short while for the running tasks
+/// to complete, and drops all the pending tasks spawned on the Neovim
+/// thread.
+///
+/// This is called automatically on `VimLeavePre`.
+pub fn shutdown() {
+    if let Some(runtime) = RUNTIME.lock().unwrap().take() {
+        runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+    }
+
+    MAIN_TASKS.with(|tasks| tasks.borrow_mut().clear());
+}
+
+/// Returns a handle to the runtime.
+///
+/// # Panics
+///
+/// Panics if the plugin's entrypoint hasn't been executed yet.
+pub fn handle() -> &'static runtime::Handle {
+    HANDLE.get().expect("the tokio runtime hasn't been started")
+}
+
+/// Spawns a future on the runtime. It's the same as calling
+/// [`Handle::spawn`](runtime::Handle::spawn) on [`handle()`].
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    handle().spawn(future)
+}
+
+/// Spawns a future on the Neovim thread, returning a handle which can be
+/// awaited from any thread to get its output.
+///
+/// The future is polled on the Neovim thread inside the runtime's context,
+/// so it can both call the Neovim API and use tokio's timers and I/O.
+pub fn spawn_on_main<F>(future: F) -> MainJoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+
+    let task = Box::pin(async move {
+        let _ = sender.send(future.await);
+    });
+
+    main_queue().push(Message::Spawn(task));
+
+    MainJoinHandle { receiver }
+}
+
+/// Runs a future on the Neovim thread and returns its output.
+///
+/// This is a shorthand for awaiting the handle returned by
+/// [`spawn_on_main`].
+///
+/// # Panics
+///
+/// Panics if the task was dropped before completing, which can only happen
+/// if Neovim is shutting down.
+pub async fn run_on_main<F>(future: F) -> F::Output
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    spawn_on_main(future).await.expect("Neovim is shutting down")
+}
+
+/// A handle to a task spawned on the Neovim thread by [`spawn_on_main`].
+///
+/// Awaiting it returns the output of the task.
+pub struct MainJoinHandle<T> {
+    receiver: oneshot::Receiver<T>,
+}
+
+impl<T> Future for MainJoinHandle<T> {
+    type Output = Result<T, MainTaskDropped>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver).poll(cx).map_err(|_| MainTaskDropped)
+    }
+}
+
+/// The error returned by a [`MainJoinHandle`] if its task was dropped before
+/// completing.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ThisError)]
+#[error("the task spawned on the Neovim thread was dropped")]
+pub struct MainTaskDropped;
+
+/// A queue of messages for the Neovim thread, together with the handle used
+/// to wake it up.
+struct MainQueue {
+    messages: Mutex<VecDeque<Message>>,
+    handle: AsyncHandle,
+}
+
+enum Message {
+    /// A new task to be polled on the Neovim thread.
+    Spawn(SendMainTask),
+
+    /// A task that has been woken up and should be polled again.
+    Wake(u64),
+}
+
+impl MainQueue {
+    fn push(&self, message: Message) {
+        self.messages.lock().unwrap().push_back(message);
+        // This can only fail if the handle is not an async handle.
+        let _ = self.handle.send();
+    }
+
+    fn pop(&self) -> Option<Message> {
+        self.messages.lock().unwrap().pop_front()
+    }
+}
+
+fn main_queue() -> &'static MainQueue {
+    MAIN_QUEUE.get().expect("the tokio runtime hasn't been started")
+}
+
+/// Wakes up a task spawned on the Neovim thread by pushing its id on the
+/// queue.
+struct MainWaker {
+    task_id: u64,
+}
+
+impl Wake for MainWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        main_queue().push(Message::Wake(self.task_id));
+    }
+}
+
+/// Polls all the tasks in the queue. Scheduled on the Neovim thread every time
+/// its async handle is woken up.
+fn poll_main_queue() {
+    let queue = main_queue();
+
+    // Keep the runtime's context entered so that the tasks can use its
+    // timers and I/O drivers.
+    let _guard = handle().enter();
+
+    while let Some(message) = queue.pop() {
+        let (task_id, task) = match message {
+            Message::Spawn(task) => {
+                let task_id = NEXT_TASK_ID.with(|id| id.replace(id.get() + 1));
+                (task_id, task as MainTask)
+            },
+
+            Message::Wake(task_id) => {
+                // The task may have already completed.
+                let Some(task) = MAIN_TASKS
+                    .with(|tasks| tasks.borrow_mut().remove(&task_id))
+                else {
+                    continue;
+                };
+                (task_id, task)
+            },
+        };
+
+        poll_main_task(task_id, task);
+    }
+}
+
+fn poll_main_task(task_id: u64, mut task: MainTask) {
+    let waker = Waker::from(Arc::new(MainWaker { task_id }));
+
+    let mut cx = Context::from_waker(&waker);
+
+    // The task is removed from the map while it's being polled so that it
+    // can spawn other tasks on the Neovim thread.
+    if task.as_mut().poll(&mut cx).is_pending() {
+        MAIN_TASKS.with(|tasks| tasks.borrow_mut().insert(task_id, task));
+    }
+}
@@ -87,6 +87,85 @@ fn buf_create_del_user_command() {
     assert_eq!(Ok(()), buf.del_user_command("Bar"));
 }
 
+#[nvim::test]
+fn buf_edit() -> Result<(), api::Error> {
+    let mut buf = api::create_buf(true, false)?;
+
+    let mut win = api::Window::current();
+    win.set_buf(&buf)?;
+
+    buf.set_lines(.., true, ["foo", "bar", "baz"])?;
+    win.set_cursor_position(Position::new(2, 1))?;
+
+    // Close the undo block of `set_lines()`.
+    api::command("let &g:undolevels = &g:undolevels")?;
+
+    buf.edit(|tx| {
+        tx.insert(Position::new(0, 0), "// ");
+        tx.replace(
+            api::types::Range::new(Position::new(1, 0), Position::new(2, 0)),
+            "qux\nquux\n",
+        );
+        Ok(())
+    })?;
+
+    let lines = buf
+        .get_lines(.., true)?
+        .map(|s| s.to_string_lossy().into())
+        .collect::<Vec<String>>();
+    assert_eq!(lines, ["// foo", "qux", "quux", "baz"]);
+    assert_eq!(win.get_cursor_position()?, Position::new(3, 1));
+
+    // Overlapping edits are rejected without touching the buffer.
+    let res = buf.edit(|tx| {
+        tx.delete(api::types::Range::new(
+            Position::new(0, 0),
+            Position::new(1, 2),
+        ));
+        tx.insert(Position::new(1, 1), "x");
+        Ok(())
+    });
+    assert!(res.is_err());
+    assert_eq!(buf.line_count()?, 4);
+
+    // All the edits are undone at once.
+    api::command("undo")?;
+
+    let lines = buf
+        .get_lines(.., true)?
+        .map(|s| s.to_string_lossy().into())
+        .collect::<Vec<String>>();
+    assert_eq!(lines, ["foo", "bar", "baz"]);
+
+    Ok(())
+}
+
+#[nvim::test]
+fn buf_edit_rollback() -> Result<(), api::Error> {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["foo", "bar", "baz"])?;
+
+    // The edits are applied from the last one, so the second one succeeds
+    // before the first one fails because its column is out of range.
+    let res = buf.edit(|tx| {
+        tx.insert(Position::new(0, 100), "x");
+        tx.replace(
+            api::types::Range::new(Position::new(2, 0), Position::new(2, 3)),
+            "qux",
+        );
+        Ok(())
+    });
+    assert!(res.is_err());
+
+    let lines = buf
+        .get_lines(.., true)?
+        .map(|s| s.to_string_lossy().into())
+        .collect::<Vec<String>>();
+    assert_eq!(lines, ["foo", "bar", "baz"]);
+
+    Ok(())
+}
+
 #[nvim::test]
 fn buf_get_changedtick() {
     let buf = Buffer::current();
@@ -132,6 +211,103 @@ fn buf_loaded_n_valid() {
     assert!(buf.is_valid());
 }
 
+#[nvim::test]
+fn buf_mirror() -> Result<(), api::Error> {
+    let mut buffer = api::create_buf(true, false)?;
+    buffer.set_lines(.., true, ["foo", "bar", "baz"])?;
+
+    let mirror = api::BufferMirror::attach(&buffer)?;
+    let tick = mirror.changedtick();
+
+    buffer.set_text(0..2, 1, 1, ["X", "Y"])?;
+    buffer.set_lines(2..2, true, ["qux"])?;
+    buffer.set_lines(0..1, true, Vec::<&str>::new())?;
+
+    let lines = buffer
+        .get_lines(.., true)?
+        .map(|line| line.as_bytes().to_owned())
+        .collect::<Vec<_>>();
+
+    assert_eq!(mirror.lines(0..mirror.line_count()), Some(lines));
+    assert_eq!(mirror.changedtick(), buffer.get_changedtick()?);
+    assert_eq!(mirror.byte_len(), buffer.get_offset(mirror.line_count())?);
+
+    let changes = mirror.changes_since(tick).unwrap().collect::<Vec<_>>();
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[0].old_bytes, 1..9);
+    assert_eq!(changes[0].new_bytes, 1..4);
+
+    drop(mirror);
+    buffer.set_lines(.., true, ["detached"])?;
+
+    Ok(())
+}
+
+#[nvim::test]
+fn buf_mirror_content_changes() -> Result<(), api::Error> {
+    let mut buffer = api::create_buf(true, false)?;
+    buffer.set_lines(.., true, ["héllo", "wörld"])?;
+
+    let mirror = api::BufferMirror::attach(&buffer)?;
+
+    let changes = Rc::new(std::cell::RefCell::new(Vec::new()));
+
+    {
+        let changes = changes.clone();
+        mirror.on_change(move |change, old_text| {
+            changes.borrow_mut().push(change.to_content_change(old_text)?);
+            Ok(())
+        });
+    }
+
+    buffer.set_text(0..1, 3, 1, ["y", "W"])?;
+
+    let changes = changes.borrow();
+    assert_eq!(changes.len(), 1);
+
+    let change = &changes[0];
+    assert_eq!(change.text, "y\nW");
+    assert_eq!(change.range_length, 5);
+    assert_eq!(
+        change.range,
+        LspRange {
+            start: LspPosition { line: 0, character: 2 },
+            end: LspPosition { line: 1, character: 1 },
+        }
+    );
+
+    Ok(())
+}
+
+#[nvim::test]
+fn buf_mirror_on_change_from_listener() -> Result<(), api::Error> {
+    let mut buffer = api::create_buf(true, false)?;
+    let mirror = Rc::new(api::BufferMirror::attach(&buffer)?);
+
+    let num_called = Rc::new(Cell::new(0));
+
+    {
+        let mirror = Rc::downgrade(&mirror);
+        let num_called = num_called.clone();
+        mirror.upgrade().unwrap().on_change(move |_, _| {
+            let num_called = num_called.clone();
+            mirror.upgrade().unwrap().on_change(move |_, _| {
+                num_called.set(num_called.get() + 1);
+                Ok(())
+            });
+            Ok(())
+        });
+    }
+
+    buffer.set_lines(.., true, ["foo"])?;
+    assert_eq!(0, num_called.get());
+
+    buffer.set_lines(.., true, ["bar"])?;
+    assert_eq!(1, num_called.get());
+
+    Ok(())
+}
+
 #[nvim::test]
 fn buf_new_delete() {
     let buf = api::create_buf(true, false).unwrap();
@@ -198,6 +374,26 @@ fn buf_set_get_del_lines() {
     assert_eq!(Ok(1), buf.line_count());
 }
 
+#[nvim::test]
+fn buf_set_lines_from_bytes_with_lines() -> Result<(), api::Error> {
+    let mut buf = api::create_buf(true, false)?;
+
+    buf.set_lines_from_bytes(.., true, b"foo\n\nbar\n")?;
+    assert_eq!(buf.line_count()?, 3);
+
+    let lines = buf.with_lines(.., |lines| {
+        lines.iter().map(|line| line.to_vec()).collect::<Vec<_>>()
+    })?;
+    assert_eq!(lines, [&b"foo"[..], b"", b"bar"]);
+
+    let len = buf.with_lines(1..=2, |lines| lines.len())?;
+    assert_eq!(len, 2);
+
+    assert!(buf.with_lines(2..4, |_| ()).is_err());
+
+    Ok(())
+}
+
 #[nvim::test]
 fn buf_set_get_del_mark() {
     let mut buf = Buffer::current();
@@ -244,6 +440,30 @@ fn buf_set_get_del_text() {
     assert_eq!(Ok(1), buf.line_count());
 }
 
+#[nvim::test]
+fn buf_set_get_text_range() -> Result<(), api::Error> {
+    let mut buf = api::create_buf(true, false)?;
+    buf.set_lines(.., true, ["foo", "bar", "baz"])?;
+
+    let range =
+        api::types::Range::new(Position::new(0, 1), Position::new(1, 2));
+
+    let text = buf
+        .get_text_range(range, &Default::default())?
+        .map(|s| s.to_string_lossy().into())
+        .collect::<Vec<String>>();
+    assert_eq!(text, ["oo", "ba"]);
+
+    buf.set_text_range(range, ["X"])?;
+    assert_eq!(buf.get_lines(..1, true)?.next().unwrap(), "fXr");
+
+    buf.set_mark_position('a', Position::new(1, 1), &Default::default())?;
+    assert_eq!(buf.get_mark_position('a')?, Some(Position::new(1, 1)));
+    assert_eq!(buf.get_mark_position('b')?, None);
+
+    Ok(())
+}
+
 #[nvim::test]
 fn buf_set_get_del_var() {
     let mut buf = Buffer::current();
@@ -0,0 +1,92 @@
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        opts::OptionOpts,
+        types::{CompleteDoneEvent, CompletionItem},
+        Buffer, CompletionProvider, CompletionStart, Completions, Window,
+    },
+    conversion::FromObject,
+    Object,
+};
+
+struct Words(&'static [&'static str]);
+
+impl CompletionProvider for Words {
+    fn complete(&mut self, base: &str) -> Completions {
+        self.0
+            .iter()
+            .filter(|word| word.starts_with(base))
+            .map(|&word| {
+                let mut item = CompletionItem::new(word);
+                item.kind = Some("v".into());
+                item
+            })
+            .collect()
+    }
+}
+
+#[nvim::test]
+fn completion_set_completefunc() {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["let fo"]).unwrap();
+    Window::current().set_cursor(1, 6).unwrap();
+
+    assert_eq!(Ok(()), buf.set_completefunc(Words(&["foo", "bar", "fox"])));
+
+    let opts = OptionOpts::builder().buffer(buf.clone()).build();
+    let func = api::get_option_value::<String>("completefunc", &opts).unwrap();
+    assert!(func.starts_with("v:lua."));
+
+    let start = api::eval::<i64>(&format!("{func}(1, '')")).unwrap();
+    assert_eq!(4, start);
+
+    let words = api::eval::<Vec<Object>>(&format!("{func}(0, 'fo')"))
+        .unwrap()
+        .into_iter()
+        .map(|obj| FromObject::from_object(obj).unwrap())
+        .collect::<Vec<CompletionItem>>();
+
+    assert_eq!(
+        vec!["foo", "fox"],
+        words.iter().map(|item| item.word.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(Some("v"), words[0].kind.as_deref());
+}
+
+#[nvim::test]
+fn completion_start_to_object() {
+    assert_eq!(Object::from(3), CompletionStart::Column(3).into());
+    assert_eq!(Object::from(-2), CompletionStart::Cancel.into());
+    assert_eq!(Object::from(-3), CompletionStart::Leave.into());
+}
+
+#[nvim::test]
+fn completion_complete_done_event() {
+    let event = CompleteDoneEvent::get().unwrap();
+    assert_eq!(None, event.item);
+}
+
+#[nvim::test]
+fn completion_provider_replaced_and_wiped() {
+    let buf = api::create_buf(true, false).unwrap();
+    let opts = OptionOpts::builder().buffer(buf.clone()).build();
+
+    assert_eq!(Ok(()), buf.set_omnifunc(Words(&["foo"])));
+    let first = api::get_option_value::<String>("omnifunc", &opts).unwrap();
+
+    // Setting another provider replaces the global of the first one.
+    assert_eq!(Ok(()), buf.set_omnifunc(Words(&["bar"])));
+    let second = api::get_option_value::<String>("omnifunc", &opts).unwrap();
+    assert_eq!(first, second);
+
+    let global = second.trim_start_matches("v:lua.").to_owned();
+    let is_set = || {
+        api::call_function::<_, bool>("luaeval", (format!("{global} ~= nil"),))
+            .unwrap()
+    };
+    assert!(is_set());
+
+    buf.delete(&Default::default()).unwrap();
+    assert!(!is_set());
+}
@@ -0,0 +1,138 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        diagnostic,
+        opts::{
+            CreateAutocmdOpts,
+            DiagnosticConfigOpts,
+            DiagnosticVirtualTextOpts,
+            GetDiagnosticsOpts,
+        },
+        types::{
+            Diagnostic,
+            DiagnosticChangedEvent,
+            DiagnosticSeverity,
+            Position,
+            Range,
+        },
+        Buffer,
+    },
+    Integer,
+    Object,
+};
+
+fn diagnostics() -> Vec<Diagnostic> {
+    let range = |row| Range::new(Position::new(row, 0), Position::new(row, 3));
+
+    let mut warning =
+        Diagnostic::new(range(0), DiagnosticSeverity::Warn, "a warning");
+    warning.source = Some("test".into());
+    warning.code = Some("W001".into());
+
+    let error =
+        Diagnostic::new(range(1), DiagnosticSeverity::Error, "an error");
+
+    vec![warning, error]
+}
+
+#[nvim::test]
+fn diagnostic_set_get() {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["foo", "bar"]).unwrap();
+
+    let ns = api::create_namespace("diagnostic_set_get");
+    assert_eq!(Ok(()), diagnostic::set(ns, &buf, diagnostics()));
+
+    let opts = GetDiagnosticsOpts::builder().namespace(ns).build();
+    let got = diagnostic::get(Some(&buf), &opts).unwrap();
+    assert_eq!(2, got.len());
+
+    for (got, expected) in got.iter().zip(diagnostics()) {
+        assert_eq!(expected.range, got.range);
+        assert_eq!(expected.severity, got.severity);
+        assert_eq!(expected.message, got.message);
+        assert_eq!(expected.source, got.source);
+        assert_eq!(expected.code, got.code);
+        assert_eq!(Some(&buf), got.buffer.as_ref());
+        assert_eq!(Some(ns), got.namespace);
+    }
+
+    let opts = GetDiagnosticsOpts::builder()
+        .severity(DiagnosticSeverity::Error)
+        .build();
+    let got = diagnostic::get(Some(&buf), &opts).unwrap();
+    assert_eq!(1, got.len());
+    assert_eq!("an error", got[0].message);
+
+    assert_eq!(Ok(()), diagnostic::reset(Some(ns), None));
+    let got = diagnostic::get(None, &Default::default()).unwrap();
+    assert!(got.is_empty());
+}
+
+#[nvim::test]
+fn diagnostic_config() {
+    let opts = DiagnosticConfigOpts::builder()
+        .underline(false)
+        .severity_sort(true, true)
+        .virtual_text_opts(&DiagnosticVirtualTextOpts {
+            prefix: Some(">".into()),
+            min_severity: Some(DiagnosticSeverity::Warn),
+            ..Default::default()
+        })
+        .build();
+
+    let ns = api::create_namespace("diagnostic_config");
+    assert_eq!(Ok(()), diagnostic::config(&opts, Some(ns)));
+    assert_eq!(Ok(()), diagnostic::config(&opts, None));
+}
+
+#[nvim::test]
+fn diagnostic_config_min_severity() {
+    let opts = DiagnosticConfigOpts::builder()
+        .virtual_text_opts(&DiagnosticVirtualTextOpts {
+            min_severity: Some(DiagnosticSeverity::Warn),
+            ..Default::default()
+        })
+        .build();
+
+    assert_eq!(Ok(()), diagnostic::config(&opts, None));
+
+    let severity = |key: &str| {
+        let expr =
+            format!("vim.diagnostic.config().virtual_text.severity.{key}");
+        api::call_function::<_, Object>("luaeval", (expr,)).unwrap()
+    };
+
+    // Warnings and errors are displayed, infos and hints aren't.
+    let warn = DiagnosticSeverity::Warn as Integer;
+    assert_eq!(Object::from(warn), severity("min"));
+    assert_eq!(Object::nil(), severity("max"));
+}
+
+#[nvim::test]
+fn diagnostic_changed_event() {
+    let buf = Buffer::current();
+    let event = Rc::new(RefCell::new(None));
+    let cloned = Rc::clone(&event);
+
+    let opts = CreateAutocmdOpts::builder()
+        .callback(move |args| {
+            let event = DiagnosticChangedEvent::try_from(args)?;
+            *cloned.borrow_mut() = Some(event);
+            Ok::<_, nvim::Error>(true)
+        })
+        .build();
+
+    api::create_autocmd(["DiagnosticChanged"], &opts).unwrap();
+
+    let ns = api::create_namespace("diagnostic_changed_event");
+    diagnostic::set(ns, &buf, diagnostics()).unwrap();
+
+    let event = event.borrow_mut().take().unwrap();
+    assert_eq!(buf, event.buffer);
+    assert_eq!(2, event.diagnostics.len());
+}
@@ -0,0 +1,86 @@
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        opts::{OptionOpts, OptionScope},
+        Buffer, Fold, FoldLevel, Window,
+    },
+};
+
+#[nvim::test]
+fn foldexpr_provider() {
+    let mut buf = Buffer::current();
+    let mut win = Window::current();
+
+    let lines = ["fn foo() {", "  1", "  2", "}", "", "x"];
+    buf.set_lines(.., true, lines).unwrap();
+
+    let calls = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&calls);
+
+    let res = buf.set_foldexpr(move |_buf| {
+        counter.set(counter.get() + 1);
+        Ok::<_, Infallible>(vec![
+            FoldLevel::Start(1),
+            FoldLevel::Same,
+            FoldLevel::Same,
+            FoldLevel::End(1),
+            FoldLevel::Level(0),
+        ])
+    });
+    assert_eq!(Ok(()), res);
+
+    // `zX` closes the folds deeper than `'foldlevel'`, which is 0.
+    api::command("normal! zX").unwrap();
+
+    let expected = Fold { start: 0, end: 3, level: 1, is_closed: true };
+    assert_eq!(vec![expected], win.folds().unwrap());
+
+    // The levels are computed once per `changedtick`.
+    assert_eq!(1, calls.get());
+
+    assert_eq!(Ok(()), win.open_fold(1));
+    assert!(!win.folds().unwrap()[0].is_closed);
+
+    assert_eq!(Ok(()), win.close_fold(1));
+    assert!(win.folds().unwrap()[0].is_closed);
+
+    assert_eq!(Ok(()), win.toggle_fold(0));
+    assert!(!win.folds().unwrap()[0].is_closed);
+
+    assert_eq!(Ok(()), win.toggle_fold(2));
+    assert!(win.folds().unwrap()[0].is_closed);
+
+    assert_eq!(Ok(()), win.open_fold(3));
+    assert!(!win.folds().unwrap()[0].is_closed);
+}
+
+#[nvim::test]
+fn open_fold_without_fold() {
+    assert!(Window::current().open_fold(0).is_err());
+}
+
+#[nvim::test]
+fn foldexpr_provider_keeps_global_options() {
+    let mut buf = Buffer::current();
+
+    let res = buf
+        .set_foldexpr(|_buf| Ok::<_, Infallible>(vec![FoldLevel::Level(0)]));
+    assert_eq!(Ok(()), res);
+
+    let opts = OptionOpts::builder().scope(OptionScope::Global).build();
+    let get = |name| api::get_option_value::<String>(name, &opts).unwrap();
+
+    assert_eq!("manual", get("foldmethod"));
+    assert_eq!("0", get("foldexpr"));
+
+    let local = OptionOpts::builder().scope(OptionScope::Local).build();
+    assert_eq!(
+        Ok("expr".to_owned()),
+        api::get_option_value::<String>("foldmethod", &local)
+    );
+}
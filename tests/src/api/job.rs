@@ -0,0 +1,92 @@
+use std::cell::{Cell, RefCell};
+use std::convert::Infallible;
+use std::rc::Rc;
+use std::time::Duration;
+
+use nvim_oxi::{
+    self as nvim,
+    api::{types::ChannelStream, Job, JobWaitStatus},
+};
+
+#[nvim::test]
+fn job_stdout_and_exit() {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let code = Rc::new(Cell::new(None));
+
+    let stdout = Rc::clone(&lines);
+    let exit = Rc::clone(&code);
+
+    let job = Job::builder(["sh", "-c", "printf 'foo\\nbar\\nbaz'; exit 3"])
+        .on_stdout(move |output| {
+            stdout.borrow_mut().extend(output.lines);
+            Ok::<_, Infallible>(())
+        })
+        .on_exit(move |status| {
+            exit.set(Some(status));
+            Ok::<_, Infallible>(())
+        })
+        .start()
+        .unwrap();
+
+    assert_eq!(Ok(JobWaitStatus::Exited(3)), job.wait(None));
+    assert_eq!(vec!["foo", "bar", "baz"], *lines.borrow());
+    assert_eq!(Some(3), code.get());
+}
+
+#[nvim::test]
+fn job_send_and_close_stdin() {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let stdout = Rc::clone(&lines);
+
+    let job = Job::builder(["cat"])
+        .on_stdout(move |output| {
+            stdout.borrow_mut().extend(output.lines);
+            Ok::<_, Infallible>(())
+        })
+        .start()
+        .unwrap();
+
+    let infos = job.channel().info().unwrap();
+    assert_eq!(ChannelStream::Job, infos.stream);
+
+    assert_eq!(Ok(()), job.send("hello\n"));
+    assert_eq!(Ok(()), job.channel().close_stdin());
+    assert_eq!(Ok(JobWaitStatus::Exited(0)), job.wait(None));
+    assert_eq!(vec!["hello"], *lines.borrow());
+}
+
+#[nvim::test]
+fn job_env_and_cwd() {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let stdout = Rc::clone(&lines);
+
+    let job = Job::builder(["sh", "-c", "echo $OXI_VAR; pwd"])
+        .env("OXI_VAR", "oxi")
+        .cwd("/")
+        .on_stdout(move |output| {
+            stdout.borrow_mut().extend(output.lines);
+            Ok::<_, Infallible>(())
+        })
+        .start()
+        .unwrap();
+
+    assert_eq!(Ok(JobWaitStatus::Exited(0)), job.wait(None));
+    assert_eq!(vec!["oxi", "/"], *lines.borrow());
+}
+
+#[nvim::test]
+fn job_wait_timeout_and_stop() {
+    let job = Job::builder(["sleep", "10"]).start().unwrap();
+
+    let status = job.wait(Some(Duration::from_millis(10)));
+    assert_eq!(Ok(JobWaitStatus::TimedOut), status);
+
+    assert_eq!(Ok(()), job.stop());
+    assert!(matches!(job.wait(None), Ok(JobWaitStatus::Exited(_))));
+}
+
+#[nvim::test]
+fn job_not_executable() {
+    let res = Job::builder(["this-is-not-a-command"]).start();
+    assert!(res.is_err());
+}
@@ -0,0 +1,156 @@
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        keymap::{self, Action, Keymap, KeymapBuilder},
+        opts::SetKeymapOpts,
+        types::Mode,
+        Buffer,
+    },
+};
+
+fn count(mode: Mode, lhs: &str) -> usize {
+    api::get_keymap(mode).filter(|infos| infos.lhs == lhs).count()
+}
+
+#[nvim::test]
+fn keymap_set_multiple_modes() {
+    let keymap =
+        keymap::set(vec![Mode::Normal, Mode::Visual], "zz", "<Cmd>w<CR>")
+            .unwrap();
+
+    assert_eq!(1, count(Mode::Normal, "zz"));
+    assert_eq!(1, count(Mode::Visual, "zz"));
+
+    let infos =
+        api::get_keymap(Mode::Normal).find(|infos| infos.lhs == "zz").unwrap();
+    assert!(infos.noremap);
+    assert_eq!(Some("<Cmd>w<CR>"), infos.rhs.as_deref());
+
+    assert_eq!(Ok(()), keymap.del());
+    assert_eq!(0, count(Mode::Normal, "zz"));
+    assert_eq!(0, count(Mode::Visual, "zz"));
+}
+
+#[nvim::test]
+fn keymap_closure() {
+    let calls = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&calls);
+
+    Keymap::builder(Mode::Normal, "zx", move || {
+        counter.set(counter.get() + 1);
+        Ok::<_, Infallible>(())
+    })
+    .desc("Count")
+    .set()
+    .unwrap();
+
+    api::command("normal zx").unwrap();
+    assert_eq!(1, calls.get());
+
+    let infos =
+        api::get_keymap(Mode::Normal).find(|infos| infos.lhs == "zx").unwrap();
+    assert!(infos.callback.is_some());
+    assert_eq!(Some("Count"), infos.desc.as_deref());
+
+    assert_eq!(Ok(()), keymap::del(Mode::Normal, "zx", None));
+}
+
+#[nvim::test]
+fn keymap_expr_closure() {
+    let buf = Buffer::current();
+
+    Keymap::builder(
+        Mode::Normal,
+        "zy",
+        Action::expr(|| Ok::<_, Infallible>("ifoo<Esc>".to_owned())),
+    )
+    .buffer(buf.clone())
+    .set()
+    .unwrap();
+
+    assert_eq!(0, count(Mode::Normal, "zy"));
+
+    api::command("normal zy").unwrap();
+    let lines = buf.get_lines(0..1, true).unwrap().collect::<Vec<_>>();
+    assert_eq!("foo", lines[0].to_string_lossy());
+
+    let del = keymap::del(Mode::Normal, "zy", Some(&buf));
+    assert_eq!(Ok(()), del);
+    assert_eq!(0, buf.get_keymap(Mode::Normal).unwrap().count());
+}
+
+#[nvim::test]
+fn keymap_plug() {
+    let keymap = Keymap::builder(Mode::Normal, "zw", "ibar<Esc>")
+        .plug("oxi-bar")
+        .set()
+        .unwrap();
+
+    assert_eq!(Some("<Plug>(oxi-bar)"), keymap.plug());
+
+    let infos =
+        api::get_keymap(Mode::Normal).find(|infos| infos.lhs == "zw").unwrap();
+    assert!(!infos.noremap);
+    assert_eq!(Some("<Plug>(oxi-bar)"), infos.rhs.as_deref());
+
+    api::command("normal zw").unwrap();
+    let lines =
+        Buffer::current().get_lines(0..1, true).unwrap().collect::<Vec<_>>();
+    assert_eq!("bar", lines[0].to_string_lossy());
+
+    assert_eq!(Ok(()), keymap.del());
+    assert_eq!(0, count(Mode::Normal, "zw"));
+    assert_eq!(0, count(Mode::Normal, "<Plug>(oxi-bar)"));
+}
+
+#[nvim::test]
+fn keymap_round_trip() {
+    Keymap::builder(Mode::Insert, "jk", "<Esc>")
+        .desc("Leave insert mode")
+        .silent(true)
+        .set()
+        .unwrap();
+
+    let infos =
+        api::get_keymap(Mode::Insert).find(|infos| infos.lhs == "jk").unwrap();
+
+    assert_eq!(Ok(()), keymap::del(Mode::Insert, "jk", None));
+    assert_eq!(0, count(Mode::Insert, "jk"));
+
+    KeymapBuilder::from(infos.clone()).set().unwrap();
+
+    let restored =
+        api::get_keymap(Mode::Insert).find(|infos| infos.lhs == "jk").unwrap();
+    assert_eq!(infos, restored);
+
+    assert_eq!(Ok(()), keymap::del(Mode::Insert, "jk", None));
+}
+
+#[nvim::test]
+fn keymap_round_trip_buffer_local() {
+    let mut buf = api::create_buf(false, true).unwrap();
+
+    let opts =
+        SetKeymapOpts::builder().expr(true).replace_keycodes(true).build();
+    buf.set_keymap(Mode::Normal, "zv", "'<Esc>'", &opts).unwrap();
+
+    let find = |buf: &Buffer| {
+        buf.get_keymap(Mode::Normal).unwrap().find(|infos| infos.lhs == "zv")
+    };
+
+    let infos = find(&buf).unwrap();
+    assert_eq!(Some(&buf), infos.buffer.as_ref());
+    assert!(infos.replace_keycodes);
+
+    assert_eq!(Ok(()), keymap::del(Mode::Normal, "zv", Some(&buf)));
+
+    KeymapBuilder::from(infos.clone()).set().unwrap();
+
+    assert_eq!(Some(infos), find(&buf));
+    assert_eq!(None, find(&Buffer::current()));
+}
@@ -0,0 +1,81 @@
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        lsp::{self, *},
+        opts::LspStartOpts,
+    },
+};
+use serde_json::json;
+
+struct Echo;
+
+impl LanguageServer for Echo {
+    fn initialize(&mut self, _: Value, _: &Client) -> ServerResult {
+        Ok(json!({ "capabilities": { "hoverProvider": true } }))
+    }
+
+    fn request(
+        &mut self,
+        method: &str,
+        params: Value,
+        client: &Client,
+    ) -> ServerResult {
+        match method {
+            "textDocument/hover" => {
+                client
+                    .notify("custom/hovered", params["position"].clone())
+                    .map_err(|err| {
+                        ResponseError::internal_error(err.to_string())
+                    })?;
+                Ok(json!({ "contents": "hello" }))
+            },
+            _ => Err(ResponseError::method_not_found(method)),
+        }
+    }
+}
+
+fn luaeval<R: nvim::conversion::FromObject>(expr: &str, id: u32) -> R {
+    api::call_function("luaeval", (expr, id)).unwrap()
+}
+
+#[nvim::test]
+fn lsp_start_in_process_server() {
+    api::command(
+        "lua vim.lsp.handlers['custom/hovered'] = function(_, params) \
+         vim.g.hovered_line = params.line end",
+    )
+    .unwrap();
+
+    let opts = LspStartOpts::builder().name("echo").build();
+    let id = lsp::start(Echo, &opts).unwrap().unwrap();
+
+    let contents: String = luaeval(
+        "vim.lsp.buf_request_sync(0, 'textDocument/hover', { textDocument = \
+         { uri = vim.uri_from_bufnr(0) }, position = { line = 3, character = \
+         0 }, }, 1000)[_A].result.contents",
+        id,
+    );
+    assert_eq!("hello", contents);
+
+    let notified: bool = luaeval(
+        "vim.wait(1000, function() return vim.g.hovered_line ~= nil end)",
+        id,
+    );
+    assert!(notified);
+    assert_eq!(Ok(3), api::get_var::<i64>("hovered_line"));
+
+    let code: i64 = luaeval(
+        "vim.lsp.get_client_by_id(_A).request_sync('foo/bar', {}, 1000, 0) \
+         .err.code",
+        id,
+    );
+    assert_eq!(ResponseError::METHOD_NOT_FOUND, code);
+
+    let stopped: bool = luaeval(
+        "(function() vim.lsp.stop_client(_A) return vim.wait(1000, \
+         function() return vim.lsp.get_client_by_id(_A) == nil end) end)()",
+        id,
+    );
+    assert!(stopped);
+}
@@ -1,9 +1,25 @@
 mod autocmd;
 mod buffer;
 mod command;
+mod completion;
+mod diagnostic;
 mod extmark;
+mod fold;
 mod global;
+mod job;
+mod keymap;
+#[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+mod lsp;
+mod quickfix;
+mod register;
+mod signs;
+mod statusline;
 mod tabpage;
+mod terminal;
+mod theme;
+mod treesitter;
+mod ui;
 mod vimscript;
+mod widgets;
 mod win_config;
 mod window;
@@ -0,0 +1,111 @@
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        opts::SetQfListOpts,
+        types::{QfAction, QfItem, QfItemKind, QfListId},
+        Buffer,
+        QfList,
+        Window,
+    },
+};
+
+fn items(buf: &Buffer) -> Vec<QfItem> {
+    (1..=3)
+        .map(|lnum| QfItem {
+            buffer: Some(buf.clone()),
+            lnum,
+            col: 2,
+            text: format!("item {lnum}"),
+            kind: QfItemKind::Warning,
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[nvim::test]
+fn set_get_qflist() {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["foo", "bar", "baz"]).unwrap();
+
+    let res = api::set_qflist(items(&buf), QfAction::Replace);
+    assert_eq!(Ok(()), res);
+
+    let got = api::get_qflist().unwrap();
+    assert_eq!(items(&buf), got);
+
+    let res = api::set_qflist(items(&buf), QfAction::Append);
+    assert_eq!(Ok(()), res);
+    assert_eq!(6, api::get_qflist().unwrap().len());
+}
+
+#[nvim::test]
+fn qflist_infos() {
+    let buf = Buffer::current();
+    let qf = QfList::Quickfix;
+
+    qf.set_items(items(&buf), QfAction::New).unwrap();
+
+    let opts = SetQfListOpts::builder()
+        .title("foo")
+        .context("some context")
+        .idx(2)
+        .build();
+    assert_eq!(Ok(()), qf.set(QfAction::Replace, &opts));
+
+    let infos = qf.infos(QfListId::Current).unwrap();
+    assert_eq!("foo", infos.title);
+    assert_eq!(2, infos.idx);
+    assert_eq!(3, infos.size);
+    assert_eq!(items(&buf), infos.items);
+    assert_eq!(nvim::Object::from("some context"), infos.context);
+    assert!(infos.quickfixtextfunc.is_nil());
+
+    let infos = qf.infos(QfListId::Nr(10)).unwrap();
+    assert_eq!(0, infos.id);
+}
+
+#[nvim::test]
+fn qflist_navigation() {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["foo", "bar", "baz"]).unwrap();
+
+    let qf = QfList::Quickfix;
+    qf.set_items(items(&buf), QfAction::Replace).unwrap();
+
+    let win = Window::current();
+
+    assert_eq!(Ok(()), qf.last());
+    assert_eq!(Ok((3, 1)), win.get_cursor());
+
+    assert_eq!(Ok(()), qf.previous());
+    assert_eq!(Ok((2, 1)), win.get_cursor());
+
+    assert_eq!(Ok(()), qf.go_to(1));
+    assert_eq!(Ok((1, 1)), win.get_cursor());
+
+    assert!(qf.previous().is_err());
+}
+
+#[nvim::test]
+fn set_get_loclist() {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["foo", "bar", "baz"]).unwrap();
+
+    let win = Window::current();
+
+    let res = win.set_loclist(items(&buf), QfAction::Replace);
+    assert_eq!(Ok(()), res);
+    assert_eq!(Ok(items(&buf)), win.get_loclist());
+    assert_eq!(Ok(vec![]), api::get_qflist());
+
+    let loclist = win.loclist();
+    assert_eq!(Ok(()), loclist.next());
+    assert_eq!(Ok((2, 1)), win.get_cursor());
+
+    assert_eq!(Ok(()), loclist.open());
+    let infos = loclist.infos(QfListId::Current).unwrap();
+    assert!(infos.winid.is_some());
+    assert!(infos.qfbufnr.is_some());
+    assert_eq!(Ok(()), loclist.close());
+}
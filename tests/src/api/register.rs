@@ -0,0 +1,98 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        opts::CreateAutocmdOpts,
+        types::{RegisterContents, RegisterType, TextYankPostEvent},
+        Buffer, Register, RegisterName,
+    },
+};
+
+#[nvim::test]
+fn register_set_get() {
+    let reg = Register::named('a').unwrap();
+
+    let contents =
+        RegisterContents::new(["foo", "bar"], RegisterType::Linewise);
+    assert_eq!(Ok(()), reg.set(&contents));
+    assert_eq!(Ok(contents), reg.get());
+
+    let contents = RegisterContents::new(
+        ["foo", "ba"],
+        RegisterType::BlockwiseVisual(Some(5)),
+    );
+    assert_eq!(Ok(()), reg.set(&contents));
+    let got = reg.get().unwrap();
+    assert_eq!(contents, got);
+    assert_eq!(Some(5), got.width());
+
+    let contents =
+        RegisterContents::new(["abc"], RegisterType::BlockwiseVisual(None));
+    assert_eq!(Ok(()), reg.set(&contents));
+    assert_eq!(Some(3), reg.get().unwrap().width());
+}
+
+#[nvim::test]
+fn register_append() {
+    let reg = Register::named('b').unwrap();
+
+    let contents = RegisterContents::new(["foo"], RegisterType::Charwise);
+    assert_eq!(Ok(()), reg.set(&contents));
+
+    let contents = RegisterContents::new(["bar"], RegisterType::Guess);
+    assert_eq!(Ok(()), reg.append(&contents));
+
+    let got = reg.get().unwrap();
+    assert_eq!(vec!["foobar"], got.lines);
+    assert_eq!(RegisterType::Charwise, got.kind);
+}
+
+#[nvim::test]
+fn register_from_char() {
+    assert_eq!(Register::named('a'), Register::from_char('A'));
+    assert_eq!(Register::numbered(3), Register::from_char('3'));
+    assert_eq!('9', Register::numbered(9).unwrap().as_char());
+    assert_eq!(None, Register::numbered(12));
+    assert_eq!(None, Register::from_char('!'));
+    assert_eq!('+', Register::Clipboard.as_char());
+    let contents = RegisterContents::new(["foo"], RegisterType::Charwise);
+    assert!(Register::LastCommand.set(&contents).is_err());
+}
+
+#[nvim::test]
+fn register_named_rejects_non_letters() {
+    assert_eq!(Some('q'), RegisterName::new('Q').map(RegisterName::get));
+    assert_eq!(None, Register::named('1'));
+    assert_eq!(None, Register::named('+'));
+    assert_eq!(None, Register::named('é'));
+    assert_eq!(None, RegisterName::new('_'));
+}
+
+#[nvim::test]
+fn text_yank_post_event() {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["foo", "bar"]).unwrap();
+
+    let event = Rc::new(RefCell::new(None));
+    let cloned = Rc::clone(&event);
+
+    let opts = CreateAutocmdOpts::builder()
+        .callback(move |_args| {
+            *cloned.borrow_mut() = Some(TextYankPostEvent::get()?);
+            Ok::<_, nvim::Error>(true)
+        })
+        .build();
+
+    api::create_autocmd(["TextYankPost"], &opts).unwrap();
+    api::command("normal! \"cyj").unwrap();
+
+    let event = event.borrow_mut().take().unwrap();
+    assert_eq!(Register::named('c'), Some(event.regname));
+    assert_eq!("y", event.operator);
+    assert_eq!(vec!["foo", "bar"], event.regcontents);
+    assert_eq!(RegisterType::Linewise, event.regtype);
+    assert!(!event.visual);
+}
@@ -0,0 +1,55 @@
+use nvim_oxi::{
+    self as nvim,
+    api::{signs::*, Buffer},
+};
+
+#[nvim::test]
+fn place_get_unplace_sign() {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["foo", "bar", "baz"]).unwrap();
+
+    let sign = Sign::builder()
+        .text("+")
+        .text_hl("DiffAdd")
+        .number_hl("LineNr")
+        .priority(20)
+        .build();
+
+    let id = buf.place_sign("oxi_signs", 1, &sign).unwrap();
+
+    let signs = buf.get_signs("oxi_signs").unwrap();
+    assert_eq!(vec![PlacedSign { id, line: 1, sign }], signs);
+
+    assert_eq!(Ok(()), buf.unplace_sign("oxi_signs", id));
+    assert_eq!(Ok(vec![]), buf.get_signs("oxi_signs"));
+}
+
+#[nvim::test]
+fn set_signs_replaces_group() {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["a", "b", "c", "d"]).unwrap();
+
+    let added = Sign::builder().text("+").priority(10).build();
+    let removed = Sign::builder().text("-").priority(10).build();
+
+    buf.place_sign("oxi_git", 0, &removed).unwrap();
+    buf.place_sign("oxi_other", 0, &added).unwrap();
+
+    let res = buf.set_signs("oxi_git", [(1, &added), (3, &removed)]);
+    assert_eq!(Ok(()), res);
+
+    let signs = buf
+        .get_signs("oxi_git")
+        .unwrap()
+        .into_iter()
+        .map(|placed| (placed.line, placed.sign))
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec![(1, added), (3, removed)], signs);
+
+    // Other groups are left untouched.
+    assert_eq!(1, buf.get_signs("oxi_other").unwrap().len());
+
+    assert_eq!(Ok(()), buf.clear_signs("oxi_git"));
+    assert_eq!(Ok(vec![]), buf.get_signs("oxi_git"));
+}
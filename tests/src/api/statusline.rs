@@ -0,0 +1,79 @@
+use nvim_oxi::{
+    self as nvim,
+    api::{self, opts::OptionOpts, statusline::*},
+};
+
+#[nvim::test]
+fn statusline_render() {
+    let line = Line::builder()
+        .segment(Segment::builder("foo").hl("Comment").build())
+        .align()
+        .segment("100%")
+        .build();
+
+    assert_eq!(Ok("%#Comment#foo%*%=100%%".to_owned()), line.render(80));
+
+    let infos = line.eval(10).unwrap();
+    assert_eq!("foo   100%", infos.str);
+}
+
+#[nvim::test]
+fn statusline_drop_by_priority() {
+    let line = Line::builder()
+        .segment("main.rs")
+        .segment(Segment::builder(" [Git: main]").priority(1).build())
+        .segment(Segment::builder(" [utf-8]").priority(0).build())
+        .segment(Segment::builder(" [unix]").priority(0).build())
+        .build();
+
+    let expected = "main.rs [Git: main] [utf-8]";
+    assert_eq!(Ok(expected.to_owned()), line.render(27));
+    assert_eq!(Ok("main.rs [Git: main]".to_owned()), line.render(20));
+    assert_eq!(Ok("main.rs".to_owned()), line.render(3));
+}
+
+#[nvim::test]
+fn statusline_click_handler() {
+    let line = Line::builder()
+        .segment(
+            Segment::builder("click me")
+                .on_click(|click| {
+                    api::set_var("clicks", click.clicks)?;
+                    Ok::<_, api::Error>(())
+                })
+                .build(),
+        )
+        .build();
+
+    let rendered = line.render(80).unwrap();
+    let (id, _) = rendered[1..].split_once('@').unwrap();
+
+    let expr = format!("v:lua._nvim_oxi_statusline_click({id}, 2, 'l', '')");
+    api::eval::<nvim::Object>(&expr).unwrap();
+    assert_eq!(Ok(2), api::get_var::<u32>("clicks"));
+}
+
+#[nvim::test]
+fn statusline_install() {
+    let res = install(Target::Winbar, |ctx| {
+        let width = ctx.width.to_string();
+        Ok::<_, api::Error>(Line::builder().segment(width).build())
+    });
+    assert_eq!(Ok(()), res);
+
+    let winbar =
+        api::get_option_value::<String>("winbar", &OptionOpts::default())
+            .unwrap();
+    assert_eq!("%!v:lua._nvim_oxi_winbar()", winbar);
+
+    // Installing another provider replaces the first one.
+    let res = install(Target::Winbar, |_| {
+        Ok::<_, api::Error>(Line::builder().segment("second").build())
+    });
+    assert_eq!(Ok(()), res);
+
+    let rendered =
+        api::call_function::<_, String>("luaeval", ("_nvim_oxi_winbar()",))
+            .unwrap();
+    assert!(rendered.contains("second"), "{rendered}");
+}
@@ -0,0 +1,116 @@
+use std::cell::{Cell, RefCell};
+use std::convert::Infallible;
+use std::io::Write;
+use std::rc::Rc;
+
+use nvim_oxi::{
+    self as nvim,
+    api::{self, opts::BufDeleteOpts, Terminal, TerminalSize, Window},
+};
+
+#[nvim::test]
+fn terminal_write() {
+    let mut term = Terminal::open().unwrap();
+    assert_eq!(None, term.size());
+
+    write!(term, "foo\n\x1b[1mbar\x1b[0m").unwrap();
+    term.flush().unwrap();
+
+    // The buffer is updated when the terminal is refreshed, which happens
+    // asynchronously.
+    let is_refreshed = api::call_function::<_, bool>(
+        "luaeval",
+        (
+            "vim.wait(200, function() \
+               return vim.api.nvim_buf_get_lines(_A, 1, 2, false)[1] == 'bar' \
+             end)",
+            term.buffer(),
+        ),
+    );
+    assert_eq!(Ok(true), is_refreshed);
+
+    let lines = term
+        .buffer()
+        .get_lines(0..2, false)
+        .unwrap()
+        .map(|line| line.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec!["foo", "bar"], lines);
+}
+
+#[nvim::test]
+fn terminal_resize() {
+    let term = Terminal::open().unwrap();
+
+    let resized = Rc::new(Cell::new(None));
+    let on_resize = Rc::clone(&resized);
+
+    term.on_resize(move |size| {
+        on_resize.set(Some(size));
+        Ok::<_, Infallible>(())
+    });
+
+    let mut win = Window::current();
+    win.set_buf(&term.buffer()).unwrap();
+
+    let width = win.get_width().unwrap();
+    let height = win.get_height().unwrap();
+    let expected = TerminalSize { width, height };
+
+    assert_eq!(Some(expected), term.size());
+    assert_eq!(Some(expected), resized.get());
+}
+
+#[nvim::test]
+fn terminal_close() {
+    let mut term = Terminal::open().unwrap();
+    let buffer = term.buffer();
+
+    assert_eq!(Ok(()), term.close());
+    assert!(term.is_closed());
+    assert!(!buffer.is_valid());
+    assert!(write!(term, "foo").is_err());
+
+    // Closing it again is a no-op.
+    assert_eq!(Ok(()), term.close());
+}
+
+#[nvim::test]
+fn terminal_buffer_wiped() {
+    let term = Terminal::open().unwrap();
+
+    let opts = BufDeleteOpts::builder().force(true).build();
+    term.buffer().delete(&opts).unwrap();
+
+    assert!(term.is_closed());
+    assert_eq!(Ok(()), term.close());
+}
+
+#[nvim::test]
+fn terminal_input() {
+    let term = Terminal::open().unwrap();
+    let receiver = term.input_receiver();
+
+    let typed = Rc::new(RefCell::new(Vec::new()));
+    let on_input = Rc::clone(&typed);
+
+    term.on_input(move |data| {
+        on_input.borrow_mut().extend_from_slice(data);
+        Ok::<_, Infallible>(())
+    });
+
+    Window::current().set_buf(&term.buffer()).unwrap();
+
+    // Enter Terminal mode, type `foo`, `<CR>` and `<C-J>`, then go back to
+    // Normal mode with `<C-\><C-N>`.
+    api::call_function::<_, i64>("feedkeys", ("ifoo\r\n\x1c\x0e", "x"))
+        .unwrap();
+
+    // The input is delivered as typed, without translating line endings.
+    assert_eq!(b"foo\r\n", &typed.borrow()[..]);
+    assert_eq!(
+        b"foo\r\n",
+        &receiver.try_iter().flatten().collect::<Vec<_>>()[..]
+    );
+}
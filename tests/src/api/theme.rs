@@ -0,0 +1,85 @@
+use nvim_oxi::{
+    self as nvim,
+    api::{self, theme::*},
+};
+
+fn hl_attr(group: &str, attr: &str) -> String {
+    let id = api::call_function::<_, i64>("hlID", (group,)).unwrap();
+    api::call_function("synIDattr", (id, attr)).unwrap()
+}
+
+#[nvim::test]
+fn theme_load() {
+    let theme = Theme::builder("oxi")
+        .background(Background::Dark)
+        .color("red", "#ff0000")
+        .group("Normal", Highlight::builder().fg("red").bold().build())
+        .link("OxiLinked", "Normal")
+        .build();
+
+    assert_eq!(Ok(()), theme.load());
+
+    assert_eq!(Ok("oxi".to_owned()), api::get_var::<String>("colors_name"));
+    assert_eq!("#ff0000", hl_attr("Normal", "fg#"));
+    assert_eq!("1", hl_attr("Normal", "bold"));
+    assert_eq!("#ff0000", hl_attr("OxiLinked", "fg#"));
+}
+
+#[nvim::test]
+fn theme_load_invalid_color() {
+    let theme = Theme::builder("broken")
+        .group("Normal", Highlight::builder().fg("not-a-color").build())
+        .build();
+
+    assert!(theme.load().is_err());
+    assert!(api::get_var::<String>("colors_name").is_err());
+}
+
+/// Returns whether `group` is defined in the namespace `ns_id`.
+// `nvim_get_hl()` was added in Neovim 0.9.
+#[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+fn is_ns_group_defined(ns_id: u32, group: &str) -> bool {
+    use nvim::{Array, Object};
+
+    let expr = "not vim.tbl_isempty(\
+                vim.api.nvim_get_hl(_A[1], { name = _A[2] }))";
+    let args = Array::from_iter([Object::from(ns_id), Object::from(group)]);
+    api::call_function("luaeval", (expr, args)).unwrap()
+}
+
+#[cfg(any(feature = "neovim-0-9", feature = "neovim-nightly"))]
+#[nvim::test]
+fn theme_load_ns_groups() {
+    let theme = Theme::builder("oxi")
+        .ns_group("oxi_float", "Normal", Highlight::builder().bold().build())
+        .ns_group("oxi_float", "OxiDropped", Highlight::link("Normal"))
+        .build();
+
+    assert_eq!(None, theme.ns_id("oxi_missing"));
+    let ns_id = theme.ns_id("oxi_float").unwrap();
+    assert_eq!(api::create_namespace("oxi_float"), ns_id);
+
+    assert_eq!(Ok(()), theme.load());
+    assert!(is_ns_group_defined(ns_id, "Normal"));
+    assert!(is_ns_group_defined(ns_id, "OxiDropped"));
+
+    let theme = Theme::builder("oxi")
+        .ns_group("oxi_float", "Normal", Highlight::builder().bold().build())
+        .build();
+
+    assert_eq!(Ok(()), theme.load());
+    assert!(is_ns_group_defined(ns_id, "Normal"));
+    assert!(!is_ns_group_defined(ns_id, "OxiDropped"));
+}
+
+#[cfg(feature = "neovim-nightly")]
+#[nvim::test]
+fn theme_activate_ns() {
+    let theme = Theme::builder("oxi")
+        .ns_group("oxi_active", "Normal", Highlight::builder().bold().build())
+        .build();
+
+    assert_eq!(Ok(()), theme.load());
+    assert_eq!(Ok(()), theme.activate_ns("oxi_active"));
+    assert!(theme.activate_ns("oxi_missing").is_err());
+}
@@ -0,0 +1,137 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        treesitter::{self, Query},
+        types::{Position, Range},
+        Buffer,
+    },
+};
+
+fn lua_buffer(lines: &[&str]) -> Buffer {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, lines.iter().copied()).unwrap();
+    buf
+}
+
+#[nvim::test]
+fn treesitter_nodes() {
+    let buf = lua_buffer(&["local x = 1", "local y = 2"]);
+
+    let parser = treesitter::get_parser(&buf, Some("lua")).unwrap();
+    assert_eq!(Ok("lua".to_owned()), parser.lang());
+
+    let trees = parser.parse().unwrap();
+    assert_eq!(1, trees.len());
+
+    let root = trees[0].root().unwrap();
+    assert_eq!(Ok("chunk".to_owned()), root.kind());
+    assert_eq!(Ok(None), root.parent().map(|p| p.map(|_| ())));
+    assert!(!root.has_error().unwrap());
+
+    let statements =
+        root.named_children().unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(2, statements.len());
+
+    let second = &statements[1];
+    assert_eq!(
+        Ok(Range::new(Position::new(1, 0), Position::new(1, 11))),
+        second.range()
+    );
+    assert_eq!(Ok(12..23), second.byte_range());
+    assert_eq!(Ok("local y = 2".to_owned()), second.text(&buf));
+
+    let pos = Position::new(0, 6);
+    let ident =
+        root.named_descendant_for_range(Range::empty(pos)).unwrap().unwrap();
+    assert_eq!(Ok("identifier".to_owned()), ident.kind());
+    assert_eq!(Ok("x".to_owned()), ident.text(&buf));
+}
+
+#[nvim::test]
+fn treesitter_children_error() {
+    let buf = lua_buffer(&["local x = 1", "local y = 2"]);
+
+    let root = treesitter::get_parser(&buf, Some("lua"))
+        .and_then(|parser| parser.parse())
+        .and_then(|trees| trees[0].root())
+        .unwrap();
+
+    // The methods of the nodes are looked up in their shared metatable.
+    api::command(
+        "lua local parser = vim.treesitter.get_parser(0, 'lua') \
+         getmetatable(parser:parse()[1]:root()).named_child = \
+           function() error('oops') end",
+    )
+    .unwrap();
+
+    let mut children = root.named_children().unwrap();
+    let err = children.next().unwrap().unwrap_err().to_string();
+    assert!(err.contains("oops"), "{err}");
+    assert!(children.next().is_none());
+}
+
+#[nvim::test]
+fn treesitter_query() {
+    let buf = lua_buffer(&["local foo = 1", "local bar = 2", "local baz = 3"]);
+
+    let root = treesitter::get_parser(&buf, Some("lua"))
+        .and_then(|parser| parser.parse())
+        .and_then(|trees| trees[0].root())
+        .unwrap();
+
+    let query =
+        Query::parse("lua", r#"((identifier) @name (#not-eq? @name "bar"))"#)
+            .unwrap();
+    assert_eq!(["name".to_owned()], query.captures());
+
+    let names = query
+        .iter_captures(&root, &buf, ..)
+        .unwrap()
+        .map(|capture| {
+            let capture = capture.unwrap();
+            assert_eq!("name", capture.name);
+            capture.node.text(&buf).unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["foo", "baz"], names);
+
+    let matches = query
+        .iter_matches(&root, &buf, 1..)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(1, matches.len());
+    assert_eq!(1, matches[0].pattern);
+    assert_eq!(1, matches[0].captures.len());
+    assert_eq!(Ok("baz".to_owned()), matches[0].captures[0].node.text(&buf));
+
+    assert!(Query::parse("lua", "(not_a_node) @foo").is_err());
+}
+
+#[nvim::test]
+fn treesitter_on_changedtree() {
+    let mut buf = lua_buffer(&["local x = 1"]);
+
+    let parser = treesitter::get_parser(&buf, Some("lua")).unwrap();
+    parser.parse().unwrap();
+
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let cloned = Rc::clone(&changes);
+
+    parser
+        .on_changedtree(move |ranges, tree| {
+            assert_eq!(Ok("chunk".to_owned()), tree.root()?.kind());
+            cloned.borrow_mut().extend(ranges);
+            Ok(())
+        })
+        .unwrap();
+
+    buf.set_lines(1..1, true, ["local y = 2"]).unwrap();
+    parser.parse().unwrap();
+
+    assert!(!changes.borrow().is_empty());
+}
@@ -0,0 +1,152 @@
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        opts::{InputOpts, SelectOpts},
+        ui::{self, InputRequest, SelectRequest},
+    },
+};
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Polls a future which is expected to be ready.
+fn poll_ready<F: Future + Unpin>(mut future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+
+    match Pin::new(&mut future).poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("the future is still pending"),
+    }
+}
+
+#[nvim::test]
+fn ui_select() {
+    let requests = Rc::new(RefCell::new(Vec::new()));
+    let cloned = Rc::clone(&requests);
+
+    ui::set_select(move |request: SelectRequest| {
+        cloned.borrow_mut().push((
+            request.items.clone(),
+            request.prompt.clone(),
+            request.kind.clone(),
+        ));
+        request.choose(Some(1))
+    })
+    .unwrap();
+
+    let opts = SelectOpts::builder()
+        .prompt("Pick one")
+        .kind("test")
+        .format_item(|n: &u8| format!("#{n}"))
+        .build();
+
+    let choice = Rc::new(RefCell::new(None));
+    let cloned = Rc::clone(&choice);
+
+    let res = ui::select(vec![10u8, 20, 30], &opts, move |choice| {
+        *cloned.borrow_mut() = Some(choice);
+        Ok::<_, Infallible>(())
+    });
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(Some(Some((1, 20))), choice.borrow_mut().take());
+
+    let (items, prompt, kind) = requests.borrow_mut().remove(0);
+    assert_eq!(vec!["#10", "#20", "#30"], items);
+    assert_eq!(Some("Pick one"), prompt.as_deref());
+    assert_eq!(Some("test"), kind.as_deref());
+
+    let response = ui::select_async(vec!["a", "b"], &SelectOpts::default());
+    assert_eq!(Some((1, "b")), poll_ready(response.unwrap()));
+
+    let (items, _, _) = requests.borrow_mut().remove(0);
+    assert_eq!(vec!["a", "b"], items);
+}
+
+#[nvim::test]
+fn ui_select_not_display() {
+    #[derive(Debug, PartialEq)]
+    struct Item(u8);
+
+    ui::set_select(|request: SelectRequest| {
+        assert_eq!(vec!["item 1", "item 2"], request.items);
+        request.choose(Some(0))
+    })
+    .unwrap();
+
+    let opts = SelectOpts::builder_with(|item: &Item| {
+        format!("item {}", item.0)
+    })
+    .build();
+
+    let response = ui::select_async(vec![Item(1), Item(2)], &opts);
+    assert_eq!(Some((0, Item(1))), poll_ready(response.unwrap()));
+}
+
+#[nvim::test]
+fn ui_select_cancelled() {
+    ui::set_select(|request: SelectRequest| request.choose(None)).unwrap();
+
+    let response = ui::select_async(vec![1, 2, 3], &SelectOpts::default());
+    assert_eq!(None, poll_ready(response.unwrap()));
+}
+
+#[nvim::test]
+fn ui_input() {
+    ui::set_input(|request: InputRequest| {
+        let input = format!(
+            "{}{}",
+            request.prompt.as_deref().unwrap_or_default(),
+            request.default.as_deref().unwrap_or_default(),
+        );
+        request.confirm(Some(input))
+    })
+    .unwrap();
+
+    let opts = InputOpts::builder()
+        .prompt("Name: ")
+        .default("foo")
+        .completion("file")
+        .build();
+
+    let input = Rc::new(RefCell::new(None));
+    let cloned = Rc::clone(&input);
+
+    let res = ui::input(&opts, move |input| {
+        *cloned.borrow_mut() = input;
+        Ok::<_, Infallible>(())
+    });
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(Some("Name: foo"), input.borrow().as_deref());
+
+    ui::set_input(|request: InputRequest| request.confirm(None)).unwrap();
+
+    let response = ui::input_async(&InputOpts::default());
+    assert_eq!(None, poll_ready(response.unwrap()));
+}
+
+#[nvim::test]
+fn ui_select_non_string_error() {
+    api::command("lua vim.ui.select = function() error({}) end").unwrap();
+
+    let res = ui::select(vec![1u8], &SelectOpts::default(), |_| {
+        Ok::<_, Infallible>(())
+    });
+
+    let err = res.unwrap_err().to_string();
+    assert!(err.contains("error object is a table value"), "{err}");
+}
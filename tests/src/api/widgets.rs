@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use nvim_oxi::{
+    self as nvim,
+    api::{
+        self,
+        opts::{InputOpts, OptionOpts, OptionScope, SelectOpts},
+        widgets::*,
+        Window,
+    },
+};
+
+#[nvim::test]
+fn popup_open_close() {
+    let opts = PopupOpts::builder().width(20).height(0.5).build();
+    let popup = Popup::open(&opts).unwrap();
+
+    let buffer = popup.buffer();
+    let window = popup.window();
+    assert_eq!(window, Window::current());
+    assert_eq!(Ok(20), window.get_width());
+
+    assert_eq!(Ok(()), popup.set_lines(["foo", "bar"]));
+    assert_eq!(Ok(2), buffer.line_count());
+
+    assert_eq!(Ok(()), popup.resize(30, 3));
+    assert_eq!(Ok(30), window.get_width());
+    assert_eq!(Ok(3), window.get_height());
+
+    let closed = Rc::new(RefCell::new(false));
+    let cloned = Rc::clone(&closed);
+    popup.on_close(move || {
+        *cloned.borrow_mut() = true;
+        Ok::<_, Infallible>(())
+    });
+
+    assert_eq!(Ok(()), popup.close());
+    assert!(popup.is_closed());
+    assert!(*closed.borrow());
+    assert!(!window.is_valid());
+    assert!(!buffer.is_valid());
+
+    // Closing twice is a no-op.
+    assert_eq!(Ok(()), popup.close());
+}
+
+#[nvim::test]
+fn popup_clamped_to_editor() {
+    let opts = PopupOpts::builder()
+        .width(10_000)
+        .height(1)
+        .position(PopupPosition::Editor { row: 0, col: 10_000 })
+        .build();
+
+    let popup = Popup::open(&opts).unwrap();
+    let window = popup.window();
+
+    let columns = api::get_option_value::<u32>("columns", &Default::default())
+        .unwrap();
+
+    // The rounded border takes one column on each side.
+    assert_eq!(Ok(columns - 2), window.get_width());
+    assert_eq!(Ok((0, 0)), window.get_position());
+
+    popup.close().unwrap();
+}
+
+#[nvim::test]
+fn list_picker() {
+    let choice = Rc::new(RefCell::new(None));
+    let cloned = Rc::clone(&choice);
+
+    let opts = SelectOpts::builder().prompt("Pick").build();
+
+    let picker = ListPicker::open(vec!["foo", "bar"], &opts, move |choice| {
+        *cloned.borrow_mut() = Some(choice);
+        Ok::<_, Infallible>(())
+    })
+    .unwrap();
+
+    let lines = picker
+        .popup()
+        .buffer()
+        .get_lines(.., true)
+        .unwrap()
+        .map(|line| line.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["foo", "bar"], lines);
+
+    // The cursorline is only enabled in the picker's window.
+    let global = OptionOpts::builder().scope(OptionScope::Global).build();
+    assert_eq!(
+        Ok(false),
+        api::get_option_value::<bool>("cursorline", &global)
+    );
+
+    picker.popup().window().set_cursor(2, 0).unwrap();
+    api::command("execute \"normal \\<CR>\"").unwrap();
+
+    assert_eq!(Some(Some((1, "bar"))), choice.borrow_mut().take());
+    assert!(picker.popup().is_closed());
+}
+
+#[nvim::test]
+fn text_prompt_cancelled() {
+    let input = Rc::new(RefCell::new(None));
+    let cloned = Rc::clone(&input);
+
+    let opts = InputOpts::builder().prompt("Name").default("foo").build();
+
+    let prompt = TextPrompt::open(&opts, move |input| {
+        *cloned.borrow_mut() = Some(input);
+        Ok::<_, Infallible>(())
+    })
+    .unwrap();
+
+    assert_eq!(Ok("foo".into()), api::get_current_line());
+
+    assert_eq!(Ok(()), prompt.close());
+    assert_eq!(Some(None), input.borrow_mut().take());
+}
@@ -102,6 +102,18 @@ fn set_get_cursor() {
     assert_eq!(Ok((1, 0)), win.get_cursor());
 }
 
+#[oxi::test]
+fn set_get_cursor_position() {
+    let mut buf = Buffer::current();
+    buf.set_lines(.., true, ["foo", "bar"]).unwrap();
+
+    let mut win = Window::current();
+
+    assert_eq!(Ok(()), win.set_cursor_position(Position::new(1, 2)));
+    assert_eq!(Ok((2, 2)), win.get_cursor());
+    assert_eq!(Ok(Position::new(1, 2)), win.get_cursor_position());
+}
+
 #[oxi::test]
 fn win_set_get_option() {
     let mut win = Window::current();
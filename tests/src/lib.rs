@@ -3,6 +3,14 @@
 
 mod api;
 
+// The logging backends send records through the libuv bindings.
+#[cfg(not(any(target_os = "windows", target_env = "msvc")))]
+mod logging;
+
 // Libuv bindings don't work on Windows.
 #[cfg(not(any(target_os = "windows", target_env = "msvc")))]
 mod libuv;
+
+// The tokio integration is built on top of the libuv bindings.
+#[cfg(not(any(target_os = "windows", target_env = "msvc")))]
+mod tokio;
@@ -0,0 +1,45 @@
+use std::convert::Infallible;
+use std::thread;
+
+use nvim_oxi::{self as nvim, libuv, TestFailure};
+
+#[nvim::test]
+fn channel_receives_all_messages(terminator: nvim::TestTerminator) {
+    let mut received = Vec::new();
+
+    let mut terminator = Some(terminator);
+
+    let sender = libuv::channel(4, move |n: usize| {
+        received.push(n);
+
+        if received.len() == 100 {
+            let res = if received.iter().copied().eq(0..100) {
+                Ok(())
+            } else {
+                Err(TestFailure::Error("messages received out of order"))
+            };
+            terminator.take().unwrap().terminate(res);
+        }
+
+        Ok::<_, Infallible>(())
+    })
+    .unwrap();
+
+    assert_eq!(sender.capacity(), 4);
+
+    thread::spawn(move || {
+        for n in 0..100 {
+            sender.send(n).unwrap();
+        }
+    });
+}
+
+#[nvim::test]
+fn channel_try_send_full() {
+    let sender = libuv::channel(1, |()| Ok::<_, Infallible>(())).unwrap();
+
+    assert!(sender.try_send(()).is_ok());
+
+    // The callback can't run until we return control to the event loop.
+    assert!(matches!(sender.try_send(()), Err(libuv::TrySendError::Full(()))));
+}
@@ -1,2 +1,3 @@
 mod async_handle;
+mod channel;
 mod timer_handle;
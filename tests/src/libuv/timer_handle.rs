@@ -1,9 +1,11 @@
+use std::cell::{Cell, RefCell};
 use std::convert::Infallible;
+use std::rc::Rc;
 use std::sync::mpsc;
 use std::thread::sleep;
 use std::time::Duration;
 
-use nvim_oxi::{self as nvim, libuv::*};
+use nvim_oxi::{self as nvim, libuv::*, TestFailure};
 
 #[nvim::test]
 fn timer_handle_0() {
@@ -41,3 +43,137 @@ fn timer_handle_0() {
 
     assert_eq!(rx.try_recv().unwrap_err(), mpsc::TryRecvError::Empty);
 }
+
+#[nvim::test]
+fn timer_handle_error_handler(terminator: nvim::TestTerminator) {
+    let mut terminator = Some(terminator);
+
+    set_error_handler(move |kind, err| {
+        let res = if kind != HandleKind::Timer {
+            Err(TestFailure::Error(format!("wrong handle kind: {kind}")))
+        } else if err.to_string() != "oops" {
+            Err(TestFailure::Error(format!("wrong error: {err}")))
+        } else {
+            Ok(())
+        };
+
+        if let Some(terminator) = terminator.take() {
+            terminator.terminate(res);
+        }
+
+        ErrorAction::Stop
+    });
+
+    let _handle = TimerHandle::start(
+        Duration::from_millis(10),
+        Duration::from_millis(10),
+        |_| Err(nvim::api::Error::Other("oops".into())),
+    )
+    .unwrap();
+}
+
+#[nvim::test]
+fn timer_handle_repeat() {
+    let mut handle = TimerHandle::start(
+        Duration::from_secs(10),
+        Duration::from_millis(100),
+        |_| Ok::<_, Infallible>(()),
+    )
+    .unwrap();
+
+    assert_eq!(handle.get_repeat(), Duration::from_millis(100));
+    assert!(handle.get_due_in() > Duration::from_secs(5));
+
+    handle.set_repeat(Duration::from_millis(200));
+    assert_eq!(handle.get_repeat(), Duration::from_millis(200));
+
+    handle.again().unwrap();
+    assert!(handle.get_due_in() <= Duration::from_millis(200));
+
+    handle.restart(Duration::from_secs(10)).unwrap();
+    assert!(handle.get_due_in() > Duration::from_secs(5));
+
+    handle.stop().unwrap();
+    assert_eq!(handle.get_due_in(), Duration::ZERO);
+
+    handle.close();
+}
+
+#[nvim::test]
+fn debouncer(terminator: nvim::TestTerminator) {
+    let num_called = Rc::new(Cell::new(0));
+
+    let mut debouncer = {
+        let num_called = num_called.clone();
+        Debouncer::new(Duration::from_millis(10), move || {
+            num_called.set(num_called.get() + 1);
+            Ok::<_, Infallible>(())
+        })
+        .unwrap()
+    };
+
+    for _ in 0..5 {
+        debouncer.call().unwrap();
+    }
+
+    assert!(debouncer.is_pending());
+
+    let _check = TimerHandle::once(Duration::from_millis(100), move || {
+        let res = match num_called.get() {
+            1 => Ok(()),
+            n => Err(TestFailure::Error(format!("called {n} times"))),
+        };
+        drop(debouncer);
+        terminator.terminate(res);
+        Ok::<_, Infallible>(())
+    })
+    .unwrap();
+}
+
+#[nvim::test]
+fn throttler(terminator: nvim::TestTerminator) {
+    let num_called = Rc::new(Cell::new(0));
+
+    let throttler = {
+        let num_called = num_called.clone();
+        Throttler::new(Duration::from_millis(50), move || {
+            num_called.set(num_called.get() + 1);
+            Ok::<_, Infallible>(())
+        })
+        .unwrap()
+    };
+
+    let throttler = Rc::new(RefCell::new(throttler));
+
+    // These are coalesced into a single execution on the next iteration of
+    // the event loop.
+    for _ in 0..5 {
+        throttler.borrow_mut().call().unwrap();
+    }
+
+    assert!(throttler.borrow().is_pending());
+
+    // These are made while cooling down, so they're executed once at the end
+    // of the first interval.
+    let _cooling_down = {
+        let throttler = throttler.clone();
+        TimerHandle::once(Duration::from_millis(10), move || {
+            for _ in 0..5 {
+                throttler.borrow_mut().call().unwrap();
+            }
+            Ok::<_, Infallible>(())
+        })
+        .unwrap()
+    };
+
+    let _check = TimerHandle::once(Duration::from_millis(200), move || {
+        let res = match num_called.get() {
+            2 => Ok(()),
+            n => Err(TestFailure::Error(format!("called {n} times"))),
+        };
+        drop(throttler);
+        terminator.terminate(res);
+        Ok::<_, Infallible>(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,41 @@
+use std::fs;
+use std::path::Path;
+
+use nvim_oxi::{
+    self as nvim,
+    api::{self, types::LogLevel},
+    logging::{FileTarget, Logger, Target},
+};
+use tracing_subscriber::layer::SubscriberExt;
+
+#[nvim::test]
+fn tracing_level_raised_at_runtime() {
+    let file_name = format!("nvim-oxi-tracing-{}.log", std::process::id());
+
+    let logger = Logger::builder()
+        .target(Target::File(FileTarget::new(&file_name)))
+        .level(LogLevel::Warn)
+        .build()
+        .unwrap();
+
+    let level = logger.level();
+    let subscriber = tracing_subscriber::registry().with(logger.clone());
+
+    tracing::subscriber::with_default(subscriber, || {
+        // The same callsite is hit before and after raising the level.
+        for i in 0..2 {
+            tracing::info!("record {i}");
+            level.set(LogLevel::Info);
+        }
+    });
+
+    logger.flush();
+
+    let dir = api::call_function::<_, String>("stdpath", ("log",)).unwrap();
+    let path = Path::new(&dir).join(&file_name);
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    assert!(!contents.contains("record 0"), "{contents}");
+    assert!(contents.contains("record 1"), "{contents}");
+}
@@ -0,0 +1,47 @@
+use std::thread;
+
+use nvim_oxi::{self as nvim, api, TestFailure};
+
+#[nvim::test]
+fn tokio_run_on_main(terminator: nvim::TestTerminator) {
+    nvim::tokio::spawn(async move {
+        let worker = thread::current().id();
+
+        let (main, buf) = nvim::tokio::run_on_main(async {
+            let buf = api::get_current_buf();
+            (thread::current().id(), buf)
+        })
+        .await;
+
+        let res = if main == worker {
+            Err(TestFailure::Error("task wasn't run on the Neovim thread"))
+        } else if buf != api::Buffer::from(1) {
+            Err(TestFailure::Error("wrong current buffer"))
+        } else {
+            Ok(())
+        };
+
+        terminator.terminate(res);
+    });
+}
+
+#[nvim::test]
+fn tokio_spawn_on_main_from_main(terminator: nvim::TestTerminator) {
+    let handle = nvim::tokio::spawn_on_main(async { 42 });
+
+    nvim::tokio::spawn(async move {
+        let res = match handle.await {
+            Ok(42) => Ok(()),
+            Ok(n) => Err(TestFailure::Error(format!("got {n}"))),
+            Err(err) => Err(TestFailure::Error(err.to_string())),
+        };
+        terminator.terminate(res);
+    });
+}
+
+#[nvim::test]
+fn tokio_shutdown() {
+    nvim::tokio::shutdown();
+    // Shutting down twice is a no-op.
+    nvim::tokio::shutdown();
+}